@@ -0,0 +1,89 @@
+//! Compares the throughput of cache hits between the std hasher and a fast
+//! FxHash style hasher, run it via `cargo bench`.
+//!
+
+use daab::rc::CanType;
+use daab::rc::Blueprint as Bp;
+use daab::rc::CacheOwned;
+use daab::rc::Resolver;
+use daab::Builder;
+use daab::Never;
+use daab::prelude::*;
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasherDefault;
+use std::hash::Hasher;
+use std::rc::Rc;
+use std::time::Instant;
+
+/// A fast, but not HashDoS resistant, hasher in the style of FxHash.
+///
+#[derive(Debug, Default, Clone, Copy)]
+struct FastHasher(u64);
+
+impl Hasher for FastHasher {
+	fn write(&mut self, bytes: &[u8]) {
+		for &b in bytes {
+			self.write_u64(u64::from(b));
+		}
+	}
+	fn write_u64(&mut self, i: u64) {
+		self.0 = (self.0.rotate_left(5) ^ i).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+	}
+	fn write_usize(&mut self, i: usize) {
+		self.write_u64(i as u64);
+	}
+	fn finish(&self) -> u64 {
+		self.0
+	}
+}
+
+type FastState = BuildHasherDefault<FastHasher>;
+
+#[derive(Debug)]
+struct NumberBuilder(u64);
+
+impl Builder<CanType, CanType> for NumberBuilder {
+	type Artifact = u64;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, _resolver: &mut Resolver) -> Result<Rc<Self::Artifact>, Never> {
+		Ok(Rc::new(self.0))
+	}
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+fn measure(name: &str, mut cache: CacheOwned, blueprints: &[Bp<NumberBuilder>]) {
+
+	// Build all artifacts once, thus only cache hits are measured
+	for bp in blueprints {
+		cache.get(bp).unpack();
+	}
+
+	let rounds = 100;
+	let start = Instant::now();
+	let mut sum = 0;
+	for _ in 0..rounds {
+		for bp in blueprints {
+			sum += *cache.get(bp).unpack();
+		}
+	}
+	let elapsed = start.elapsed();
+
+	let gets = rounds * blueprints.len();
+	println!("{}: {} gets in {:?} ({:.1} ns/get, checksum {})",
+		name, gets, elapsed, elapsed.as_nanos() as f64 / gets as f64, sum);
+}
+
+fn main() {
+	let blueprints: Vec<_> = (0..10_000)
+		.map(|i| Bp::new(NumberBuilder(i)))
+		.collect();
+
+	measure("default", CacheOwned::new(), &blueprints);
+	measure("std", CacheOwned::with_hasher(RandomState::new()), &blueprints);
+	measure("fast", CacheOwned::with_hasher(FastState::default()), &blueprints);
+}
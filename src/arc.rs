@@ -6,6 +6,8 @@
 
 use std::fmt::Debug;
 use std::any::Any;
+use std::time::Duration;
+use std::time::Instant;
 
 use cfg_if::cfg_if;
 
@@ -13,6 +15,9 @@ use cfg_if::cfg_if;
 use crate::Doctor;
 
 use crate::BlueprintDyn;
+use crate::cache::PartialResult;
+use crate::BuilderId;
+use crate::cache::DeclaredDependency;
 use crate::Never;
 
 
@@ -42,6 +47,10 @@ pub type BuilderCan = CanType;
 ///
 pub type Blueprint<B> = crate::Blueprint<B, CanType>;
 
+/// A weak reference to a `Blueprint`, see `Blueprint::new_with_self`.
+///
+pub type WeakPromise<B> = crate::blueprint::WeakPromise<B, CanType>;
+
 cfg_if! {
 	if #[cfg(feature = "unsized")] {
 		/// The unsized variant of `Blueprint`.
@@ -97,6 +106,58 @@ cfg_if::cfg_if!{
 ///
 pub type CacheOwned = crate::CacheOwned<CanType, CanType>;
 
+impl Cache {
+	/// Builds as much of the Artifact as possible within `deadline`.
+	///
+	/// The dependencies declared via [`Builder::declare_dependencies`] are
+	/// built bottom-up in this `Cache`, just like [`get_iterative`] does,
+	/// until the deadline has passed. Finally, if all declared dependencies
+	/// are available and the deadline has not yet passed, the given Builder
+	/// itself is built.
+	///
+	/// The returned [`PartialResult`] contains the Artifact, if it got built,
+	/// as well as the ids of the dependencies built during this call and of
+	/// those still pending. The built Artifacts are kept in this `Cache`, thus
+	/// calling this method again continues where the last call stopped, which
+	/// suits interactive tools rendering intermediate results.
+	///
+	/// Notice, builds are not interrupted. The deadline is only checked between builds, thus a
+	/// single slow build, including its undeclared dependencies, may exceed
+	/// it.
+	///
+	/// [`Builder::declare_dependencies`]: ../trait.Builder.html#method.declare_dependencies
+	/// [`get_iterative`]: ../cache/struct.Cache.html#method.get_iterative
+	/// [`PartialResult`]: ../cache/struct.PartialResult.html
+	///
+	pub fn get_partial<B>(
+			&mut self,
+			promise: &Blueprint<B>,
+			deadline: Duration,
+		) -> PartialResult<BinType<B::Artifact>, B::Err>
+			where
+				B: crate::Builder<CanType, CanType> + Send + Sync,
+				B::Artifact: Send + Sync {
+
+		let end = Instant::now() + deadline;
+
+		let (built, pending) = self.build_declared_dependencies(promise, || {
+			Instant::now() < end
+		});
+
+		let artifact = if pending.is_empty() && Instant::now() < end {
+			Some(self.get(promise))
+		} else {
+			None
+		};
+
+		PartialResult {
+			artifact,
+			built,
+			pending,
+		}
+	}
+}
+
 
 /*
 /// Allows to resolve any `Blueprint` into its artifact-builder.
@@ -179,6 +240,51 @@ pub trait Builder: Debug + 'static {
 	/// Return an inital dynamic state for this builder.
 	///
 	fn init_dyn_state(&self) -> Self::DynState;
+
+	/// Returns a copy of the given error, if it shall be cached.
+	///
+	/// See the `cache_error` method of the general `Builder` trait.
+	///
+	fn cache_error(&self, _err: &Self::Err) -> Option<Self::Err> {
+		None
+	}
+
+	/// Returns a hook to be called with the Artifact when it is evicted.
+	///
+	/// See the `evict_hook` method of the general `Builder` trait.
+	///
+	fn evict_hook(&self) -> Option<fn(&CanType)> {
+		None
+	}
+
+	/// Declares the dependencies which this Builder refers to weakly.
+	///
+	/// See the `weak_dependencies` method of the general `Builder` trait.
+	///
+	fn weak_dependencies(&self, _declare: &mut dyn FnMut(BuilderId)) {
+		// none
+	}
+
+	/// Returns the approximate memory size of the given Artifact in bytes.
+	///
+	/// See the `artifact_size_hint` method of the general `Builder` trait.
+	///
+	/// **Notice: This method is only available if the `lru` feature
+	/// has been activated**.
+	///
+	#[cfg(feature = "lru")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "lru")))]
+	fn artifact_size_hint(&self, _artifact: &Self::Artifact) -> usize {
+		0
+	}
+
+	/// Returns the dependencies of this Builder, declared up front.
+	///
+	/// See the `declare_dependencies` method of the general `Builder` trait.
+	///
+	fn declare_dependencies(&self) -> Vec<Box<dyn DeclaredDependency<CanType, CanType>>> {
+		Vec::new()
+	}
 }
 
 impl<B: ?Sized + Builder> crate::Builder<CanType, CanType> for B {
@@ -195,6 +301,27 @@ impl<B: ?Sized + Builder> crate::Builder<CanType, CanType> for B {
 	fn init_dyn_state(&self) -> Self::DynState {
 		self.init_dyn_state()
 	}
+
+	fn cache_error(&self, err: &Self::Err) -> Option<Self::Err> {
+		self.cache_error(err)
+	}
+
+	fn evict_hook(&self) -> Option<fn(&CanType)> {
+		self.evict_hook()
+	}
+
+	fn weak_dependencies(&self, declare: &mut dyn FnMut(BuilderId)) {
+		self.weak_dependencies(declare)
+	}
+
+	#[cfg(feature = "lru")]
+	fn artifact_size_hint(&self, artifact: &Self::Artifact) -> usize {
+		self.artifact_size_hint(artifact)
+	}
+
+	fn declare_dependencies(&self) -> Vec<Box<dyn DeclaredDependency<CanType, CanType>>> {
+		self.declare_dependencies()
+	}
 }
 
 /*
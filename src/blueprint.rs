@@ -58,10 +58,16 @@ use cfg_if::cfg_if;
 
 use crate::Builder;
 use crate::BuilderId;
+use crate::Cache;
 use crate::Can;
 use crate::CanBuilder;
+use crate::CanRef;
 use crate::CanSized;
+use crate::CanStrong;
+use crate::canning::CanCyclic;
+use crate::canning::CanDowngrade;
 use crate::Never;
+use crate::utils::LazyBuilder;
 
 
 
@@ -103,6 +109,20 @@ pub trait Promise: Debug + 'static {
 	/// accessor is required for this library to work.
 	///
 	fn canned(&self) -> CannedAccessor<Self::BCan>;
+
+	/// Get a function formatting the inner builder via `Debug`, given the
+	/// can of the inner builder.
+	///
+	/// This allows to defer formatting the builder until its text is
+	/// actually needed. The default implementation returns `None`, in which
+	/// case the builder is formatted right away.
+	///
+	/// Notice: this function is only used internally.
+	///
+	#[cfg(feature = "diagnostics")]
+	fn debug_fn(&self) -> Option<fn(Self::BCan) -> Option<String>> {
+		None
+	}
 }
 
 /// Opaque builder accessor, used internally.
@@ -119,6 +139,54 @@ pub struct CannedAccessor<BCan> {
 }
 
 
+/// Borrows a Promise, either owned or by reference.
+///
+/// This trait is implemented for any [`Promise`] and for references to it,
+/// thus generic code taking an `AP: BorrowPromise<P>` by value accepts a
+/// `blueprint` as well as a `&blueprint`. It is the `Promise` analog to
+/// `std::borrow::Borrow`.
+///
+/// ```
+/// use daab::*;
+/// use daab::blueprint::BorrowPromise;
+/// use daab::blueprint::Promise;
+///
+/// fn get_number<AP, P>(cache: &mut rc::Cache, promise: AP) -> u32
+///         where
+///             AP: BorrowPromise<P>,
+///             P: Promise<Builder = rc::ConstBuilder<u32>, BCan = rc::BuilderCan> {
+///
+///     *cache.get_ref(promise.borrow_promise()).unpack()
+/// }
+///
+/// let mut cache = rc::Cache::new();
+/// let blueprint = rc::Blueprint::new(rc::ConstBuilder::new(std::rc::Rc::new(42)));
+///
+/// assert_eq!(get_number(&mut cache, &blueprint), 42);
+/// assert_eq!(get_number(&mut cache, blueprint), 42);
+/// ```
+///
+/// [`Promise`]: trait.Promise.html
+///
+pub trait BorrowPromise<P: ?Sized + Promise> {
+	/// Borrows the Promise.
+	///
+	fn borrow_promise(&self) -> &P;
+}
+
+impl<P: ?Sized + Promise> BorrowPromise<P> for P {
+	fn borrow_promise(&self) -> &P {
+		self
+	}
+}
+
+impl<P: ?Sized + Promise> BorrowPromise<P> for &P {
+	fn borrow_promise(&self) -> &P {
+		self
+	}
+}
+
+
 /// Wraps a Builder as a blueprint for its artifact from the `Cache`.
 ///
 /// This is a wrapper around the Bin of the Builder-Can containing the actual
@@ -147,6 +215,81 @@ impl<B, BCan: CanSized<B>> Blueprint<B, BCan> {
 	}
 }
 
+impl<B, F, BCan> Blueprint<LazyBuilder<B, F>, BCan>
+		where
+			F: FnOnce() -> B,
+			BCan: CanSized<LazyBuilder<B, F>> {
+
+	/// Crates a new `Blueprint` for a Builder constructed on first use.
+	///
+	/// The given function is called at most once, when the Builder is first
+	/// accessed, i.e. usually when its Artifact is first resolved. See
+	/// `LazyBuilder` for details.
+	///
+	pub fn new_lazy(make: F) -> Self {
+		Self::new(LazyBuilder::new(make))
+	}
+}
+
+#[cfg(feature = "cyclic")]
+impl<B, BCan: CanCyclic<B>> Blueprint<B, BCan> {
+	/// Crates a new `Blueprint` for the Builder returned by `f`, which gets a
+	/// `WeakPromise` of the `Blueprint` under construction.
+	///
+	/// This is analogous to `Rc::new_cyclic` and allows a Builder to refer
+	/// to its own `Blueprint`, e.g. to invalidate itself later on. Notice
+	/// that the `WeakPromise` can not be upgraded until this function
+	/// returned, but its `id` is already valid.
+	///
+	/// **Notice: This function is only available if the `cyclic` feature has
+	/// been activated**.
+	///
+	/// ```
+	/// use daab::*;
+	///
+	/// #[derive(Debug)]
+	/// struct Timer {
+	///     me: rc::WeakPromise<Timer>,
+	/// }
+	/// impl rc::SimpleBuilder for Timer {
+	///     type Artifact = ();
+	///
+	///     fn build(&self, _resolver: &mut rc::Resolver) -> Self::Artifact {
+	///         // `self.me` may be handed out to schedule a self-invalidation
+	///     }
+	/// }
+	///
+	/// let blueprint = rc::Blueprint::new_with_self(|me| Timer { me });
+	/// ```
+	///
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "cyclic")))]
+	pub fn new_with_self<F: FnOnce(WeakPromise<B, BCan>) -> B>(f: F) -> Self {
+		let bin = BCan::new_cyclic_bin(|weak| {
+			f(WeakPromise {
+				builder: weak.clone(),
+				id: BuilderId::new(BCan::weak_bin_as_ptr(weak), || None),
+			})
+		});
+
+		Self::new_binned(bin)
+	}
+}
+
+impl<B, BCan: CanDowngrade<B>> Blueprint<B, BCan> {
+	/// Creates a `WeakPromise` of this `Blueprint`.
+	///
+	/// The `WeakPromise` does not keep the Builder alive, thus it allows a
+	/// Builder to refer to an optional dependency, see
+	/// `Builder::weak_dependencies`.
+	///
+	pub fn downgrade(&self) -> WeakPromise<B, BCan> {
+		WeakPromise {
+			builder: BCan::downgrade_bin(&self.builder),
+			id: self.id(),
+		}
+	}
+}
+
 impl<B, BCan: Can<B>> Blueprint<B, BCan> {
 	/// Create a new `Blueprint` for the given binned Builder.
 	///
@@ -178,7 +321,56 @@ impl<B, BCan: Can<B>> Blueprint<B, BCan> {
 	/// same Artifact form a `Cache`.
 	///
 	pub fn id(&self) -> BuilderId {
-		BuilderId::new(BCan::bin_as_ptr(&self.builder))
+		BuilderId::new(BCan::bin_as_ptr(&self.builder), || BCan::bin_liveness(&self.builder))
+	}
+
+	/// Returns the type name of the inner Builder.
+	///
+	/// This is the name as of `std::any::type_name`, thus it is intended for
+	/// logging and debugging only.
+	///
+	pub fn builder_type_name(&self) -> &'static str {
+		std::any::type_name::<B>()
+	}
+}
+
+impl<B, BCan: CanSized<B>> Blueprint<B, BCan>
+		where
+			B: PartialEq,
+			BCan::Bin: AsRef<B>, {
+
+	/// Compares the inner Builders of both `Blueprint`s by value.
+	///
+	/// **Notice: this is distinct from `==`**, which compares the identity of
+	/// the Builders (i.e. their ids), just like a `Cache` does. Thus two
+	/// independently constructed `Blueprint`s of equal Builders are
+	/// `value_eq` but not `==`, and they will not share their Artifact in a
+	/// `Cache`. Use `Cache::intern` in order to get a single `Blueprint` for
+	/// equal Builders.
+	///
+	/// ```
+	/// use daab::*;
+	///
+	/// #[derive(Debug, PartialEq)]
+	/// struct Answer(u32);
+	/// impl rc::SimpleBuilder for Answer {
+	///     type Artifact = u32;
+	///
+	///     fn build(&self, _resolver: &mut rc::Resolver) -> Self::Artifact {
+	///         self.0
+	///     }
+	/// }
+	///
+	/// let first = rc::Blueprint::new(Answer(42));
+	/// let second = rc::Blueprint::new(Answer(42));
+	///
+	/// assert!(first.value_eq(&second));
+	/// assert!(first != second);
+	/// assert!(!first.value_eq(&rc::Blueprint::new(Answer(7))));
+	/// ```
+	///
+	pub fn value_eq(&self, other: &Self) -> bool {
+		self.builder.as_ref() == other.builder.as_ref()
 	}
 }
 
@@ -205,6 +397,52 @@ impl<B, BCan: CanSized<B>> Promise for Blueprint<B, BCan>
 			can: BCan::from_bin(self.builder.clone()),
 		}
 	}
+
+	#[cfg(feature = "diagnostics")]
+	fn debug_fn(&self) -> Option<fn(BCan) -> Option<String>> {
+		Some(|can| {
+			can.downcast_can().map(|bin| format!("{:?}", bin.as_ref()))
+		})
+	}
+}
+
+impl<B, BCan: CanSized<B>> Blueprint<B, BCan>
+		where
+			B: 'static + Debug,
+			BCan::Bin: AsRef<B> + Clone, {
+
+	/// Gets the Artifact of this Blueprint from the given `Cache`.
+	///
+	/// This is equivalent to `cache.get(&blueprint)`, except that the
+	/// Builder type is pinned to the one of this Blueprint, which helps type
+	/// inference, e.g. when comparing Artifacts.
+	///
+	pub fn get<ArtCan>(&self, cache: &mut Cache<ArtCan, BCan>)
+			-> Result<<ArtCan as Can<B::Artifact>>::Bin, B::Err>
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanSized<B::Artifact> + Clone + Debug,
+				BCan: CanStrong + Debug {
+
+		cache.get(self)
+	}
+
+	/// Gets a clone of the Artifact of this Blueprint from the given `Cache`.
+	///
+	/// This is equivalent to `cache.get_cloned(&blueprint)`, except that the
+	/// Builder type is pinned to the one of this Blueprint, which helps type
+	/// inference, e.g. when comparing Artifacts.
+	///
+	pub fn get_cloned<ArtCan>(&self, cache: &mut Cache<ArtCan, BCan>)
+			-> Result<B::Artifact, B::Err>
+			where
+				B: Builder<ArtCan, BCan>,
+				B::Artifact: Clone,
+				ArtCan: CanRef<B::Artifact> + Debug,
+				BCan: CanStrong + Debug {
+
+		cache.get_cloned(self)
+	}
 }
 
 cfg_if! {
@@ -261,6 +499,54 @@ impl<B, BCan: Can<B>> PartialEq for Blueprint<B, BCan> {
 impl<B, BCan: Can<B>> Eq for Blueprint<B, BCan> {
 }
 
+
+/// A weak reference to a `Blueprint`.
+///
+/// A `WeakPromise` does not keep its Builder alive, it only allows to get
+/// the `Blueprint` back as long as there is any other `Blueprint` of the same
+/// Builder left. It is created by [`Blueprint::downgrade`], or with the
+/// `cyclic` feature by [`Blueprint::new_with_self`] to allow a Builder to
+/// refer to its own `Blueprint`.
+///
+/// [`Blueprint::new_with_self`]: struct.Blueprint.html#method.new_with_self
+/// [`Blueprint::downgrade`]: struct.Blueprint.html#method.downgrade
+///
+pub struct WeakPromise<B, BCan: CanCyclic<B>> {
+	builder: BCan::WeakBin,
+	id: BuilderId,
+}
+
+impl<B, BCan: CanCyclic<B>> WeakPromise<B, BCan> {
+	/// Tries to get the `Blueprint` back, if it is still alive.
+	///
+	pub fn upgrade(&self) -> Option<Blueprint<B, BCan>> {
+		BCan::upgrade_bin(&self.builder).map(Blueprint::new_binned)
+	}
+
+	/// Returns the id of the inner Builder.
+	///
+	/// This is the same id as the one of the respective `Blueprint`s.
+	///
+	pub fn id(&self) -> BuilderId {
+		self.id
+	}
+}
+
+impl<B, BCan: CanCyclic<B>> Clone for WeakPromise<B, BCan> {
+	fn clone(&self) -> Self {
+		WeakPromise {
+			builder: self.builder.clone(),
+			id: self.id,
+		}
+	}
+}
+
+impl<B, BCan: CanCyclic<B>> fmt::Debug for WeakPromise<B, BCan> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "WeakPromise {{id: {:p}}}", self.id())
+	}
+}
+
 impl<B, BCan: Can<B>> fmt::Pointer for Blueprint<B, BCan> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		writeln!(f, "{:p}", BCan::bin_as_ptr(&self.builder))
@@ -378,8 +664,22 @@ cfg_if! {
 			/// containing/sharing the same Builder and consequently will deliver the
 			/// same Artifact form a `Cache`.
 			///
+			/// This also holds for a `BlueprintUnsized` converted from a
+			/// `Blueprint` (even with an unsized Builder), thus both compare
+			/// equal and are interchangeable when used with a `Cache`.
+			///
 			pub fn id(&self) -> BuilderId {
-				BuilderId::new(BCan::can_as_ptr(&self.builder_canned))
+				BuilderId::new(BCan::can_as_ptr(&self.builder_canned), || self.builder_canned.can_liveness())
+			}
+
+			/// Returns the type name of the inner Builder.
+			///
+			/// This is the name as of `std::any::type_name`, thus for a trait
+			/// object Builder, it is the name of the trait object type. It is
+			/// intended for logging and debugging only.
+			///
+			pub fn builder_type_name(&self) -> &'static str {
+				std::any::type_name::<B>()
 			}
 
 			/// Returns the pointer to the inner Builder.
@@ -505,6 +805,24 @@ cfg_if! {
 		impl<B: ?Sized, BCan: Can<B>> Eq for BlueprintUnsized<B, BCan> {
 		}
 
+		impl<B, UB: ?Sized, BCan> PartialEq<BlueprintUnsized<UB, BCan>> for Blueprint<B, BCan>
+				where
+					BCan: Can<B> + Can<UB>, {
+
+			fn eq(&self, other: &BlueprintUnsized<UB, BCan>) -> bool {
+				self.id().eq(&other.id())
+			}
+		}
+
+		impl<B, UB: ?Sized, BCan> PartialEq<Blueprint<B, BCan>> for BlueprintUnsized<UB, BCan>
+				where
+					BCan: Can<B> + Can<UB>, {
+
+			fn eq(&self, other: &Blueprint<B, BCan>) -> bool {
+				self.id().eq(&other.id())
+			}
+		}
+
 		impl<B: ?Sized, BCan: Can<B>> fmt::Pointer for BlueprintUnsized<B, BCan> {
 			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 				writeln!(f, "{:p}", BCan::can_as_ptr(&self.builder_canned))
@@ -662,7 +980,17 @@ impl<ArtCan, BCan, Art, Err, DynSt> BlueprintDyn<ArtCan, BCan, Art, Err, DynSt>
 	/// same Artifact form a `Cache`.
 	///
 	pub fn id(&self) -> BuilderId {
-		BuilderId::new(BCan::can_as_ptr(&self.builder_canned))
+		BuilderId::new(BCan::can_as_ptr(&self.builder_canned), || self.builder_canned.can_liveness())
+	}
+
+	/// Returns the type name of the inner Builder.
+	///
+	/// Since the Builder is a trait object, this is the name of the trait
+	/// object type as of `std::any::type_name`. It is intended for logging
+	/// and debugging only.
+	///
+	pub fn builder_type_name(&self) -> &'static str {
+		std::any::type_name::<BuilderDyn<ArtCan, BCan, Art, Err, DynSt>>()
 	}
 
 	/// Returns the pointer to the inner Builder.
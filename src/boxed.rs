@@ -14,6 +14,8 @@ use cfg_if::cfg_if;
 use crate::Doctor;
 
 use crate::BlueprintDyn;
+use crate::BuilderId;
+use crate::cache::DeclaredDependency;
 use crate::Never;
 
 
@@ -56,6 +58,20 @@ cfg_if! {
 pub type DynamicBlueprint<Artifact, Err=Never, DynState=()> =
 	BlueprintDyn<CanType, BuilderCan, Artifact, Err, DynState>;
 
+/// Wraps the given Builder as `DynamicBlueprint`.
+///
+/// This is a shorthand of `DynamicBlueprint::new` for the common case of an
+/// infallible Builder without dynamic state, i.e. with `Err = Never` and
+/// `DynState = ()`. It allows to mix different Builders of the same
+/// Artifact type, e.g. in a `Vec`, without spelling out the full type.
+///
+pub fn dyn_blueprint<B>(builder: B) -> DynamicBlueprint<B::Artifact>
+		where
+			B: crate::Builder<CanType, BuilderCan, Err = Never, DynState = ()> {
+
+	DynamicBlueprint::new(builder)
+}
+
 pub type ConstBuilder<T> = crate::utils::ConstBuilder<CanType, BuilderCan, BinType<T>, T>;
 pub type ConfigurableBuilder<T> = crate::utils::ConfigurableBuilder<CanType, BuilderCan, T>;
 
@@ -153,6 +169,51 @@ pub trait Builder: Debug + 'static {
 	/// Return an inital dynamic state for this builder.
 	///
 	fn init_dyn_state(&self) -> Self::DynState;
+
+	/// Returns a copy of the given error, if it shall be cached.
+	///
+	/// See the `cache_error` method of the general `Builder` trait.
+	///
+	fn cache_error(&self, _err: &Self::Err) -> Option<Self::Err> {
+		None
+	}
+
+	/// Returns a hook to be called with the Artifact when it is evicted.
+	///
+	/// See the `evict_hook` method of the general `Builder` trait.
+	///
+	fn evict_hook(&self) -> Option<fn(&CanType)> {
+		None
+	}
+
+	/// Declares the dependencies which this Builder refers to weakly.
+	///
+	/// See the `weak_dependencies` method of the general `Builder` trait.
+	///
+	fn weak_dependencies(&self, _declare: &mut dyn FnMut(BuilderId)) {
+		// none
+	}
+
+	/// Returns the approximate memory size of the given Artifact in bytes.
+	///
+	/// See the `artifact_size_hint` method of the general `Builder` trait.
+	///
+	/// **Notice: This method is only available if the `lru` feature
+	/// has been activated**.
+	///
+	#[cfg(feature = "lru")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "lru")))]
+	fn artifact_size_hint(&self, _artifact: &Self::Artifact) -> usize {
+		0
+	}
+
+	/// Returns the dependencies of this Builder, declared up front.
+	///
+	/// See the `declare_dependencies` method of the general `Builder` trait.
+	///
+	fn declare_dependencies(&self) -> Vec<Box<dyn DeclaredDependency<CanType, crate::rc::CanType>>> {
+		Vec::new()
+	}
 }
 
 impl<B: ?Sized + Builder> crate::Builder<CanType, crate::rc::CanType> for B {
@@ -169,6 +230,27 @@ impl<B: ?Sized + Builder> crate::Builder<CanType, crate::rc::CanType> for B {
 	fn init_dyn_state(&self) -> Self::DynState {
 		self.init_dyn_state()
 	}
+
+	fn cache_error(&self, err: &Self::Err) -> Option<Self::Err> {
+		self.cache_error(err)
+	}
+
+	fn evict_hook(&self) -> Option<fn(&CanType)> {
+		self.evict_hook()
+	}
+
+	fn weak_dependencies(&self, declare: &mut dyn FnMut(BuilderId)) {
+		self.weak_dependencies(declare)
+	}
+
+	#[cfg(feature = "lru")]
+	fn artifact_size_hint(&self, artifact: &Self::Artifact) -> usize {
+		self.artifact_size_hint(artifact)
+	}
+
+	fn declare_dependencies(&self) -> Vec<Box<dyn DeclaredDependency<CanType, crate::rc::CanType>>> {
+		self.declare_dependencies()
+	}
 }
 
 
@@ -23,14 +23,25 @@
 //!
 
 
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::error::Error;
 use std::fmt;
 use std::fmt::Debug;
+use std::hash::BuildHasher;
+use std::hash::Hash;
 use std::marker::PhantomData;
+use std::ops::Deref;
+use std::time::Duration;
 
 use cfg_if::cfg_if;
 
 #[cfg(feature = "mut_box")]
 use crate::canning::CanRefMut;
+use crate::canning::CanBase;
+use crate::canning::CanDowngrade;
 
 use crate::CanStrong;
 use crate::CanSized;
@@ -38,13 +49,18 @@ use crate::CanRef;
 use crate::Can;
 
 use crate::Promise;
+use crate::Blueprint;
 
 use crate::Builder;
+use crate::BuilderId;
+use crate::Never;
+use crate::Unpacking;
 
 mod internal;
 
 use internal::BuilderEntry;
 use internal::RawCache;
+use internal::ErasedState;
 
 
 
@@ -154,6 +170,9 @@ use internal::RawCache;
 /// To clear all Artifacts there is the [`clear_artifacts`] method. And to
 /// purge all Builders from the `Cache` there is the [`clear_all`] method.
 ///
+/// The internal maps of the `Cache` use the std hasher by default, see
+/// [`CacheOwned::with_hasher`].
+///
 /// [`invalidate`]: struct.Cache.html#method.invalidate
 /// [`purge`]: struct.Cache.html#method.purge
 /// [`garbage_collection`]: struct.Cache.html#method.garbage_collection
@@ -161,750 +180,3507 @@ use internal::RawCache;
 /// [`clear_all`]: struct.Cache.html#method.clear_all
 /// [`is_builder_known`]: struct.Cache.html#method.is_builder_known
 /// [`number_of_known_builders`]: struct.Cache.html#method.number_of_known_builders
+/// [`CacheOwned::with_hasher`]: type.CacheOwned.html#method.with_hasher
 ///
 pub struct Cache<
 	ArtCan,
 	BCan,
-	#[cfg(feature = "diagnostics")] Doc: ?Sized = dyn Doctor<ArtCan, BCan>
+	#[cfg(feature = "diagnostics")] Doc: ?Sized = dyn Doctor<ArtCan, BCan>,
 > where BCan: CanStrong {
 
 	/// The inner cache
 	#[cfg(feature = "diagnostics")]
-	inner: RawCache<ArtCan, BCan, Doc>,
+	pub(crate) inner: RawCache<ArtCan, BCan, Doc>,
 	#[cfg(not(feature = "diagnostics"))]
-	inner: RawCache<ArtCan, BCan>,
+	pub(crate) inner: RawCache<ArtCan, BCan>,
 
 }
 
-/// The ownable and storable variant of the Cache.
-///
-/// This is a simple type-def to Cache, which guarantees independent of
-/// whether the `diagnostics` feature is enabled or not that this type is
-/// storable as owned value.
-///
-/// When ever a Cache needs to be stored such as in a struct, this type
-/// alias should be preferred over using Cache directly.
-///
-#[cfg(feature = "diagnostics")]
-pub type CacheOwned<ArtCan, BCan> =
-	Cache<ArtCan, BCan, DefDoctor>;
+cfg_if! {
+	if #[cfg(feature = "diagnostics")] {
+		/// The `Cache` with the given hasher and an opaque doctor, on which
+		/// most methods are defined.
+		type CacheDyn<ArtCan, BCan> = Cache<ArtCan, BCan, dyn Doctor<ArtCan, BCan>>;
+	} else {
+		/// The `Cache` with the given hasher, on which most methods are
+		/// defined.
+		type CacheDyn<ArtCan, BCan> = Cache<ArtCan, BCan>;
+	}
+}
 
-/// The ownable and storable variant of the Cache.
+/// Error type of the checked `Cache` accessors.
 ///
-/// This is a simple type-def to Cache, which guarantees independent of
-/// whether the `diagnostics` feature is enabled or not that this type is
-/// storable as owned value.
+/// Some `Cache` methods can fail for other reasons than a failing Builder.
+/// Those return this error, which wraps the error of the Builder (`E`) in the
+/// `Build` variant.
 ///
-/// When ever a Cache needs to be stored such as in a struct, this type
-/// alias should be preferred over using Cache directly.
+/// Currently, the only other reason is a Cached Artifact of an unexpected
+/// type, which can only happen due to a bug e.g. in a custom Can
+/// implementation. See [`Cache::checked_get`].
 ///
-#[cfg(not(feature = "diagnostics"))]
-pub type CacheOwned<ArtCan, BCan> =
-	Cache<ArtCan, BCan>;
+/// [`Cache::checked_get`]: struct.Cache.html#method.checked_get
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheError<E = crate::Never> {
+	/// The Builder failed to produce its Artifact.
+	Build(E),
 
-impl<ArtCan, BCan> Default for CacheOwned<ArtCan, BCan>
-	where BCan: CanStrong {
+	/// A cached Artifact or dynamic state has not the expected type.
+	TypeMismatch,
+}
 
-	fn default() -> Self {
-		CacheOwned::new()
+impl CacheError {
+	/// Converts an infallible `CacheError` into one of any Builder error.
+	///
+	pub fn cast<E>(self) -> CacheError<E> {
+		match self {
+			CacheError::Build(n) => match n {},
+			CacheError::TypeMismatch => CacheError::TypeMismatch,
+		}
 	}
 }
 
-impl<ArtCan, BCan> CacheOwned<ArtCan, BCan>
-	where BCan: CanStrong {
-
-	/// Creates a new empty cache with a dummy doctor.
-	///
-	pub fn new() -> Self {
-		cfg_if! {
-			if #[cfg(feature = "diagnostics")] {
-				Self {
-					inner: RawCache::new_with_doctor(Default::default())
-				}
-			} else {
-				Self {
-					inner: RawCache::new()
-				}
-			}
+impl<E: fmt::Display> fmt::Display for CacheError<E> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			CacheError::Build(e) => write!(f, "Builder failed: {}", e),
+			CacheError::TypeMismatch => write!(f, "Cached value is of invalid type"),
 		}
 	}
 }
 
-cfg_if! {
-	if #[cfg(feature = "diagnostics")] {
-		use std::ops::Deref;
-		use std::ops::DerefMut;
-		use crate::Doctor;
-		use crate::DefDoctor;
-		use crate::BuilderHandle;
+impl<E: Error + 'static> Error for CacheError<E> {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			CacheError::Build(e) => Some(e),
+			CacheError::TypeMismatch => None,
+		}
+	}
+}
 
-		impl<ArtCan, BCan, Doc> Debug for Cache<ArtCan, BCan, Doc>
-			where ArtCan: Debug, BCan: CanStrong + Debug, Doc: Debug {
+/// A collection of errors of multiple Builders.
+///
+/// This type is intended for Builders with multiple fallible dependencies,
+/// which want to report all failed dependencies at once instead of just the
+/// first one. The errors are collected via [`Resolver::resolve_collecting`]
+/// and kept in the order they occurred, along with the [`BuilderId`] of the
+/// failed Builder.
+///
+/// [`Resolver::resolve_collecting`]: struct.Resolver.html#method.resolve_collecting
+/// [`BuilderId`]: ../struct.BuilderId.html
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiError<E> {
+	errors: Vec<(BuilderId, E)>,
+}
 
-			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-				self.inner.fmt(f)
-			}
+impl<E> MultiError<E> {
+	/// Creates a new empty collection of errors.
+	///
+	pub fn new() -> Self {
+		MultiError {
+			errors: Vec::new(),
 		}
+	}
 
-		impl<ArtCan, BCan, Doc> Cache<ArtCan, BCan, Doc>
-			where BCan: CanStrong, Doc: Doctor<ArtCan, BCan> + 'static {
-
-			/// Creates new empty cache with given doctor for inspection.
-			///
-			/// **Notice: This function is only available if the `diagnostics` feature has been activated**.
-			///
-			pub fn new_with_doctor(doctor: Doc) -> Self {
-				Self {
-					inner: RawCache::new_with_doctor(doctor)
-				}
-			}
+	/// Adds the error of the given Builder.
+	///
+	pub fn push(&mut self, id: BuilderId, err: E) {
+		self.errors.push((id, err));
+	}
 
-			/// Returns a reference of the inner doctor.
-			///
-			/// **Notice: This function is only available if the `diagnostics` feature has been activated**.
-			///
-			pub fn doctor(&mut self) -> &mut Doc {
-				&mut self.inner.doctor
-			}
+	/// Returns whether no error has been collected.
+	///
+	pub fn is_empty(&self) -> bool {
+		self.errors.is_empty()
+	}
 
-			/// Consumes the `Cache` and returns the inner doctor.
-			///
-			/// **Notice: This function is only available if the `diagnostics` feature has been activated**.
-			///
-			pub fn into_doctor(self) -> Doc {
-				self.inner.doctor
-			}
-		}
+	/// Returns the number of collected errors.
+	///
+	pub fn len(&self) -> usize {
+		self.errors.len()
+	}
 
-		impl<ArtCan, BCan, Doc> Deref for Cache<ArtCan, BCan, Doc>
-			where BCan: CanStrong, Doc: Doctor<ArtCan, BCan> + 'static {
+	/// Returns the collected errors along with the id of their Builders.
+	///
+	pub fn errors(&self) -> &[(BuilderId, E)] {
+		&self.errors
+	}
 
-			type Target = Cache<ArtCan, BCan>;
+	/// Returns the collected errors along with the id of their Builders.
+	///
+	pub fn into_errors(self) -> Vec<(BuilderId, E)> {
+		self.errors
+	}
 
-			fn deref(&self) -> &Self::Target {
-				self
-			}
+	/// Returns `Ok` if no error has been collected, or `Err` with `self`
+	/// otherwise.
+	///
+	/// This allows to use the `?` operator after all dependencies have been
+	/// resolved.
+	///
+	pub fn into_result(self) -> Result<(), Self> {
+		if self.is_empty() {
+			Ok(())
+		} else {
+			Err(self)
 		}
+	}
+}
 
-		impl<ArtCan, BCan, Doc> DerefMut for Cache<ArtCan, BCan, Doc>
-			where BCan: CanStrong, Doc: Doctor<ArtCan, BCan> + 'static {
+impl<E> Default for MultiError<E> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
 
-			fn deref_mut(&mut self) -> &mut Self::Target {
-				self
-			}
-		}
+impl<E: fmt::Display> fmt::Display for MultiError<E> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} Builder(s) failed", self.errors.len())?;
 
+		for (_, e) in &self.errors {
+			write!(f, "; {}", e)?;
+		}
 
-	} else {
-		impl<ArtCan, BCan> Debug for Cache<ArtCan, BCan>
-			where ArtCan: Debug, BCan: CanStrong + Debug {
+		Ok(())
+	}
+}
 
-			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-				self.inner.fmt(f)
-			}
-		}
+impl<E: Error + 'static> Error for MultiError<E> {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		self.errors.first().map(|(_, e)| e as &(dyn Error + 'static))
 	}
 }
 
-impl<ArtCan: Debug, BCan: CanStrong + Debug> Cache<ArtCan, BCan> {
+/// A reference to a cached Artifact along with some metadata.
+///
+/// This wrapper is returned by [`Cache::lookup_ref_meta`]. It dereferences to
+/// the Artifact, thus it can be used mostly like a plain `&T`, but
+/// additionally provides the [`BuilderId`] of the Builder of the Artifact and
+/// the type name of the Artifact. This is useful e.g. for logging when
+/// handling the Artifacts of many Builders.
+///
+/// [`Cache::lookup_ref_meta`]: struct.Cache.html#method.lookup_ref_meta
+/// [`BuilderId`]: ../struct.BuilderId.html
+///
+#[derive(Debug)]
+pub struct ArtifactRef<'a, T: ?Sized> {
+	artifact: &'a T,
+	id: BuilderId,
+}
 
-	/// Tests whether the artifact or dynamic state of the given builder is
-	/// recorded in this cache.
-	///
-	/// If this function returns `true`, the artifact or dynamic state or both
-	/// are kept in this `Cache` and additionally a "weak" Can to the Builder.
-	/// That is `rc::Weak<dyn Any>` when using the `rc` module.
-	/// If this function returns `false`, it is guaranteed that none of the
-	/// above are kept in this `Cache`.
+impl<'a, T: ?Sized> ArtifactRef<'a, T> {
+	/// Returns the id of the Builder of the Artifact.
 	///
-	/// [`number_of_known_builders`] returns the amount of Builders for
-	/// which this methods returns `true`.
+	pub fn id(&self) -> BuilderId {
+		self.id
+	}
+
+	/// Returns the type name of the Artifact as of `std::any::type_name`.
 	///
-	/// [`number_of_known_builders`]: struct.Cache.html#method.number_of_known_builders
+	pub fn type_name(&self) -> &'static str {
+		std::any::type_name::<T>()
+	}
+
+	/// Returns the plain reference to the Artifact.
 	///
-	pub fn is_builder_known<AP: ?Sized>(
-			&self,
-			promise: &AP
-		) -> bool
-			where
-				BCan: Can<AP::Builder>,
-				AP: Promise<BCan = BCan> {
+	pub fn into_inner(self) -> &'a T {
+		self.artifact
+	}
+}
 
-		self.inner.is_builder_known(promise)
+impl<'a, T: ?Sized> Clone for ArtifactRef<'a, T> {
+	fn clone(&self) -> Self {
+		*self
 	}
+}
 
-	/// Gets the stored Artifact in its Bin, if it exists.
-	///
-	/// Returns the Artifact in its Bin. That is an `Rc<B::Artifact>` when using
-	/// the `rc` module. The Bin is useful to share an identical artifact
-	/// or one that is not `Clone` when an owned value is required or lifetime
-	/// errors occur using [`lookup_ref`].
+impl<'a, T: ?Sized> Copy for ArtifactRef<'a, T> {
+}
+
+impl<'a, T: ?Sized> Deref for ArtifactRef<'a, T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		self.artifact
+	}
+}
+
+/// Lightweight usage counters of a `Cache`.
+///
+/// These are always collected, in contrast to the full inspection provided by
+/// a [`Doctor`] which requires the `diagnostics` feature. They are returned by
+/// [`Cache::stats`] and can be reset via [`Cache::reset_stats`].
+///
+/// Notice, that Artifacts resolved by a Builder during its build are counted
+/// too.
+///
+/// [`Doctor`]: ../diagnostics/trait.Doctor.html
+/// [`Cache::stats`]: struct.Cache.html#method.stats
+/// [`Cache::reset_stats`]: struct.Cache.html#method.reset_stats
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CacheStats {
+	/// Number of Artifact requests answered from the cache.
+	pub hits: u64,
+
+	/// Number of Artifact requests requiring a build (including requests
+	/// answered by a cached error).
+	pub misses: u64,
+
+	/// Number of actually executed builds, successful or not.
+	pub builds: u64,
+
+	/// Number of invalidation requests.
+	pub invalidations: u64,
+}
+
+/// The storage of the Artifacts of a `Cache`.
+///
+/// By default, a `Cache` keeps its Artifacts in a `HashMap`, which implements
+/// this trait. An alternative storage can be supplied upon construction via
+/// [`CacheOwned::with_store`], e.g. to keep huge Artifacts out of the main
+/// memory.
+///
+/// A store must keep each inserted Artifact until it is removed or cleared by
+/// the `Cache`, because the `Cache` keeps track of the dependencies between
+/// the stored Artifacts. For a size-bounded `Cache`, see
+/// [`Cache::set_memory_budget`] instead.
+///
+/// [`CacheOwned::with_store`]: type.CacheOwned.html#method.with_store
+/// [`Cache::set_memory_budget`]: struct.Cache.html#method.set_memory_budget
+///
+pub trait ArtifactStore<ArtCan> {
+	/// Inserts the Artifact of the given Builder, returning the previous one,
+	/// if any.
 	///
-	/// This method will not attempt to build the Artifact if it does not exist
-	/// already, instead `None` will be returned then.
+	fn insert(&mut self, id: BuilderId, can: ArtCan) -> Option<ArtCan>;
+
+	/// Gets the Artifact of the given Builder, if any.
 	///
-	/// For an overview of different accessor methods see [Artifact Accessors]
-	/// section of `Cache`.
+	fn get(&self, id: BuilderId) -> Option<&ArtCan>;
+
+	/// Gets the Artifact of the given Builder mutably, if any.
 	///
-	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
-	/// [`lookup_ref`]: struct.Cache.html#method.lookup_ref
+	fn get_mut(&mut self, id: BuilderId) -> Option<&mut ArtCan>;
+
+	/// Removes and returns the Artifact of the given Builder, if any.
 	///
-	pub fn lookup<AP, B: ?Sized>(
-			&self,
-			promise: &AP
-		) -> Option<ArtCan::Bin>
-			where
-				ArtCan: CanSized<B::Artifact>,
-				ArtCan: Clone,
-				B: Builder<ArtCan, BCan>,
-				BCan: Can<AP::Builder>,
-				AP: Promise<Builder = B, BCan = BCan>  {
+	fn remove(&mut self, id: BuilderId) -> Option<ArtCan>;
 
-		self.inner.lookup(promise)
+	/// Tests whether there is an Artifact of the given Builder.
+	///
+	fn contains(&self, id: BuilderId) -> bool {
+		self.get(id).is_some()
 	}
 
-	/// Gets the stored Artifact by reference, if it exists.
-	///
-	/// Returns the Artifact as reference into this `Cache`. The reference is
-	/// useful to access artifact for short time, as it dose not incur any
-	/// cloning overhead, thus it is the cheapest way to access an Artifact, and
-	/// should be preferred wherever possible.
-	///
-	/// When an owned value is required instead or lifetime issues arise,
-	/// [`lookup`] and [`lookup_cloned`] are alternatives, which return a clone
-	/// of the Artifact Bin or of the Artifact itself, respectively.
-	///
-	/// Also notice, that using some special `ArtCan`s such as using the
-	/// [`boxed`] module there also exists a [`lookup_mut`] for mutable access
-	/// to the artifact stored within this `Cache`.
+	/// Removes all Artifacts.
 	///
-	/// This method will not attempt to build the Artifact if it does not exist
-	/// already, instead `None` will be returned then.
+	fn clear(&mut self);
+
+	/// Returns the number of stored Artifacts.
 	///
-	/// For an overview of different accessor methods see [Artifact Accessors]
-	/// section of `Cache`.
+	fn len(&self) -> usize;
+
+	/// Tests whether there are no Artifacts stored.
 	///
-	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
-	/// [`lookup`]: struct.Cache.html#method.lookup
-	/// [`boxed`]: ../boxed/index.html
-	/// [`lookup_cloned`]: struct.Cache.html#method.lookup_cloned
-	/// [`lookup_mut`]: struct.Cache.html#method.lookup_mut
+	fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the ids of the Builders of all stored Artifacts.
 	///
-	pub fn lookup_ref<AP, B: ?Sized>(
-			&self,
-			promise: &AP
-		) -> Option<&B::Artifact>
-			where
-				ArtCan: CanRef<B::Artifact>,
-				B: Builder<ArtCan, BCan>,
-				BCan: Can<AP::Builder>,
-				AP: Promise<Builder = B, BCan = BCan>  {
+	fn ids(&self) -> Box<dyn Iterator<Item = BuilderId> + '_>;
+}
 
-		self.inner.lookup_ref(promise)
+impl<ArtCan, S: BuildHasher> ArtifactStore<ArtCan> for HashMap<BuilderId, ArtCan, S> {
+	fn insert(&mut self, id: BuilderId, can: ArtCan) -> Option<ArtCan> {
+		HashMap::insert(self, id, can)
 	}
 
+	fn get(&self, id: BuilderId) -> Option<&ArtCan> {
+		HashMap::get(self, &id)
+	}
 
-cfg_if! {
-	if #[cfg(feature = "mut_box")] {
+	fn get_mut(&mut self, id: BuilderId) -> Option<&mut ArtCan> {
+		HashMap::get_mut(self, &id)
+	}
 
-		/// Gets the stored Artifact by mutable reference, if it exists.
-		///
-		/// Returns the Artifact as mutable reference into this `Cache`.
-		/// The mutable reference might be useful to access the Artifact in place
-		/// to mutate it.
-		///
-		/// Note: If mutation is not required [`lookup_ref`] should be
-		/// preferred. Also if mutation is conditional, first a shared reference
-		/// should be acquired via [`lookup_ref`] to test the condition and only
-		/// when necessary a mutable reference should be acquired.
-		///
-		/// **Currently, when using this method, all artifacts which depended on
-		/// accessed one will be invalidate!**
-		///
-		/// This method will not attempt to build the Artifact if it does not exist
-		/// already, instead `None` will be returned then.
-		///
-		/// For an overview of different accessor methods see [Artifact Accessors]
-		/// section of `Cache`.
-		///
-		///
-		///
-		/// # Unstable
-		///
-		/// This function should be only used with care, because currently, this
-		/// function will invalidate all depending artifacts, **but this is subject
-		/// to change**.
-		///
-		/// Therefore, **this method must be considered unstable!** The semantic of
-		/// this function might change in a breaking way within a non-breaking
-		/// version update!
-		///
-		///
-		/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
-		/// [`lookup_ref`]: struct.Cache.html#method.lookup_ref
-		///
-		#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "mut_box")))]
-		pub fn lookup_mut<AP, B: ?Sized>(
-				&mut self,
-				promise: &AP
-			) -> Option<&mut B::Artifact>
-				where
-					ArtCan: CanRefMut<B::Artifact>,
-					B: Builder<ArtCan, BCan>,
-					BCan: Can<AP::Builder>,
-					AP: Promise<Builder = B, BCan = BCan>  {
+	fn remove(&mut self, id: BuilderId) -> Option<ArtCan> {
+		HashMap::remove(self, &id)
+	}
 
-			self.inner.lookup_mut(promise)
+	fn contains(&self, id: BuilderId) -> bool {
+		self.contains_key(&id)
+	}
+
+	fn clear(&mut self) {
+		HashMap::clear(self)
+	}
+
+	fn len(&self) -> usize {
+		HashMap::len(self)
+	}
+
+	fn ids(&self) -> Box<dyn Iterator<Item = BuilderId> + '_> {
+		Box::new(self.keys().copied())
+	}
+}
+
+/// The Builders affected by an invalidation.
+///
+/// This report is returned by [`Cache::invalidate_report`]. The Builders are
+/// listed in the order they have been invalidated.
+///
+/// [`Cache::invalidate_report`]: struct.Cache.html#method.invalidate_report
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InvalidationReport {
+	/// The Builders whose Artifacts have been removed.
+	pub invalidated_artifacts: Vec<BuilderId>,
+
+	/// The invalidated Builders which kept their dynamic state.
+	pub preserved_dyn_states: Vec<BuilderId>,
+}
+
+/// The outcome of a build which might have been cut short by a deadline.
+///
+/// This is returned by [`arc::Cache::get_partial`].
+///
+/// [`arc::Cache::get_partial`]: struct.Cache.html#method.get_partial
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialResult<T, E> {
+	/// The result of the requested Builder, or `None` if it has not been
+	/// built before the deadline, i.e. the result is incomplete.
+	pub artifact: Option<Result<T, E>>,
+
+	/// The Builders whose Artifacts have been built within the deadline.
+	pub built: Vec<BuilderId>,
+
+	/// The declared dependencies which have not been built yet.
+	pub pending: Vec<BuilderId>,
+}
+
+impl<T, E> PartialResult<T, E> {
+	/// Returns whether the requested Builder has been built.
+	///
+	pub fn is_complete(&self) -> bool {
+		self.artifact.is_some()
+	}
+}
+
+/// The outcome of rebuilding a Builder and its dependents.
+///
+/// This report is returned by [`Cache::rebuild_subtree`]. The Builders are
+/// listed in the order they have been processed.
+///
+/// [`Cache::rebuild_subtree`]: struct.Cache.html#method.rebuild_subtree
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RebuildReport {
+	/// The Builders whose Artifacts have been rebuilt.
+	pub rebuilt: Vec<BuilderId>,
+
+	/// The Builders whose rebuild failed.
+	pub failed: Vec<BuilderId>,
+
+	/// The Builders which have not been rebuilt, because they aren't
+	/// watched or because the cascade has been aborted.
+	pub skipped: Vec<BuilderId>,
+}
+
+impl RebuildReport {
+	/// Returns whether all Builders have been rebuilt.
+	///
+	pub fn is_complete(&self) -> bool {
+		self.failed.is_empty() && self.skipped.is_empty()
+	}
+}
+
+/// The inputs read to build an Artifact.
+///
+/// This is returned by [`Cache::artifact_provenance`].
+///
+/// **Notice: This type is only available if the `provenance` feature has been
+/// activated**.
+///
+/// [`Cache::artifact_provenance`]: struct.Cache.html#method.artifact_provenance
+///
+#[cfg(feature = "provenance")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "provenance")))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+	/// The Builders whose Artifacts have been resolved, in the order of
+	/// their first resolution.
+	pub artifacts: Vec<BuilderId>,
+
+	/// The Builders whose dynamic states have been read, in the order of
+	/// their first read.
+	pub dyn_states: Vec<BuilderId>,
+}
+
+/// The amount of data removed by clearing a `Cache`.
+///
+/// This report is returned by [`Cache::clear_all_reporting`] and
+/// [`Cache::clear_artifacts_reporting`].
+///
+/// [`Cache::clear_all_reporting`]: struct.Cache.html#method.clear_all_reporting
+/// [`Cache::clear_artifacts_reporting`]: struct.Cache.html#method.clear_artifacts_reporting
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ClearReport {
+	/// The number of removed Artifacts.
+	pub artifacts: usize,
+
+	/// The number of removed dynamic states.
+	pub dyn_states: usize,
+
+	/// The number of removed dependency relations between Builders.
+	pub edges: usize,
+}
+
+/// Scope of a `Cache::with_frozen_config` call.
+///
+/// Restores the previous frozen state of the dynamic states when dropped,
+/// thus even if the given function panics.
+///
+struct FrozenConfigScope<'c, ArtCan, BCan: CanStrong> {
+	cache: &'c mut CacheDyn<ArtCan, BCan>,
+
+	/// Whether the dynamic states were frozen before.
+	was_frozen: bool,
+}
+
+impl<'c, ArtCan, BCan: CanStrong> Drop for FrozenConfigScope<'c, ArtCan, BCan> {
+	fn drop(&mut self) {
+		self.cache.inner.freeze_config(self.was_frozen);
+	}
+}
+
+/// Collects invalidations within a [`Cache::batch`].
+///
+/// The invalidations requested through this context are not applied
+/// immediately. Instead, they are deduplicated and applied all at once when
+/// the batch ends, traversing the depending Builders only once.
+///
+/// [`Cache::batch`]: struct.Cache.html#method.batch
+///
+pub struct BatchCtx<'a, ArtCan, BCan: CanStrong> {
+	cache: &'a mut RawCache<ArtCan, BCan>,
+	/// Builders to be invalidated, in the order of their first request.
+	pending: Vec<BuilderId>,
+	/// The same Builders as `pending`, for fast lookup.
+	pending_set: HashSet<BuilderId>,
+	#[cfg(feature = "diagnostics")]
+	diag_builders: Vec<BuilderHandle<BCan>>,
+}
+
+impl<'a, ArtCan, BCan: CanStrong> Debug for BatchCtx<'a, ArtCan, BCan> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		// Keep it shallow, the cache might be huge
+		write!(fmt, "BatchCtx {{pending: {:?}}}", self.pending)
+	}
+}
+
+impl<'a, ArtCan: Debug, BCan: CanStrong + Debug> BatchCtx<'a, ArtCan, BCan> {
+
+	/// Queues the invalidation of the given Builder.
+	///
+	/// The Builder and all depending Artifacts will be invalidated when the
+	/// batch ends, see [`Cache::invalidate`].
+	///
+	/// [`Cache::invalidate`]: struct.Cache.html#method.invalidate
+	///
+	pub fn invalidate<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		)
+			where
+				B: Debug + 'static,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		let id = promise.id();
+
+		if self.pending_set.insert(id) {
+			self.pending.push(id);
+
+			#[cfg(feature = "diagnostics")]
+			self.diag_builders.push(BuilderHandle::new(promise));
+		}
+	}
+
+	/// Sets the dynamic state of the given Builder and queues its
+	/// invalidation.
+	///
+	/// The new dynamic state is stored immediately, but the Builder and all
+	/// depending Artifacts will be invalidated when the batch ends, see
+	/// [`Cache::dyn_state_mut`].
+	///
+	/// [`Cache::dyn_state_mut`]: struct.Cache.html#method.dyn_state_mut
+	///
+	pub fn set_dyn_state<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP,
+			dyn_state: B::DynState,
+		)
+			where
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.cache.assert_config_not_frozen();
+
+		*self.cache.ensure_dyn_state(promise) = dyn_state;
+
+		#[cfg(feature = "diagnostics")]
+		self.cache.doctor.dyn_state_changed(&BuilderHandle::new(promise));
+
+		if self.cache.dyn_state_change_invalidates(promise.id()) {
+			self.invalidate(promise);
+		}
+	}
+
+	/// Applies all queued invalidations.
+	///
+	fn finish(self) {
+		self.cache.invalidate_batch(
+			&self.pending,
+			#[cfg(feature = "diagnostics")]
+			&self.diag_builders,
+		);
+	}
+}
+
+/// The ownable and storable variant of the Cache.
+///
+/// This is a simple type-def to Cache, which guarantees independent of
+/// whether the `diagnostics` feature is enabled or not that this type is
+/// storable as owned value.
+///
+/// When ever a Cache needs to be stored such as in a struct, this type
+/// alias should be preferred over using Cache directly.
+///
+#[cfg(feature = "diagnostics")]
+pub type CacheOwned<ArtCan, BCan> =
+	Cache<ArtCan, BCan, DefDoctor>;
+
+/// The ownable and storable variant of the Cache.
+///
+/// This is a simple type-def to Cache, which guarantees independent of
+/// whether the `diagnostics` feature is enabled or not that this type is
+/// storable as owned value.
+///
+/// When ever a Cache needs to be stored such as in a struct, this type
+/// alias should be preferred over using Cache directly.
+///
+#[cfg(not(feature = "diagnostics"))]
+pub type CacheOwned<ArtCan, BCan> =
+	Cache<ArtCan, BCan>;
+
+impl<ArtCan, BCan> Default for CacheOwned<ArtCan, BCan>
+	where BCan: CanStrong {
+
+	fn default() -> Self {
+		CacheOwned::new()
+	}
+}
+
+impl<ArtCan, BCan> CacheOwned<ArtCan, BCan>
+	where BCan: CanStrong {
+
+	/// Creates a new empty cache with a dummy doctor, using the given hasher
+	/// for its internal maps.
+	///
+	/// The keys of these maps are [`BuilderId`]s, which are cheap to hash,
+	/// thus a fast non-cryptographic hasher may noticeably speed up the
+	/// lookup of Artifacts. The hasher is kept internal to the `Cache`, thus
+	/// the Builders are the same as for any other `Cache`.
+	///
+	/// [`BuilderId`]: ../struct.BuilderId.html
+	///
+	pub fn with_hasher<S: BuildHasher + 'static>(hasher: S) -> Self {
+		Self::with_erased_hasher(ErasedState::new(hasher))
+	}
+
+	/// Creates a new empty cache with a dummy doctor, using the given erased
+	/// hasher for its internal maps.
+	///
+	fn with_erased_hasher(hasher: ErasedState) -> Self {
+		cfg_if! {
+			if #[cfg(feature = "diagnostics")] {
+				Self {
+					inner: RawCache::new_with_doctor_and_hasher(Default::default(), hasher)
+				}
+			} else {
+				Self {
+					inner: RawCache::with_hasher(hasher)
+				}
+			}
+		}
+	}
+}
+
+impl<ArtCan, BCan> CacheOwned<ArtCan, BCan>
+	where BCan: CanStrong {
+
+	/// Creates a new empty cache with a dummy doctor.
+	///
+	pub fn new() -> Self {
+		Self::with_erased_hasher(ErasedState::default())
+	}
+
+	/// Creates a new empty cache with a dummy doctor, keeping its Artifacts
+	/// in the given store.
+	///
+	/// See [`ArtifactStore`] for the requirements on the store.
+	///
+	/// # Panics
+	///
+	/// Panics if the given store is not empty.
+	///
+	/// [`ArtifactStore`]: trait.ArtifactStore.html
+	///
+	pub fn with_store<St>(store: St) -> Self
+			where
+				St: ArtifactStore<ArtCan> + 'static {
+
+		let mut cache = Self::new();
+		let inner: &mut RawCache<ArtCan, BCan> = &mut cache.inner;
+		inner.set_store(Box::new(store));
+
+		cache
+	}
+}
+
+cfg_if! {
+	if #[cfg(feature = "diagnostics")] {
+		use std::ops::DerefMut;
+		use crate::Doctor;
+		use crate::DefDoctor;
+		use crate::BuilderHandle;
+
+		impl<ArtCan, BCan, Doc> Debug for Cache<ArtCan, BCan, Doc>
+			where ArtCan: Debug, BCan: CanStrong + Debug, Doc: Debug {
+
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				self.inner.fmt(f)
+			}
+		}
+
+		impl<ArtCan, BCan, Doc> Cache<ArtCan, BCan, Doc>
+			where BCan: CanStrong, Doc: Doctor<ArtCan, BCan> + 'static {
+
+			/// Creates new empty cache with given doctor for inspection.
+			///
+			/// **Notice: This function is only available if the `diagnostics` feature has been activated**.
+			///
+			pub fn new_with_doctor(doctor: Doc) -> Self {
+				Self {
+					inner: RawCache::new_with_doctor(doctor)
+				}
+			}
 		}
+
+		impl<ArtCan, BCan, Doc> Cache<ArtCan, BCan, Doc>
+			where BCan: CanStrong, Doc: Doctor<ArtCan, BCan> + 'static {
+
+			/// Returns a reference of the inner doctor.
+			///
+			/// **Notice: This function is only available if the `diagnostics` feature has been activated**.
+			///
+			pub fn doctor(&mut self) -> &mut Doc {
+				&mut self.inner.doctor
+			}
+
+			/// Consumes the `Cache` and returns the inner doctor.
+			///
+			/// **Notice: This function is only available if the `diagnostics` feature has been activated**.
+			///
+			pub fn into_doctor(self) -> Doc {
+				self.inner.doctor
+			}
+		}
+
+		impl<ArtCan, BCan, Doc> Deref for Cache<ArtCan, BCan, Doc>
+			where BCan: CanStrong, Doc: Doctor<ArtCan, BCan> + 'static {
+
+			type Target = CacheDyn<ArtCan, BCan>;
+
+			fn deref(&self) -> &Self::Target {
+				self
+			}
+		}
+
+		impl<ArtCan, BCan, Doc> DerefMut for Cache<ArtCan, BCan, Doc>
+			where BCan: CanStrong, Doc: Doctor<ArtCan, BCan> + 'static {
+
+			fn deref_mut(&mut self) -> &mut Self::Target {
+				self
+			}
+		}
+
+
+	} else {
+		impl<ArtCan, BCan> Debug for Cache<ArtCan, BCan>
+			where ArtCan: Debug, BCan: CanStrong + Debug {
+
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				self.inner.fmt(f)
+			}
+		}
+
+		impl<ArtCan, BCan> Cache<ArtCan, BCan>
+			where BCan: CanStrong {
+
+			/// Creates new empty cache, ignoring the given doctor.
+			///
+			/// This is the counterpart of `new_with_doctor` of the
+			/// `diagnostics` API, thus constructing a `Cache` with a doctor,
+			/// which need not be `Default`, compiles regardless whether the
+			/// `diagnostics` feature has been activated.
+			///
+			pub fn new_with_doctor<Doc>(_doctor: Doc) -> Self {
+				Self::new()
+			}
+		}
+	}
+}
+
+impl<ArtCan: Debug, BCan: CanStrong + Debug> CacheDyn<ArtCan, BCan> {
+
+	/// Tests whether the artifact or dynamic state of the given builder is
+	/// recorded in this cache.
+	///
+	/// If this function returns `true`, the artifact or dynamic state or both
+	/// are kept in this `Cache` and additionally a "weak" Can to the Builder.
+	/// That is `rc::Weak<dyn Any>` when using the `rc` module.
+	/// If this function returns `false`, it is guaranteed that none of the
+	/// above are kept in this `Cache`.
+	///
+	/// [`number_of_known_builders`] returns the amount of Builders for
+	/// which this methods returns `true`.
+	///
+	/// [`number_of_known_builders`]: struct.Cache.html#method.number_of_known_builders
+	///
+	pub fn is_builder_known<AP: ?Sized>(
+			&self,
+			promise: &AP
+		) -> bool
+			where
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.inner.is_builder_known(promise)
+	}
+
+	/// Tests whether the artifact or dynamic state of the Builder with the
+	/// given id is recorded in this cache.
+	///
+	/// This is the id-based variant of [`is_builder_known`]. It allows to
+	/// query the state of Builders which are only tracked by their
+	/// [`BuilderId`] without retaining their promises.
+	///
+	/// Notice, that a `BuilderId` is only unique as long as its Builder is
+	/// alive, see [`BuilderId`] for details.
+	///
+	/// [`is_builder_known`]: struct.Cache.html#method.is_builder_known
+	/// [`BuilderId`]: ../struct.BuilderId.html
+	///
+	pub fn is_builder_known_id(&self, id: BuilderId) -> bool {
+		self.inner.is_builder_known_by_id(id)
+	}
+
+	/// Returns the number of strong references to the Builder with the given
+	/// id, if it is known to this `Cache`.
+	///
+	/// The `Cache` itself only keeps weak references to Builders, thus the
+	/// count consists of all the `Blueprint`s of that Builder held elsewhere,
+	/// including those held by depending Builders. A count of `0` means that
+	/// the Builder is unreachable and will be removed by the next
+	/// [`garbage_collection`].
+	///
+	/// This is useful to diagnose why `garbage_collection` does not reclaim
+	/// a Builder, i.e. someone still holds a `Blueprint` of it.
+	///
+	/// [`garbage_collection`]: struct.Cache.html#method.garbage_collection
+	///
+	pub fn builder_strong_count(&self, id: BuilderId) -> Option<usize> {
+		self.inner.builder_strong_count(id)
+	}
+
+	/// Tests whether the Artifact of the Builder with the given id is
+	/// cached in this `Cache`.
+	///
+	/// This is the id-based pendant to testing any of the `lookup*` methods
+	/// for `is_some()`, but it does not require the promise nor does it
+	/// access the Artifact. Thus it is useful for code which keeps track of
+	/// Builders only by their [`BuilderId`].
+	///
+	/// [`BuilderId`]: ../struct.BuilderId.html
+	///
+	pub fn contains_artifact_id(&self, id: BuilderId) -> bool {
+		self.inner.contains_artifact_by_id(id)
+	}
+
+	/// Tests whether an error of the given Builder is cached.
+	///
+	/// Errors are only cached for Builders opting-in via
+	/// [`Builder::cache_error`]. A cached error is kept until the Builder is
+	/// invalidated or [`clear_errors`] is called.
+	///
+	/// [`Builder::cache_error`]: ../trait.Builder.html#method.cache_error
+	/// [`clear_errors`]: struct.Cache.html#method.clear_errors
+	///
+	pub fn has_cached_error<AP: ?Sized>(
+			&self,
+			promise: &AP
+		) -> bool
+			where
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.inner.has_cached_error(promise)
+	}
+
+	/// Returns the inputs read to build the Artifact of the given Builder.
+	///
+	/// The returned [`Provenance`] lists the Builders whose Artifacts have
+	/// been resolved and whose dynamic states have been read (via
+	/// [`Resolver::my_state`]) during the build of the Artifact. This allows
+	/// tools to explain why an Artifact is what it is. Returns `None` if
+	/// there is no Artifact of the given Builder.
+	///
+	/// **Notice: This method is only available if the `provenance` feature
+	/// has been activated**.
+	///
+	/// [`Provenance`]: struct.Provenance.html
+	/// [`Resolver::my_state`]: struct.Resolver.html#method.my_state
+	///
+	#[cfg(feature = "provenance")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "provenance")))]
+	pub fn artifact_provenance<AP>(
+			&self,
+			promise: &AP
+		) -> Option<&Provenance>
+			where
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.inner.provenance(promise.id())
+	}
+
+	/// Enables or disables the precise invalidation upon dynamic state
+	/// changes.
+	///
+	/// By default, modifying the dynamic state of a Builder via
+	/// [`dyn_state_mut`] or [`BatchCtx::set_dyn_state`] invalidates its
+	/// Artifact and thus all its dependents. With precise invalidation
+	/// enabled, the Artifact is only invalidated if its [provenance] shows
+	/// that its build actually read the dynamic state (via
+	/// [`Resolver::my_state`]). Otherwise, the Artifact and its dependents
+	/// are kept, which reduces over-invalidation for Builders whose
+	/// Artifact does not depend on their dynamic state.
+	///
+	/// The tracking is coarse: reading any part of the dynamic state counts
+	/// as reading all of it, so a change of a part which has not been read
+	/// still invalidates. On the other hand, a Builder must access its
+	/// dynamic state exclusively via [`Resolver::my_state`] during its
+	/// build, since it is considered independent of its dynamic state
+	/// otherwise. Notably, the same applies if the Artifact has been
+	/// inserted manually without reading the dynamic state. In contrast, a
+	/// cached error (see [`Builder::cache_error`]) is always dropped upon a
+	/// change of the dynamic state.
+	///
+	/// **Notice: This method is only available if the `provenance` feature
+	/// has been activated**.
+	///
+	/// [`dyn_state_mut`]: struct.Cache.html#method.dyn_state_mut
+	/// [`BatchCtx::set_dyn_state`]: struct.BatchCtx.html#method.set_dyn_state
+	/// [provenance]: struct.Cache.html#method.artifact_provenance
+	/// [`Resolver::my_state`]: struct.Resolver.html#method.my_state
+	/// [`Builder::cache_error`]: ../trait.Builder.html#method.cache_error
+	///
+	#[cfg(feature = "provenance")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "provenance")))]
+	pub fn set_precise_invalidation(&mut self, enabled: bool) {
+		self.inner.set_precise_invalidation(enabled)
+	}
+
+	/// Returns the estimated number of builds per second of the given
+	/// Builder.
+	///
+	/// The rate is estimated from the times of the recent builds (up to 16)
+	/// of the Builder, including failed ones, until now. Thus it decays if
+	/// the Builder stops being rebuilt. A Builder which is rebuilt far more
+	/// often than expected indicates thrashing, typically caused by an
+	/// over-broad dependency on a frequently invalidated Builder. Returns
+	/// `0.0` if the Builder has not been built yet.
+	///
+	/// The build times are kept across invalidations, but they are removed
+	/// when the Builder is purged or forgotten by the garbage collection.
+	///
+	/// **Notice: This method is only available if the `rebuild_rate` feature
+	/// has been activated**.
+	///
+	#[cfg(feature = "rebuild_rate")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "rebuild_rate")))]
+	pub fn rebuild_rate<AP>(
+			&self,
+			promise: &AP
+		) -> f64
+			where
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.inner.rebuild_rate(promise.id())
+	}
+
+	/// Returns the Builders whose Artifacts have been fetched directly, but
+	/// which no Builder depends upon.
+	///
+	/// A Builder counts as fetched directly if its Artifact has been
+	/// requested by one of the `get` methods of this `Cache`, as opposed to
+	/// being resolved by the `Resolver` of a build. Together with
+	/// [`root_only_builders`], this gives an overview of the shape of the
+	/// dependency graph, e.g. to spot Builders which could be merged or
+	/// simplified.
+	///
+	/// Whether a Builder is depended upon is derived from the current
+	/// dependency edges, which are removed along with invalidated Artifacts.
+	/// The flag of being fetched directly is kept until the Builder is
+	/// purged or forgotten by the garbage collection.
+	///
+	/// **Notice: This method is only available if the `dag_lint` feature
+	/// has been activated**.
+	///
+	/// [`root_only_builders`]: struct.Cache.html#method.root_only_builders
+	///
+	#[cfg(feature = "dag_lint")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "dag_lint")))]
+	pub fn leaf_only_builders(&self) -> Vec<BuilderId> {
+		self.inner.leaf_only_builders()
+	}
+
+	/// Returns the known Builders which have never been fetched directly.
+	///
+	/// These are typically the Builders which are only ever resolved as
+	/// dependencies of other Builders, see [`leaf_only_builders`].
+	///
+	/// **Notice: This method is only available if the `dag_lint` feature
+	/// has been activated**.
+	///
+	/// [`leaf_only_builders`]: struct.Cache.html#method.leaf_only_builders
+	///
+	#[cfg(feature = "dag_lint")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "dag_lint")))]
+	pub fn root_only_builders(&self) -> Vec<BuilderId> {
+		self.inner.root_only_builders()
+	}
+
+	/// Returns the version of the Artifact of the given Builder.
+	///
+	/// The version is incremented each time an Artifact of the Builder is
+	/// built and inserted into this `Cache`, and it is `0` if the Builder
+	/// has never been built. Thus consumers holding a previously fetched
+	/// Artifact can detect cheaply whether it has been rebuilt since, by
+	/// comparing the versions, without comparing values or pointers.
+	///
+	/// The version is kept when the Artifact is invalidated, but it is reset
+	/// when the Builder is purged or forgotten by the garbage collection.
+	///
+	/// **Notice: This method is only available if the `artifact_version` feature
+	/// has been activated**.
+	///
+	#[cfg(feature = "artifact_version")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "artifact_version")))]
+	pub fn artifact_version<AP>(
+			&self,
+			promise: &AP
+		) -> u64
+			where
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.inner.artifact_version(promise.id())
+	}
+
+	/// Returns the pointer to the stored Artifact, if it exists.
+	///
+	/// The pointer identifies the Artifact instance, thus it allows cheap
+	/// identity checks, e.g. whether two Builders share the same Artifact,
+	/// without cloning the Bin as [`lookup`] does. It equals the pointer of
+	/// the Bin as of `Can::bin_as_ptr`, e.g. `Rc::as_ptr` when using the `rc`
+	/// module.
+	///
+	/// The returned pointer is only valid as long as the Artifact stays in
+	/// this `Cache` or in some Bin, thus it may only be used for comparing with
+	/// other pointers but dereferencing it can never be considered safe.
+	///
+	/// This method will not attempt to build the Artifact if it does not exist
+	/// already, instead `None` will be returned then.
+	///
+	/// [`lookup`]: struct.Cache.html#method.lookup
+	///
+	pub fn artifact_ptr<AP: ?Sized>(
+			&self,
+			promise: &AP
+		) -> Option<*const ()>
+			where
+				ArtCan: CanBase,
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.inner.artifact_can_by_id(promise.id())
+			.map(|can| can.can_as_ptr() as *const ())
+	}
+
+	/// Gets the stored Artifact in its Bin, if it exists.
+	///
+	/// Returns the Artifact in its Bin. That is an `Rc<B::Artifact>` when using
+	/// the `rc` module. The Bin is useful to share an identical artifact
+	/// or one that is not `Clone` when an owned value is required or lifetime
+	/// errors occur using [`lookup_ref`].
+	///
+	/// This method will not attempt to build the Artifact if it does not exist
+	/// already, instead `None` will be returned then.
+	///
+	/// For an overview of different accessor methods see [Artifact Accessors]
+	/// section of `Cache`.
+	///
+	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
+	/// [`lookup_ref`]: struct.Cache.html#method.lookup_ref
+	///
+	pub fn lookup<AP, B: ?Sized>(
+			&self,
+			promise: &AP
+		) -> Option<ArtCan::Bin>
+			where
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.lookup(promise)
+	}
+
+	/// Gets the stored Artifact by reference, if it exists.
+	///
+	/// Returns the Artifact as reference into this `Cache`. The reference is
+	/// useful to access artifact for short time, as it dose not incur any
+	/// cloning overhead, thus it is the cheapest way to access an Artifact, and
+	/// should be preferred wherever possible.
+	///
+	/// When an owned value is required instead or lifetime issues arise,
+	/// [`lookup`] and [`lookup_cloned`] are alternatives, which return a clone
+	/// of the Artifact Bin or of the Artifact itself, respectively.
+	///
+	/// Also notice, that using some special `ArtCan`s such as using the
+	/// [`boxed`] module there also exists a [`lookup_mut`] for mutable access
+	/// to the artifact stored within this `Cache`.
+	///
+	/// This method will not attempt to build the Artifact if it does not exist
+	/// already, instead `None` will be returned then.
+	///
+	/// For an overview of different accessor methods see [Artifact Accessors]
+	/// section of `Cache`.
+	///
+	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
+	/// [`lookup`]: struct.Cache.html#method.lookup
+	/// [`boxed`]: ../boxed/index.html
+	/// [`lookup_cloned`]: struct.Cache.html#method.lookup_cloned
+	/// [`lookup_mut`]: struct.Cache.html#method.lookup_mut
+	///
+	pub fn lookup_ref<AP, B: ?Sized>(
+			&self,
+			promise: &AP
+		) -> Option<&B::Artifact>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.lookup_ref(promise)
+	}
+
+	/// Gets the stored Artifact by reference along with its metadata, if it
+	/// exists.
+	///
+	/// This works like [`lookup_ref`], but wraps the reference in an
+	/// [`ArtifactRef`], which additionally provides the [`BuilderId`] of the
+	/// given Builder and the type name of the Artifact.
+	///
+	/// [`lookup_ref`]: struct.Cache.html#method.lookup_ref
+	/// [`ArtifactRef`]: struct.ArtifactRef.html
+	/// [`BuilderId`]: ../struct.BuilderId.html
+	///
+	pub fn lookup_ref_meta<AP, B: ?Sized>(
+			&self,
+			promise: &AP
+		) -> Option<ArtifactRef<'_, B::Artifact>>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.lookup_ref(promise).map(|artifact| {
+			ArtifactRef {
+				artifact,
+				id: promise.id(),
+			}
+		})
+	}
+
+
+cfg_if! {
+	if #[cfg(feature = "mut_box")] {
+
+		/// Gets the stored Artifact by mutable reference, if it exists.
+		///
+		/// Returns the Artifact as mutable reference into this `Cache`.
+		/// The mutable reference might be useful to access the Artifact in place
+		/// to mutate it.
+		///
+		/// Note: If mutation is not required [`lookup_ref`] should be
+		/// preferred. Also if mutation is conditional, first a shared reference
+		/// should be acquired via [`lookup_ref`] to test the condition and only
+		/// when necessary a mutable reference should be acquired.
+		///
+		/// **Currently, when using this method, all artifacts which depended on
+		/// accessed one will be invalidate!**
+		///
+		/// This method will not attempt to build the Artifact if it does not exist
+		/// already, instead `None` will be returned then.
+		///
+		/// For an overview of different accessor methods see [Artifact Accessors]
+		/// section of `Cache`.
+		///
+		///
+		///
+		/// # Unstable
+		///
+		/// This function should be only used with care, because currently, this
+		/// function will invalidate all depending artifacts, **but this is subject
+		/// to change**.
+		///
+		/// Therefore, **this method must be considered unstable!** The semantic of
+		/// this function might change in a breaking way within a non-breaking
+		/// version update!
+		///
+		///
+		/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
+		/// [`lookup_ref`]: struct.Cache.html#method.lookup_ref
+		///
+		#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "mut_box")))]
+		pub fn lookup_mut<AP, B: ?Sized>(
+				&mut self,
+				promise: &AP
+			) -> Option<&mut B::Artifact>
+				where
+					ArtCan: CanRefMut<B::Artifact>,
+					B: Builder<ArtCan, BCan>,
+					BCan: Can<AP::Builder>,
+					AP: Promise<Builder = B, BCan = BCan>  {
+
+			self.inner.lookup_mut(promise)
+		}
+	}
+}
+
+	/// Gets a clone of the stored Artifact, if it exists.
+	///
+	/// Returns a clone of the Artifact. The clone is useful when cloning the
+	/// Artifact itself is viable and an owned value is required or lifetime
+	/// errors occur using [`lookup_ref`].
+	///
+	/// This method will not attempt to build the Artifact if it does not exist
+	/// already, instead `None` will be returned then.
+	///
+	/// For an overview of different accessor methods see [Artifact Accessors]
+	/// section of `Cache`.
+	///
+	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
+	/// [`lookup_ref`]: struct.Cache.html#method.lookup_ref
+	///
+	pub fn lookup_cloned<AP, B: ?Sized>(
+			&self,
+			promise: &AP
+		) -> Option<B::Artifact>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				B::Artifact: Clone,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.lookup_cloned(promise)
+	}
+
+	/// Gets the Artifact in its Bin.
+	///
+	/// Returns the Artifact in its Bin. That is an `Rc<B::Artifact>` when using
+	/// the `rc` module. The Bin is useful to share an identical artifact
+	/// or one that is not `Clone` when an owned value is required or lifetime
+	/// errors occur using [`get_ref`].
+	///
+	/// This method will try to build the Artifact if it is not stored in the
+	/// `Cache`. The building using the Builder's `build` method could fail,
+	/// thus a `Result` is returned. An `Err` will be returned only, if the
+	/// Artifact was not cached and the Builder returned an `Err`.
+	///
+	/// The returned Bin shares the Artifact with this `Cache`, but it is not
+	/// tied to it. If the Artifact gets invalidated later on, the Bin stays
+	/// valid and keeps pointing at the old, now detached Artifact, while
+	/// subsequent calls return the newly built one.
+	///
+	/// For an overview of different accessor methods see [Artifact Accessors]
+	/// section of `Cache`.
+	///
+	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
+	/// [`get_ref`]: struct.Cache.html#method.get_ref
+	///
+	pub fn get<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<ArtCan::Bin, B::Err>
+			where
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.get(promise)
+	}
+
+	/// Gets the Artifact in its Bin, building declared dependencies
+	/// iteratively.
+	///
+	/// This works like [`get`], but first builds all dependencies declared
+	/// via [`Builder::declare_dependencies`], transitively, bottom-up using
+	/// an explicit work stack. Thus, when the Builders get to resolve their
+	/// dependencies, those are already cached, and the call stack depth is
+	/// bounded even for very deep dependency chains.
+	///
+	/// Dependencies which are not declared are resolved recursively as usual.
+	/// If a declared dependency fails to build, it is skipped here, and its
+	/// error surfaces when its dependent resolves it.
+	///
+	/// [`get`]: struct.Cache.html#method.get
+	/// [`Builder::declare_dependencies`]: ../trait.Builder.html#method.declare_dependencies
+	///
+	pub fn get_iterative<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<ArtCan::Bin, B::Err>
+			where
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.build_declared_dependencies(promise, || true);
+
+		self.get(promise)
+	}
+
+	/// Builds the declared dependencies of the given promise bottom-up, as
+	/// long as `proceed` returns `true`, which is checked before each build.
+	///
+	/// Returns the ids of the dependencies which have been built, and those
+	/// of the declared dependencies which are still lacking an Artifact.
+	///
+	pub(crate) fn build_declared_dependencies<AP: ?Sized>(
+			&mut self,
+			promise: &AP,
+			mut proceed: impl FnMut() -> bool,
+		) -> (Vec<BuilderId>, Vec<BuilderId>)
+			where
+				AP::Builder: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan>  {
+
+		let mut built = Vec::new();
+		let mut seen = HashSet::new();
+		let mut stack: Vec<_> = promise.builder().builder.declare_dependencies()
+			.into_iter()
+			.map(|dep| (dep, false))
+			.collect();
+
+		while let Some((dep, expanded)) = stack.pop() {
+			if self.contains_artifact_id(dep.id()) {
+				continue;
+			}
+
+			if expanded {
+				if !proceed() {
+					stack.push((dep, true));
+					break;
+				}
+				if dep.warm(self) {
+					built.push(dep.id());
+				}
+			} else if seen.insert(dep.id()) {
+				let children = dep.declared_dependencies();
+				stack.push((dep, true));
+				stack.extend(children.into_iter().map(|child| (child, false)));
+			}
+		}
+
+		// Collect the remaining dependencies, without building them
+		let mut pending = Vec::new();
+		let mut listed = HashSet::new();
+		while let Some((dep, _)) = stack.pop() {
+			if self.contains_artifact_id(dep.id()) || !listed.insert(dep.id()) {
+				continue;
+			}
+			pending.push(dep.id());
+			stack.extend(dep.declared_dependencies().into_iter().map(|child| (child, false)));
+		}
+
+		(built, pending)
+	}
+
+	/// Gets the Artifact in its Bin, providing a context to the Builders.
+	///
+	/// This works like [`get`], but the given context is accessible via
+	/// [`Resolver::ctx_mut`] to all Builders which are built during this
+	/// call, including the Builders of dependencies. This allows to inject
+	/// request-scoped resources, such as a connection, which shall not be
+	/// part of the Builders themselves.
+	///
+	/// Since the context is not part of the Builders, Artifacts are not
+	/// rebuilt if a different context is provided. The context is moved into
+	/// this `Cache` for the time of the call and returned along with the
+	/// result. If a Builder panics, the context is dropped.
+	///
+	/// [`get`]: struct.Cache.html#method.get
+	/// [`Resolver::ctx_mut`]: struct.Resolver.html#method.ctx_mut
+	///
+	pub fn get_with_ctx<Ctx, AP, B: ?Sized>(
+			&mut self,
+			promise: &AP,
+			ctx: Ctx,
+		) -> (Result<ArtCan::Bin, B::Err>, Ctx)
+			where
+				Ctx: 'static,
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.get_with_ctx(promise, ctx)
+	}
+
+	/// Gets the Artifact of an infallible Builder in its Bin.
+	///
+	/// This works like [`get`], but it is only available for Builders whose
+	/// `Err` type is [`Never`]. Since such a Builder can not fail, the Bin is
+	/// returned directly instead of a `Result`, which otherwise had to be
+	/// [unpacked].
+	///
+	/// [`get`]: struct.Cache.html#method.get
+	/// [`Never`]: ../enum.Never.html
+	/// [unpacked]: ../trait.Unpacking.html
+	///
+	pub fn build_infallible<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> ArtCan::Bin
+			where
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan, Err = Never>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.get(promise).unpack()
+	}
+
+	/// Gets a type-erased clone of the Artifact.
+	///
+	/// This is the dynamic counterpart to [`get_cloned`]. It accepts any
+	/// [`ErasedPromise`], thus it allows resolving a collection of promises
+	/// with different Artifact types uniformly. The returned Artifact (or
+	/// Builder error) has to be downcasted by the user.
+	///
+	/// [`get_cloned`]: struct.Cache.html#method.get_cloned
+	/// [`ErasedPromise`]: trait.ErasedPromise.html
+	///
+	pub fn get_erased(
+			&mut self,
+			promise: &dyn ErasedPromise<ArtCan, BCan>
+		) -> Result<ErasedArtifact, ErasedErr> {
+
+		promise.get_erased(self)
+	}
+
+	/// Builds the Artifacts of the given type-erased promises.
+	///
+	/// This is intended for eagerly building a set of heterogeneous roots,
+	/// e.g. at application startup, which the typed getters can not do at
+	/// once. Each Artifact is built only if it is not already cached, and it is
+	/// not cloned. The result contains for each root in order whether its
+	/// Artifact is available, the Builder errors are discarded.
+	///
+	pub fn warm(
+			&mut self,
+			roots: &[Box<dyn ErasedPromise<ArtCan, BCan>>]
+		) -> Vec<Result<(), ()>> {
+
+		roots.iter().map(|root| {
+			if root.warm(self) {
+				Ok(())
+			} else {
+				Err(())
+			}
+		}).collect()
+	}
+
+	/// Gets the Artifact in its Bin, failing over to a secondary Builder.
+	///
+	/// Tries to get the Artifact of the `primary` promise just like [`get`].
+	/// If that fails, the Artifact of the `secondary` promise is returned
+	/// instead, and the error of the primary one is dropped. Both promises
+	/// must produce the same Artifact type, e.g. two alternative
+	/// implementations of the same step.
+	///
+	/// Both Builders are cached normally, including a cached error of the
+	/// primary one (see [`Builder::cache_error`]). An `Err` will be returned
+	/// only, if both Builders failed, in which case the secondary's error is
+	/// returned.
+	///
+	/// [`get`]: struct.Cache.html#method.get
+	/// [`Builder::cache_error`]: ../trait.Builder.html#method.cache_error
+	///
+	pub fn get_or<AP1, AP2, B1: ?Sized, B2: ?Sized>(
+			&mut self,
+			primary: &AP1,
+			secondary: &AP2,
+		) -> Result<ArtCan::Bin, B2::Err>
+			where
+				ArtCan: CanSized<B1::Artifact>,
+				ArtCan: Clone,
+				B1: Builder<ArtCan, BCan>,
+				B2: Builder<ArtCan, BCan, Artifact = B1::Artifact>,
+				BCan: Can<AP1::Builder>,
+				BCan: Can<AP2::Builder>,
+				AP1: Promise<Builder = B1, BCan = BCan>,
+				AP2: Promise<Builder = B2, BCan = BCan>  {
+
+		self.inner.get_or(primary, secondary)
+	}
+
+	/// Creates a `Blueprint` for a new Builder and gets its Artifact.
+	///
+	/// This is a shorthand for constructing the Builder via
+	/// `builder_provider`, wrapping it into a `Blueprint`, and calling [`get`]
+	/// on it. The `Blueprint` is returned along with the result, so that the
+	/// Artifact may be accessed or invalidated later on.
+	///
+	/// [`get`]: struct.Cache.html#method.get
+	///
+	pub fn get_or_register<B, F>(
+			&mut self,
+			builder_provider: F,
+		) -> (Blueprint<B, BCan>, Result<ArtCan::Bin, B::Err>)
+			where
+				F: FnOnce() -> B,
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan>,
+				BCan: CanSized<B>,
+				BCan::Bin: AsRef<B> + Clone {
+
+		let blueprint = Blueprint::new(builder_provider());
+		let res = self.get(&blueprint);
+
+		(blueprint, res)
+	}
+
+cfg_if! {
+	if #[cfg(feature = "checked")] {
+		/// Gets the stored Artifact in its Bin, if it exists, without
+		/// panicking on a type mismatch.
+		///
+		/// This method works like [`lookup`], but instead of panicking, it
+		/// returns [`CacheError::TypeMismatch`] if the cached Artifact has not
+		/// the expected type. This can not happen unless there is a bug
+		/// (e.g. in a custom Can implementation), thus in debug builds this
+		/// method still panics in that case.
+		///
+		/// **Notice: This function is only available if the `checked` feature has been activated**.
+		///
+		/// [`lookup`]: struct.Cache.html#method.lookup
+		/// [`CacheError::TypeMismatch`]: enum.CacheError.html#variant.TypeMismatch
+		///
+		#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "checked")))]
+		pub fn checked_lookup<AP, B: ?Sized>(
+				&self,
+				promise: &AP
+			) -> Result<Option<ArtCan::Bin>, CacheError>
+				where
+					ArtCan: CanSized<B::Artifact>,
+					ArtCan: Clone,
+					B: Builder<ArtCan, BCan>,
+					BCan: Can<AP::Builder>,
+					AP: Promise<Builder = B, BCan = BCan>  {
+
+			self.inner.checked_lookup(promise)
+		}
+
+		/// Gets the Artifact in its Bin, without panicking on a type mismatch.
+		///
+		/// This method works like [`get`], but instead of panicking, it
+		/// returns [`CacheError::TypeMismatch`] if the cached Artifact has not
+		/// the expected type. This can not happen unless there is a bug
+		/// (e.g. in a custom Can implementation), thus in debug builds this
+		/// method still panics in that case.
+		///
+		/// The error of the Builder is returned as [`CacheError::Build`].
+		///
+		/// **Notice: This function is only available if the `checked` feature has been activated**.
+		///
+		/// [`get`]: struct.Cache.html#method.get
+		/// [`CacheError::TypeMismatch`]: enum.CacheError.html#variant.TypeMismatch
+		/// [`CacheError::Build`]: enum.CacheError.html#variant.Build
+		///
+		#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "checked")))]
+		pub fn checked_get<AP, B: ?Sized>(
+				&mut self,
+				promise: &AP
+			) -> Result<ArtCan::Bin, CacheError<B::Err>>
+				where
+					ArtCan: CanSized<B::Artifact>,
+					ArtCan: Clone,
+					B: Builder<ArtCan, BCan>,
+					BCan: Can<AP::Builder>,
+					AP: Promise<Builder = B, BCan = BCan>  {
+
+			self.inner.checked_get(promise)
+		}
+	}
+}
+
+	/// Gets the Artifact in its Bin, building it with the given function
+	/// instead of the Builder's `build` method.
+	///
+	/// If the Artifact is cached, it is returned as is, just like [`get`].
+	/// Otherwise, `build_fn` is called in place of the Builder's `build`
+	/// method, getting a [`Resolver`] for the Builder. Thus all Artifacts
+	/// resolved by `build_fn` are tracked as dependencies as usual. The
+	/// produced Artifact is then cached normally, i.e. subsequent calls to
+	/// `get` return it until it is invalidated, and only then the Builder's
+	/// own `build` method is used again. So the override only applies to this
+	/// very call.
+	///
+	/// This is primarily useful for testing and mocking, as it allows to
+	/// substitute the logic of a Builder without swapping its `Blueprint`.
+	///
+	/// [`get`]: struct.Cache.html#method.get
+	/// [`Resolver`]: struct.Resolver.html
+	///
+	pub fn get_overriding<AP, B: ?Sized, F>(
+			&mut self,
+			promise: &AP,
+			build_fn: F,
+		) -> Result<ArtCan::Bin, B::Err>
+			where
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>,
+				F: FnOnce(&mut Resolver<ArtCan, BCan, B::DynState>)
+					-> Result<B::Artifact, B::Err>  {
+
+		self.inner.get_overriding(promise, build_fn)
+	}
+
+	/// Creates a [`Resolver`] for the given Builder to build its Artifact
+	/// manually.
+	///
+	/// This is an escape hatch for Builders whose building is orchestrated by
+	/// external logic. The returned `Resolver` can be used to resolve
+	/// arbitrary Artifacts, which are tracked as dependencies of the given
+	/// Builder as usual. Finally, the produced Artifact is inserted via
+	/// [`Resolver::finish`]. In most cases [`get_overriding`] is the simpler
+	/// alternative.
+	///
+	/// Any cached Artifact of the given Builder is invalidated first, so that
+	/// its dependencies are tracked afresh.
+	///
+	/// The caller is responsible to keep the dependency tracking consistent,
+	/// i.e. the inserted Artifact must only depend on Artifacts resolved by
+	/// this `Resolver`. If the `Resolver` is dropped without calling `finish`,
+	/// the Builder just has no Artifact, but the tracked dependencies remain
+	/// until it is invalidated.
+	///
+	/// [`Resolver`]: struct.Resolver.html
+	/// [`Resolver::finish`]: struct.Resolver.html#method.finish
+	/// [`get_overriding`]: struct.Cache.html#method.get_overriding
+	///
+	pub fn resolver_for<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP,
+		) -> Resolver<'_, ArtCan, BCan, B::DynState>
+			where
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.invalidate(promise);
+		self.inner.resolver_for(promise)
+	}
+
+	/// Gets the Artifact in its Bin and whether it has been freshly built.
+	///
+	/// This method works like [`get`], but additionally returns `true` if
+	/// the Artifact has been built during this call, or `false` if it was
+	/// served from this `Cache`. This is useful to react only on fresh
+	/// Artifacts, e.g. to notify observers.
+	///
+	/// Notice, the flag only refers to the Artifact of the given Builder,
+	/// dependencies might be built in either case.
+	///
+	/// [`get`]: struct.Cache.html#method.get
+	///
+	pub fn get_fresh<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<(ArtCan::Bin, bool), B::Err>
+			where
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.get_fresh(promise)
+	}
+
+	/// Gets the Artifact by reference.
+	///
+	/// Returns the Artifact as reference into this `Cache`. The reference is
+	/// useful to access artifact for short time, as it dose not incur any
+	/// cloning overhead, thus it is the cheapest way to access an Artifact, and
+	/// should be preferred wherever possible.
+	///
+	/// When an owned value is required instead or lifetime issues arise,
+	/// [`get`] and [`get_cloned`] are alternatives, which return a clone
+	/// of the Artifact Bin or of the Artifact itself, respectively.
+	///
+	/// Also notice, that using some special `ArtCan`s such as using the
+	/// [`boxed`] module there also exists a [`get_mut`] for mutable access
+	/// to the artifact stored within this `Cache`.
+	///
+	/// This method will try to build the Artifact if it is not stored in the
+	/// `Cache`. The building using the Builder's `build` method could fail,
+	///  thus a `Result` is returned. An `Err` will be returned only, if the
+	/// Artifact was not cached and the Builder returned an `Err`.
+	///
+	/// For an overview of different accessor methods see [Artifact Accessors]
+	/// section of `Cache`.
+	///
+	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
+	/// [`get`]: struct.Cache.html#method.get
+	/// [`get_cloned`]: struct.Cache.html#method.get_cloned
+	/// [`boxed`]: ../boxed/index.html
+	/// [`get_mut`]: struct.Cache.html#method.get_mut
+	///
+	pub fn get_ref<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<&B::Artifact, B::Err>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.get_ref(promise)
+	}
+
+	/// Gets the Artifact and the dynamic state of a Builder by reference.
+	///
+	/// This works like [`get_ref`], but additionally returns the dynamic
+	/// state which was used to build the returned Artifact. Since both are
+	/// retrieved at once, they are guaranteed to belong together, and no
+	/// second lookup is required as when calling [`get_ref`] and
+	/// [`get_dyn_state`] one after another.
+	///
+	/// The building using the Builder's `build` method could fail,
+	/// thus a `Result` is returned. An `Err` will be returned only, if the
+	/// Artifact was not cached and the Builder returned an `Err`.
+	///
+	/// [`get_ref`]: struct.Cache.html#method.get_ref
+	/// [`get_dyn_state`]: struct.Cache.html#method.get_dyn_state
+	///
+	pub fn get_with_state<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<(&B::Artifact, &B::DynState), B::Err>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.get_with_state(promise)
+	}
+
+
+cfg_if! {
+	if #[cfg(feature = "mut_box")] {
+		/// Gets the Artifact by mutable reference.
+		///
+		/// Returns the Artifact as mutable reference into this `Cache`.
+		/// The mutable reference might be useful to access the Artifact in place
+		/// to mutate it.
+		///
+		/// Note: If mutation is not required [`get_ref`] should be
+		/// preferred. Also if mutation is conditional, first a shared reference
+		/// should be acquired via [`get_ref`] to test the condition and only
+		/// when necessary a mutable reference should be acquired.
+		///
+		/// **Currently, when using this method, all artifacts which depended on
+		/// accessed one will be invalidate!**
+		///
+		/// This method will try to build the Artifact if it is not stored in the
+		/// `Cache`. The building using the Builder's `build` method could fail,
+		///  thus a `Result` is returned. An `Err` will be returned only, if the
+		/// Artifact was not cached and the Builder returned an `Err`.
+		///
+		/// For an overview of different accessor methods see [Artifact Accessors]
+		/// section of `Cache`.
+		///
+		///
+		///
+		/// # Unstable
+		///
+		/// This function should be only used with care, because currently, this
+		/// function will invalidate all depending artifacts, **but this is subject
+		/// to change**.
+		///
+		/// Therefore, **this method must be considered unstable!** The semantic of
+		/// this function might change in a breaking way within a non-breaking
+		/// version update!
+		///
+		///
+		/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
+		/// [`get_ref`]: struct.Cache.html#method.get_ref
+		///
+		#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "mut_box")))]
+		pub fn get_mut<AP, B: ?Sized>(
+				&mut self,
+				promise: &AP
+			) -> Result<&mut B::Artifact, B::Err>
+				where
+					ArtCan: CanRefMut<B::Artifact>,
+					B: Builder<ArtCan, BCan>,
+					BCan: Can<AP::Builder>,
+					AP: Promise<Builder = B, BCan = BCan>  {
+
+			self.inner.get_mut(promise)
+		}
+	}
+}
+
+	/// Get a clone of the Artifact.
+	///
+	/// Returns a clone of the Artifact. The clone is useful when cloning the
+	/// Artifact itself is viable and an owned value is required or lifetime
+	/// errors occur using [`get_ref`].
+	///
+	/// This method will try to build the Artifact if it is not stored in the
+	/// `Cache`. The building using the Builder's `build` method could fail,
+	/// thus a `Result` is returned. An `Err` will be returned only, if the
+	/// Artifact was not cached and the Builder returned an `Err`.
+	///
+	/// For an overview of different accessor methods see [Artifact Accessors]
+	/// section of `Cache`.
+	///
+	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
+	/// [`get_ref`]: struct.Cache.html#method.get_ref
+	///
+	pub fn get_cloned<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<B::Artifact, B::Err>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				B::Artifact: Clone,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.get_cloned(promise)
+	}
+
+	/// Gets a clone of the Artifact once, without keeping it cached.
+	///
+	/// The Artifact is built if necessary, then a clone of it is taken, and
+	/// finally the Builder is [`purge`]d, i.e. its Artifact, dynamic state,
+	/// and its reference are removed from this `Cache`, regardless whether
+	/// the build succeeded. This suits transient computations, which use the
+	/// dependency resolution of a `Cache` but shall not persist in it.
+	///
+	/// Like with [`purge`], all depending Artifacts are invalidated too.
+	/// Further, the dependencies built transitively for the Builder are
+	/// purged as well, so nothing lingers. Only those dependencies which
+	/// have been known to this `Cache` before stay, since they might be
+	/// shared with other Builders.
+	///
+	/// If the Builder itself has been known to this `Cache` before, e.g.
+	/// because its dynamic state has been set, it is not purged. Instead,
+	/// only the Artifacts built by this call are removed, i.e. its own and
+	/// those of its dependencies, thus previously cached Artifacts and the
+	/// dynamic states are kept.
+	///
+	/// Notice, that this defeats the caching of the given Builder by design,
+	/// each call builds its Artifact anew, unless it was cached before.
+	///
+	/// [`purge`]: struct.Cache.html#method.purge
+	///
+	pub fn get_once<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<B::Artifact, B::Err>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				B::Artifact: Clone,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.get_once(promise)
+	}
+
+	/// Gets the Artifact as `Cow`.
+	///
+	/// This is intended for generic code which sometimes gets along with a
+	/// reference and sometimes requires an owned value, without committing to
+	/// either at the type level. The Artifact is referenced just like by
+	/// [`get_ref`], thus this method always returns `Cow::Borrowed`. Calling
+	/// `into_owned` on it yields a clone, as returned by [`get_cloned`].
+	///
+	/// This method will try to build the Artifact if it is not stored in the
+	/// `Cache`. The building using the Builder's `build` method could fail,
+	/// thus a `Result` is returned. An `Err` will be returned only, if the
+	/// Artifact was not cached and the Builder returned an `Err`.
+	///
+	/// [`get_ref`]: struct.Cache.html#method.get_ref
+	/// [`get_cloned`]: struct.Cache.html#method.get_cloned
+	///
+	pub fn get_cow<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<Cow<'_, B::Artifact>, B::Err>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				B::Artifact: Clone,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.get_ref(promise).map(Cow::Borrowed)
+	}
+
+	/// Gets the Artifact by reference and a clone of it.
+	///
+	/// This combines [`get_ref`] and [`get_cloned`] with a single lookup, for
+	/// when a quick read via the reference and an owned value for later are
+	/// both required.
+	///
+	/// This method will try to build the Artifact if it is not stored in the
+	/// `Cache`. The building using the Builder's `build` method could fail,
+	/// thus a `Result` is returned. An `Err` will be returned only, if the
+	/// Artifact was not cached and the Builder returned an `Err`.
+	///
+	/// [`get_ref`]: struct.Cache.html#method.get_ref
+	/// [`get_cloned`]: struct.Cache.html#method.get_cloned
+	///
+	pub fn get_ref_and_clone<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<(&B::Artifact, B::Artifact), B::Err>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				B::Artifact: Clone,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.get_ref_and_clone(promise)
+	}
+
+	/// Gets the dynamic state of the given builder, if any.
+	///
+	/// To initialize the dynamic state when it does not exist, use the
+	/// [`dyn_state`] method instead.
+	///
+	/// [`dyn_state`]: struct.Cache.html#method.dyn_state
+	///
+	pub fn get_dyn_state<AP, B: ?Sized>(
+			&self, promise: &AP
+		) -> Option<&B::DynState>
+			where
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.get_dyn_state(promise)
+	}
+
+	/// Gets the dynamic state of the given Builder.
+	///
+	/// This method will initialize the dynamic state if
+	/// it didn't exist yet in this `Cache`.
+	///
+	/// Alternatively, [`dyn_state_mut`] can be used to acquire mutable access
+	/// to the dynamic state in order to "reconfigure" the Builder. Or
+	/// [`get_dyn_state`] to only access the dynamic state if it already exists.
+	///
+	/// [`get_dyn_state`]: struct.Cache.html#method.get_dyn_state
+	/// [`dyn_state_mut`]: struct.Cache.html#method.dyn_state_mut
+	///
+	pub fn dyn_state<AP, B: ?Sized>(
+			&mut self, promise: &AP
+		) -> &B::DynState
+			where
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.dyn_state(promise)
+	}
+
+	/// Gets the mutable dynamic state of the given Builder.
+	///
+	/// This method will initialize the dynamic state if
+	/// it didn't exist yet in this `Cache`.
+	///
+	/// As opposed to [`dyn_state`], this method will invalidate the Artifact of
+	/// the given Builder, including all depending Artifacts.
+	///
+	/// [`dyn_state`]: struct.Cache.html#method.dyn_state
+	///
+	pub fn dyn_state_mut<AP, B: ?Sized>(
+			&mut self, promise: &AP
+		) -> &mut B::DynState
+			where
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.dyn_state_mut(promise)
+	}
+
+	/// Deletes all cached Artifacts in this cache, but keeps dynamic states.
+	///
+	/// Like with an invalidation, the Builders of the deleted Artifacts are
+	/// marked dirty, see [`rebuild_dirty`].
+	///
+	/// [`rebuild_dirty`]: struct.Cache.html#method.rebuild_dirty
+	///
+	pub fn clear_artifacts(&mut self) {
+		self.inner.clear_artifacts();
+	}
+
+	/// Deletes all cached Artifacts like [`clear_artifacts`], and reports what
+	/// has been removed.
+	///
+	/// The returned [`ClearReport`] contains the number of removed Artifacts
+	/// and dependency relations, which is useful e.g. for logging the scale
+	/// of the operation. No dynamic states are removed.
+	///
+	/// [`clear_artifacts`]: struct.Cache.html#method.clear_artifacts
+	/// [`ClearReport`]: struct.ClearReport.html
+	///
+	pub fn clear_artifacts_reporting(&mut self) -> ClearReport {
+		self.inner.clear_artifacts()
+	}
+
+	/// Deletes all cached errors in this cache, but keeps Artifacts and
+	/// dynamic states.
+	///
+	/// This forces all Builders with a cached error (see
+	/// [`Builder::cache_error`]) to be rebuilt on their next access, without
+	/// invalidating any Artifacts.
+	///
+	/// [`Builder::cache_error`]: ../trait.Builder.html#method.cache_error
+	///
+	pub fn clear_errors(&mut self) {
+		self.inner.clear_errors()
+	}
+
+	/// Returns the usage counters of this cache.
+	///
+	pub fn stats(&self) -> CacheStats {
+		self.inner.stats()
+	}
+
+	/// Resets all usage counters of this cache to zero.
+	///
+	pub fn reset_stats(&mut self) {
+		self.inner.reset_stats()
+	}
+
+	/// Registers a callback which is called whenever an Artifact is removed.
+	///
+	/// The callback gets the id of the Builder whose Artifact is removed,
+	/// regardless whether it is removed due to invalidation, purging,
+	/// clearing, or garbage collection. This allows for instance to keep an
+	/// external mirror of this cache in sync, without requiring the
+	/// **`diagnostics`** feature. Multiple callbacks may be registered, which
+	/// are called in registration order.
+	///
+	/// Also see `Builder::evict_hook` for a per-Builder hook getting the
+	/// actual Artifact.
+	///
+	pub fn on_evict(&mut self, cb: Box<dyn FnMut(BuilderId)>) {
+		self.inner.on_evict(cb)
+	}
+
+	/// Sets a sampler timing every `every_n`-th build.
+	///
+	/// The callback `f` gets the id of the sampled Builder and the duration
+	/// of its build. Only sampled builds are timed, thus this gives a
+	/// statistical insight into the build costs with little overhead, and
+	/// without requiring the **`diagnostics`** feature. Notice that the
+	/// duration of a build includes the builds of its dependencies, if they
+	/// were built on the way. A previously set sampler is replaced.
+	///
+	/// # Panics
+	///
+	/// Panics if `every_n` is zero.
+	///
+	pub fn set_build_sampler<F>(&mut self, every_n: usize, f: F)
+			where
+				F: FnMut(BuilderId, Duration) + 'static {
+
+		self.inner.set_build_sampler(every_n, Box::new(f))
+	}
+
+	/// Sets hooks which are called before and after each build.
+	///
+	/// The `before` hook gets the id of a Builder right before it builds its
+	/// Artifact, the `after` hook gets the same id right after the build
+	/// along with whether it succeeded. Since dependencies are built while
+	/// their dependent is building, the hook calls are nested accordingly.
+	/// Replaying a cached error is no build, thus it calls no hook.
+	///
+	/// This allows for cross-cutting concerns such as advancing a progress
+	/// bar or emitting tracing spans with little overhead, and without
+	/// requiring the **`diagnostics`** feature or a custom `Doctor`. The
+	/// hooks must not access this `Cache`. Previously set hooks are
+	/// replaced.
+	///
+	pub fn set_build_hooks<F, G>(&mut self, before: F, after: G)
+			where
+				F: FnMut(BuilderId) + 'static,
+				G: FnMut(BuilderId, bool) + 'static {
+
+		self.inner.set_build_hooks(Box::new(before), Box::new(after))
+	}
+
+	/// Removes the hooks set via [`set_build_hooks`], if any.
+	///
+	/// [`set_build_hooks`]: struct.Cache.html#method.set_build_hooks
+	///
+	pub fn clear_build_hooks(&mut self) {
+		self.inner.clear_build_hooks()
+	}
+
+	/// Sets the provider of deterministic seeds for the Builders.
+	///
+	/// Builders using randomness can get a seed via [`Resolver::seed`],
+	/// which is derived from the value returned by `f` for the id of the
+	/// Builder and the number of its builds in this `Cache`. Thus, given a
+	/// provider which maps the Builders to stable keys (notice that the ids
+	/// themselves differ between runs), builds become reproducible, e.g. for
+	/// snapshot testing of Artifact contents. A previously set provider is
+	/// replaced.
+	///
+	/// The seeds do not affect the caching in any way. They are entirely
+	/// opt-in, i.e. Builders which do not call [`Resolver::seed`] are not
+	/// affected at all.
+	///
+	/// [`Resolver::seed`]: struct.Resolver.html#method.seed
+	///
+	pub fn set_seed_provider<F>(&mut self, f: F)
+			where
+				F: Fn(BuilderId) -> u64 + 'static {
+
+		self.inner.set_seed_provider(Box::new(f))
+	}
+
+	/// Removes the seed provider set via [`set_seed_provider`], if any.
+	///
+	/// Afterwards, the seeds only depend on the number of builds.
+	///
+	/// [`set_seed_provider`]: struct.Cache.html#method.set_seed_provider
+	///
+	pub fn clear_seed_provider(&mut self) {
+		self.inner.clear_seed_provider()
+	}
+
+	/// Removes the sampler set via [`set_build_sampler`], if any.
+	///
+	/// [`set_build_sampler`]: struct.Cache.html#method.set_build_sampler
+	///
+	pub fn clear_build_sampler(&mut self) {
+		self.inner.clear_build_sampler()
+	}
+
+	/// Invalidates all Artifacts except for the `keep` most recently accessed
+	/// ones.
+	///
+	/// This is a simple LRU eviction, which may be called regularly to bound
+	/// the number of Artifacts in this `Cache`. As with any invalidation, the
+	/// dependents of an evicted Artifact are evicted too, even if they were
+	/// accessed recently. Thus, fewer than `keep` Artifacts might remain.
+	/// Returns the ids of all evicted Artifacts.
+	///
+	/// An Artifact is considered accessed when it is built and when it is
+	/// retrieved via the `get` methods of this `Cache` or via a `Resolver`,
+	/// but not via the `lookup` methods. Notice that this bookkeeping imposes
+	/// a small overhead on each such access: a counter increment and a
+	/// `HashMap` update.
+	///
+	/// **Notice: This method is only available if the `lru` feature
+	/// has been activated**.
+	///
+	#[cfg(feature = "lru")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "lru")))]
+	pub fn evict_lru(&mut self, keep: usize) -> Vec<BuilderId> {
+		self.inner.evict_lru(keep)
+	}
+
+	/// Returns the approximate memory used by the Artifacts in bytes.
+	///
+	/// This is the sum of the size hints, as returned by
+	/// [`Builder::artifact_size_hint`], of all Artifacts in this `Cache`.
+	///
+	/// **Notice: This method is only available if the `lru` feature
+	/// has been activated**.
+	///
+	/// [`Builder::artifact_size_hint`]: ../trait.Builder.html#method.artifact_size_hint
+	///
+	#[cfg(feature = "lru")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "lru")))]
+	pub fn approx_memory(&self) -> usize {
+		self.inner.approx_memory()
+	}
+
+	/// Limits the approximate memory used by the Artifacts.
+	///
+	/// Whenever [`approx_memory`] exceeds the given budget, this `Cache`
+	/// evicts the least recently accessed Artifacts with a non-zero size hint
+	/// until the budget is met again, i.e. it acts as LRU-cache. Evicting an
+	/// Artifact invalidates it, thus its dependents are evicted along with
+	/// it. The budget is checked right away and after each build, but
+	/// Artifacts which have just been built and their dependencies are not
+	/// evicted. Thus the budget might be exceeded temporarily.
+	///
+	/// Accessing an Artifact via the `get` methods of this `Cache` or via a
+	/// `Resolver` counts as access.
+	///
+	/// **Notice: This method is only available if the `lru` feature
+	/// has been activated**.
+	///
+	/// [`approx_memory`]: struct.Cache.html#method.approx_memory
+	///
+	#[cfg(feature = "lru")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "lru")))]
+	pub fn set_memory_budget(&mut self, budget: usize) {
+		self.inner.set_memory_budget(budget)
+	}
+
+	/// Removes the memory budget set via [`set_memory_budget`], if any.
+	///
+	/// **Notice: This method is only available if the `lru` feature
+	/// has been activated**.
+	///
+	/// [`set_memory_budget`]: struct.Cache.html#method.set_memory_budget
+	///
+	#[cfg(feature = "lru")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "lru")))]
+	pub fn clear_memory_budget(&mut self) {
+		self.inner.clear_memory_budget()
+	}
+
+	/// Groups the known Builders whose type and `Debug` output are identical.
+	///
+	/// Structurally equal Builders behind distinct `Blueprint`s build the same
+	/// Artifact twice. This helper lists such suspects, only returning groups
+	/// of at least two Builders. The `Debug` output of the Builders is only
+	/// formatted by this method, except for Builders behind unsized
+	/// `Blueprint`s, whose output is captured once, when they first become
+	/// known to this cache. Builders which are no longer referenced outside
+	/// of this cache are skipped.
+	///
+	/// **Notice: This function is only available if the `diagnostics` feature has been activated**.
+	///
+	#[cfg(feature = "diagnostics")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "diagnostics")))]
+	pub fn find_duplicate_builders(&self) -> Vec<Vec<BuilderId>> {
+		self.inner.find_duplicate_builders()
+	}
+
+	/// Returns a `Blueprint` for the given Builder, reusing the `Blueprint` of
+	/// an equal Builder if one has been interned before.
+	///
+	/// Since `Blueprint`s are identified by pointer, two `Blueprint`s of
+	/// structurally equal Builders have different Artifacts. Creating the
+	/// `Blueprint`s via this method deduplicates such Builders, thus they will
+	/// share their Artifact.
+	///
+	/// The interned `Blueprint`s are kept by this `Cache`, thus their
+	/// Builders will not be cleaned up by the [`garbage_collection`] until
+	/// [`clear_interned`] or [`clear_all`] is called.
+	///
+	/// [`garbage_collection`]: struct.Cache.html#method.garbage_collection
+	/// [`clear_interned`]: struct.Cache.html#method.clear_interned
+	/// [`clear_all`]: struct.Cache.html#method.clear_all
+	///
+	pub fn intern<B>(&mut self, builder: B) -> Blueprint<B, BCan>
+			where
+				B: Hash + Eq + Debug + 'static,
+				BCan: CanSized<B>,
+				BCan::Bin: AsRef<B> + Clone {
+
+		self.inner.intern(builder)
+	}
+
+	/// Forgets all `Blueprint`s interned via [`intern`].
+	///
+	/// This does not affect any Artifacts or dynamic states.
+	///
+	/// [`intern`]: struct.Cache.html#method.intern
+	///
+	pub fn clear_interned(&mut self) {
+		self.inner.clear_interned()
+	}
+
+	/// Imports the Artifacts, dynamic states, dependency relations, and known
+	/// Builders of `other` into this cache.
+	///
+	/// Builders which are already known to this cache are skipped, i.e. on
+	/// conflict, the Artifact and the dynamic state of this cache win. Since
+	/// the Artifacts of `other` depending on such a Builder might be built
+	/// from a different Artifact, they are not imported either, just as if
+	/// they were invalidated. The dependency relations of all Builders that
+	/// have an Artifact after the import are unioned, thus invalidating a
+	/// Builder of either cache also invalidates its dependents from the other
+	/// one.
+	///
+	/// The imported Builders remain watched (see [`watch`]), dirty, and
+	/// frozen (see [`freeze`]) if they were so in `other`.
+	///
+	/// This is useful to combine the caches of independent workers, e.g. of
+	/// several threads, which have built overlapping parts of a graph.
+	///
+	/// [`watch`]: struct.Cache.html#method.watch
+	/// [`freeze`]: struct.Cache.html#method.freeze
+	///
+	pub fn merge_from(&mut self, mut other: CacheOwned<ArtCan, BCan>) {
+		self.inner.merge_from(&mut other.inner)
+	}
+
+	/// Clears the entire cache including all kept Builders, Artifacts and
+	/// dynamic states.
+	///
+	pub fn clear_all(&mut self) {
+		self.inner.clear_all();
+	}
+
+	/// Clears the entire cache like [`clear_all`], and reports what has been
+	/// removed.
+	///
+	/// The returned [`ClearReport`] contains the number of removed Artifacts,
+	/// dynamic states, and dependency relations, which is useful e.g. for
+	/// logging the scale of the operation.
+	///
+	/// [`clear_all`]: struct.Cache.html#method.clear_all
+	/// [`ClearReport`]: struct.ClearReport.html
+	///
+	pub fn clear_all_reporting(&mut self) -> ClearReport {
+		self.inner.clear_all()
+	}
+
+	/// Deletes the artifact and the dynamic state of the given builder.
+	///
+	/// This function has the effect that all references of the given builder
+	/// held by this cache will be removed.
+	///
+	/// As a consequence, all depending Artifacts (if any) will be invalidated,
+	/// but their dynamic states will be kept.
+	///
+	/// If you only want to remove the artifact you can use [`invalidate`]
+	/// instead.
+	///
+	/// [`invalidate`]: struct.Cache.html#method.invalidate
+	///
+	pub fn purge<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		)
+			where
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.purge(promise)
+	}
+
+	/// Removes the Artifact of the given Builder from the `Cache` and
+	/// all depending Artifacts, but keep their dynamic states.
+	///
+	/// Depending Artifacts are all Artifacts, which used the former during
+	/// their building. The dependencies are automatically tracked via the
+	/// [`Resolver`] provided to the [`build`] method of a Builder.
+	///
+	/// In order to remove also the dynamic state of the given builder use the
+	/// [`purge`] method.
+	///
+	/// [`purge`]: struct.Cache.html#method.purge
+	/// [`Resolver`]: struct.Resolver.html
+	/// [`build`]: ../trait.Builder.html#tymethod.build
+	///
+	pub fn invalidate<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		)
+			where
+				B: Debug + 'static,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.invalidate(promise);
+	}
+
+	/// Invalidates like [`invalidate`] and reports what has been affected.
+	///
+	/// The returned [`InvalidationReport`] lists the Builders whose Artifacts
+	/// have been removed, and those which kept their dynamic state (which
+	/// [`invalidate`] never removes, as opposed to [`purge`]). This is useful
+	/// for verifying that invalidation behaves as intended in complex graphs.
+	///
+	/// [`invalidate`]: struct.Cache.html#method.invalidate
+	/// [`purge`]: struct.Cache.html#method.purge
+	/// [`InvalidationReport`]: struct.InvalidationReport.html
+	///
+	pub fn invalidate_report<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> InvalidationReport
+			where
+				B: Debug + 'static,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.invalidate(promise)
+	}
+
+	/// Freezes the Artifact of the given Builder, exempting it from
+	/// invalidation.
+	///
+	/// While frozen, the Artifact of the Builder is kept by [`invalidate`],
+	/// by the invalidation propagating from its dependencies, by the
+	/// eviction due to a memory budget, and by [`clear_artifacts`]. Thus its
+	/// dependents are rebuilt against the frozen Artifact. This is useful
+	/// e.g. to keep a known-good baseline Artifact during experimentation.
+	///
+	/// If the Builder has no Artifact yet, the Artifact is frozen as soon
+	/// as it is built. Only [`purge`], [`clear_all`], and the garbage
+	/// collection of the Builder remove a frozen Artifact, which also
+	/// unfreezes the Builder.
+	///
+	/// Notice, that by intent a frozen Artifact might be inconsistent with
+	/// the current dependencies and the dynamic state of its Builder. Also
+	/// [`unfreeze`] does not invalidate the Artifact, use [`invalidate`]
+	/// afterwards to catch up with the changes made in between.
+	///
+	/// [`invalidate`]: struct.Cache.html#method.invalidate
+	/// [`clear_artifacts`]: struct.Cache.html#method.clear_artifacts
+	/// [`purge`]: struct.Cache.html#method.purge
+	/// [`clear_all`]: struct.Cache.html#method.clear_all
+	/// [`unfreeze`]: struct.Cache.html#method.unfreeze
+	///
+	pub fn freeze<AP>(
+			&mut self,
+			promise: &AP
+		)
+			where
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.inner.freeze(promise)
+	}
+
+	/// Unfreezes the Artifact of the given Builder, see [`freeze`].
+	///
+	/// [`freeze`]: struct.Cache.html#method.freeze
+	///
+	pub fn unfreeze<AP>(
+			&mut self,
+			promise: &AP
+		)
+			where
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.inner.unfreeze(promise.id())
+	}
+
+	/// Tests whether the Artifact of the given Builder is frozen, see
+	/// [`freeze`].
+	///
+	/// [`freeze`]: struct.Cache.html#method.freeze
+	///
+	pub fn is_frozen<AP>(
+			&self,
+			promise: &AP
+		) -> bool
+			where
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.inner.is_frozen(promise.id())
+	}
+
+	/// Runs the given function with all dynamic states frozen.
+	///
+	/// While `f` runs, modifying any dynamic state via [`dyn_state_mut`] or
+	/// [`BatchCtx::set_dyn_state`] panics. Thus a sequence of builds within
+	/// `f` is guaranteed to see a consistent configuration, which is useful
+	/// for multi-step build sequences that assume the configuration doesn't
+	/// shift mid-way. Builders may still modify their own dynamic state from
+	/// within their build via [`Resolver::my_state`].
+	///
+	/// Panicking was chosen over queuing the modifications until `f`
+	/// returns, because [`dyn_state_mut`] hands out a reference to the
+	/// stored state, which can not be deferred. A modification within `f` is
+	/// thus considered a logic error. Nested calls are allowed, the states
+	/// are unfrozen when the outermost call returns.
+	///
+	/// [`dyn_state_mut`]: struct.Cache.html#method.dyn_state_mut
+	/// [`BatchCtx::set_dyn_state`]: struct.BatchCtx.html#method.set_dyn_state
+	/// [`Resolver::my_state`]: struct.Resolver.html#method.my_state
+	///
+	pub fn with_frozen_config<F, R>(&mut self, f: F) -> R
+			where
+				F: FnOnce(&mut Self) -> R {
+
+		let was_frozen = self.inner.freeze_config(true);
+		let scope = FrozenConfigScope {
+			cache: self,
+			was_frozen,
+		};
+
+		f(&mut *scope.cache)
+	}
+
+	/// Runs the given function with a [`BatchCtx`] deferring invalidations.
+	///
+	/// All invalidations requested through the context are deduplicated and
+	/// applied at once after `f` returned. Thus the depending Builders are
+	/// traversed only once, which is beneficial for bursts of
+	/// reconfigurations of Builders sharing many dependents.
+	///
+	/// [`BatchCtx`]: struct.BatchCtx.html
+	///
+	pub fn batch<F, R>(&mut self, f: F) -> R
+			where
+				F: FnOnce(&mut BatchCtx<ArtCan, BCan>) -> R {
+
+		let mut ctx = BatchCtx {
+			cache: &mut self.inner,
+			pending: Vec::new(),
+			pending_set: HashSet::new(),
+			#[cfg(feature = "diagnostics")]
+			diag_builders: Vec::new(),
+		};
+
+		let res = f(&mut ctx);
+
+		ctx.finish();
+
+		res
+	}
+
+	/// Removes the Artifact of the given Builder from the `Cache` and the
+	/// depending Artifacts up to `max_depth` hops away, but keep their dynamic
+	/// states.
+	///
+	/// This works like [`invalidate`], except that only the Artifacts of
+	/// Builders which depend on the given one at most over `max_depth` hops
+	/// are removed, e.g. a `max_depth` of `1` only removes the immediate
+	/// dependents. Deeper dependents stay cached, but still depend on the
+	/// invalidated Builders, i.e. they are invalidated as usual when any of
+	/// them is invalidated again.
+	///
+	/// **Use with care:** the Artifacts which are kept were built from the
+	/// now invalidated Artifacts, thus they might become inconsistent with
+	/// their rebuilt dependencies.
+	///
+	/// [`invalidate`]: struct.Cache.html#method.invalidate
+	///
+	pub fn invalidate_to_depth<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP,
+			max_depth: usize,
+		)
+			where
+				B: Debug + 'static,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.inner.invalidate_to_depth(promise, max_depth)
+	}
+
+	/// Invalidates all builders and their dyn state which can not be builded
+	/// any more, because there are no more references to them.
+	///
+	/// This function has the complexity of `O(n)` with `n` being the [number of
+	/// known builders]. Thus this function is not light-weight, but should be
+	/// called regularly at appropriate locations, i.e. where many builders
+	/// go out of scope.
+	///
+	/// If builders and dynamic states are explicitly removed before going out
+	/// of scope e.g. via [`purge`] or [`clean_all`], this method is not needed
+	/// to be called in order to prevent memory leakage. Also notice, this
+	/// method only takes efforts to reduce resource leakage, but can't give
+	/// any guarantees, nor has any functional impact.
+	///
+	/// Only those Builders will be cleaned up, for which all Cans and Bins
+	/// (e.g. `Rc`s) have been dropped. If Artifacts or dynamic states refers to
+	/// Builders, those Builders are cleaned up within the same call, as soon
+	/// as the referring Artifacts or dynamic states are removed. However, in
+	/// case of cyclic dependencies (e.g. between dynamic states and
+	/// Artifacts) they might never be cleaned by this GC.
+	///
+	/// Frozen Builders are roots of this GC, i.e. the Builders a frozen
+	/// Artifact depends on, even just weakly, are kept until it is unfrozen.
+	/// A frozen Builder itself is cleaned up as soon as it is unreachable,
+	/// which also unfreezes it, see [`freeze`].
+	///
+	/// [number of known builders]: struct.Cache.html#method.number_of_known_builders
+	/// [`purge`]: struct.Cache.html#method.purge
+	/// [`clean_all`]: struct.Cache.html#method.clean_all
+	/// [`freeze`]: struct.Cache.html#method.freeze
+	///
+	pub fn garbage_collection(&mut self) {
+		self.inner.garbage_collection();
 	}
-}
 
-	/// Gets a clone of the stored Artifact, if it exists.
+	/// Invalidates all unreachable builders and returns their ids.
 	///
-	/// Returns a clone of the Artifact. The clone is useful when cloning the
-	/// Artifact itself is viable and an owned value is required or lifetime
-	/// errors occur using [`lookup_ref`].
+	/// This runs the same collection as [`garbage_collection`], but
+	/// additionally reports which Builders have been removed, e.g. for logging
+	/// them. The ids are listed in the order of removal.
 	///
-	/// This method will not attempt to build the Artifact if it does not exist
-	/// already, instead `None` will be returned then.
+	/// [`garbage_collection`]: struct.Cache.html#method.garbage_collection
 	///
-	/// For an overview of different accessor methods see [Artifact Accessors]
-	/// section of `Cache`.
+	pub fn purge_unreachable(&mut self) -> Vec<BuilderId> {
+		self.inner.garbage_collection()
+	}
+
+	/// Registers the given Builder to be rebuilt by [`rebuild_dirty`].
 	///
-	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
-	/// [`lookup_ref`]: struct.Cache.html#method.lookup_ref
+	/// The `Cache` only keeps a weak reference to the Builder, thus watching
+	/// a Builder does not keep it alive.
 	///
-	pub fn lookup_cloned<AP, B: ?Sized>(
-			&self,
-			promise: &AP
-		) -> Option<B::Artifact>
+	/// [`rebuild_dirty`]: struct.Cache.html#method.rebuild_dirty
+	///
+	pub fn watch<B>(
+			&mut self,
+			promise: &Blueprint<B, BCan>
+		)
 			where
+				B: Builder<ArtCan, BCan>,
 				ArtCan: CanRef<B::Artifact>,
+				BCan: CanDowngrade<B>,
+				BCan::Bin: AsRef<B> + Clone {
+
+		self.inner.watch(promise)
+	}
+
+	/// Rebuilds all Artifacts invalidated since the last call.
+	///
+	/// Whenever an Artifact is removed by an invalidation, including the
+	/// propagation to its dependents, its Builder is marked dirty. A Builder
+	/// stops being dirty when its Artifact is built again or when it is
+	/// purged or garbage collected.
+	///
+	/// This method tries to rebuild the Artifact of each dirty Builder and
+	/// clears the set of dirty Builders. It returns for each of them, in the
+	/// order of their ids, whether its Artifact is available now. This allows
+	/// an incremental "refresh everything that changed" without tracking
+	/// changes manually.
+	///
+	/// Only Builders registered via [`watch`] can be rebuilt, since the
+	/// `Cache` itself has no typed access to its Builders. For all other
+	/// dirty Builders `Err(None)` is returned, while a failed build returns
+	/// its error as [`ErasedErr`], which can be downcast to the `Err` type
+	/// of the Builder.
+	///
+	/// [`watch`]: struct.Cache.html#method.watch
+	/// [`ErasedErr`]: type.ErasedErr.html
+	///
+	pub fn rebuild_dirty(&mut self) -> Vec<(BuilderId, Result<(), Option<ErasedErr>>)> {
+		self.inner.rebuild_dirty()
+	}
+
+	/// Invalidates and rebuilds the given Builder and all its transitive
+	/// dependents.
+	///
+	/// This is the complement to [`invalidate`] for proactively repopulating
+	/// the `Cache` after a known change. The `root` is rebuilt first, then
+	/// its dependents, each one after its own dependencies within the
+	/// subtree. If `stop_on_error` is set, the cascade is aborted on the
+	/// first failed build and all remaining dependents are skipped,
+	/// otherwise the remaining dependents are still attempted.
+	///
+	/// Like in [`rebuild_dirty`], dependents can only be rebuilt if they are
+	/// registered via [`watch`], otherwise they are skipped, unless they
+	/// have been rebuilt along with another dependent. The returned
+	/// [`RebuildReport`] lists which Builders have been rebuilt, which
+	/// failed, and which have been skipped.
+	///
+	/// [`invalidate`]: struct.Cache.html#method.invalidate
+	/// [`rebuild_dirty`]: struct.Cache.html#method.rebuild_dirty
+	/// [`watch`]: struct.Cache.html#method.watch
+	/// [`RebuildReport`]: struct.RebuildReport.html
+	///
+	pub fn rebuild_subtree<AP, B: ?Sized>(
+			&mut self,
+			root: &AP,
+			stop_on_error: bool,
+		) -> RebuildReport
+			where
 				B: Builder<ArtCan, BCan>,
-				B::Artifact: Clone,
+				ArtCan: CanRef<B::Artifact>,
 				BCan: Can<AP::Builder>,
 				AP: Promise<Builder = B, BCan = BCan>  {
 
-		self.inner.lookup_cloned(promise)
+		self.inner.rebuild_subtree(root, stop_on_error)
 	}
 
-	/// Gets the Artifact in its Bin.
+	/// Returns the number of currently kept artifact promises.
 	///
-	/// Returns the Artifact in its Bin. That is an `Rc<B::Artifact>` when using
-	/// the `rc` module. The Bin is useful to share an identical artifact
-	/// or one that is not `Clone` when an owned value is required or lifetime
-	/// errors occur using [`get_ref`].
+	/// This method is offered as kind of debugging or analysis tool for
+	/// keeping track of the number of active Builders.
 	///
-	/// This method will try to build the Artifact if it is not stored in the
-	/// `Cache`. The building using the Builder's `build` method could fail,
-	/// thus a `Result` is returned. An `Err` will be returned only, if the
-	/// Artifact was not cached and the Builder returned an `Err`.
+	/// When adding dynamic state or issuing the building of a promise may
+	/// increase the returned number. Like wise the purging of Builders
+	/// may decrement this count. Additionally, if there are
+	/// no more usable references to a Builder, the [`garbage_collection`]
+	/// method may reduce this number.
 	///
-	/// For an overview of different accessor methods see [Artifact Accessors]
-	/// section of `Cache`.
+	/// [`is_builder_known`] can test whether individual Builders are stored in
+	/// this cache. `number_of_known_builders` returns exactly how many
+	/// Builders exist for which `is_builder_known` returns `true`.
 	///
-	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
-	/// [`get_ref`]: struct.Cache.html#method.get_ref
+	/// [`is_builder_known`]: struct.Cache.html#method.is_builder_known
+	/// [`garbage_collection`]: struct.Cache.html#method.garbage_collection
 	///
-	pub fn get<AP, B: ?Sized>(
+	pub fn number_of_known_builders(&self) -> usize {
+		self.inner.number_of_known_builders()
+	}
+
+	/// Checks the internal invariants of this cache.
+	///
+	/// Panics with a descriptive message if any invariant is violated, e.g.
+	/// if the tracked dependencies are inconsistent. Since this indicates a
+	/// bug in this crate, this method is intended for tests, such as property
+	/// tests stressing invalidation and garbage collection.
+	///
+	#[cfg(any(test, feature = "validate"))]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "validate")))]
+	pub fn assert_consistent(&self) {
+		self.inner.assert_consistent()
+	}
+
+	/// Returns the ids of the Builders which would need to be built to
+	/// `get` the Artifact of the given promise (dry-run).
+	///
+	/// If the Artifact of the given Builder is cached, the returned list is
+	/// empty. Otherwise, the given Builder is listed first, followed by those
+	/// of its dependencies which lack an Artifact too.
+	///
+	/// **Notice: the result is only an approximation**. The `Cache` learns
+	/// the dependencies of a Builder only while it is building, or if they
+	/// are declared via [`Builder::declare_dependencies`]. Thus this method
+	/// can only follow the declared dependencies and those recorded by the
+	/// last build, both are united at every Builder. Since invalidation
+	/// drops the recorded ones, usually only the given Builder and its
+	/// declared dependencies are reported when its Artifact is missing. The
+	/// actual `get` may build further dependencies (e.g. if the Builder uses
+	/// undeclared dependencies).
+	///
+	/// This method will never build anything nor add anything to the `Cache`.
+	///
+	/// [`Builder::declare_dependencies`]: ../trait.Builder.html#method.declare_dependencies
+	///
+	pub fn plan<AP: ?Sized>(
+			&self,
+			promise: &AP
+		) -> Vec<BuilderId>
+			where
+				AP::Builder: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.inner.plan(promise)
+	}
+}
+
+
+
+
+
+/// Resolves dependent Artifacts for Builders.
+///
+/// This struct is only available to the [`build`] method of Builders. It is
+/// specific to that Builder, which will be referred to as the _owning
+/// Builder_. The `Resolver` provides limited access to the [`Cache`] for which
+/// the owning Builder builds its Artifact. This access is limited to the
+/// dynamic state of the owning Builder and the Artifacts of other builders.
+///
+/// This concept of a specific `Resolver` serves the important purpose of
+/// tracking dependencies between Artifacts. Thus all Artifacts which are
+/// retrieved through a `Resolver` create a _dependency_ of the Artifact of
+/// the owning Builder upon the resolved Artifact.
+///
+/// These tracked dependencies are used to correctly implement the Artifact
+/// invalidation of the `Cache` through [`Cache::invalidate`].
+///
+/// [`build`]: ../trait.Builder.html#tymethod.build
+/// [`Cache`]: struct.Cache.html
+/// [`Cache::invalidate`]: struct.Cache.html#method.invalidate
+///
+pub struct Resolver<'a, ArtCan, BCan: CanStrong, DynState = ()> {
+	user: BuilderEntry<BCan>,
+	cache: &'a mut RawCache<ArtCan, BCan>,
+	#[cfg(feature = "diagnostics")]
+	diag_builder: BuilderHandle<BCan>,
+	/// Builders resolved during this build, in the order of first resolution.
+	resolved: Vec<BuilderId>,
+	/// The same Builders as `resolved`, for fast lookup.
+	resolved_set: HashSet<BuilderId>,
+	/// Builders whose dyn state has been read during this build.
+	#[cfg(feature = "provenance")]
+	state_reads: Vec<BuilderId>,
+	_b: PhantomData<DynState>,
+}
+
+impl<'a, ArtCan, BCan: CanStrong, DynState> Debug for Resolver<'a, ArtCan, BCan, DynState> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		// Keep it shallow, the cache might be huge
+		write!(fmt, "Resolver {{id: {:p}, diagnostics: {}}}",
+			self.user.id(), cfg!(feature = "diagnostics"))
+	}
+}
+
+impl<'a, ArtCan, BCan, DynState> Resolver<'a, ArtCan, BCan, DynState>
+	where
+		ArtCan: Debug,
+		BCan: CanStrong,
+		DynState: 'static, {
+
+	/// Record a dependency upon the given promise.
+	///
+	fn track_dependency<AP>(
 			&mut self,
 			promise: &AP
-		) -> Result<ArtCan::Bin, B::Err>
+		)
 			where
-				ArtCan: CanSized<B::Artifact>,
-				ArtCan: Clone,
-				B: Builder<ArtCan, BCan>,
 				BCan: Can<AP::Builder>,
-				AP: Promise<Builder = B, BCan = BCan>  {
+				AP: Promise<BCan = BCan> {
 
-		self.inner.get(promise)
+		let id = promise.id();
+		if self.resolved_set.insert(id) {
+			self.resolved.push(id);
+		}
+
+		cfg_if! {
+			if #[cfg(feature = "diagnostics")] {
+				self.cache.track_dependency(
+					&self.user, &self.diag_builder, promise)
+			} else {
+				self.cache.track_dependency(
+					&self.user, promise)
+			}
+		}
 	}
 
-	/// Gets the Artifact by reference.
+
+	/// Returns the context provided via [`Cache::get_with_ctx`].
 	///
-	/// Returns the Artifact as reference into this `Cache`. The reference is
-	/// useful to access artifact for short time, as it dose not incur any
-	/// cloning overhead, thus it is the cheapest way to access an Artifact, and
-	/// should be preferred wherever possible.
+	/// Returns `None` if this build has not been triggered (directly or
+	/// indirectly) by [`Cache::get_with_ctx`] or if the context is not a
+	/// `Ctx`.
 	///
-	/// When an owned value is required instead or lifetime issues arise,
-	/// [`get`] and [`get_cloned`] are alternatives, which return a clone
-	/// of the Artifact Bin or of the Artifact itself, respectively.
+	/// [`Cache::get_with_ctx`]: struct.Cache.html#method.get_with_ctx
 	///
-	/// Also notice, that using some special `ArtCan`s such as using the
-	/// [`boxed`] module there also exists a [`get_mut`] for mutable access
-	/// to the artifact stored within this `Cache`.
+	pub fn ctx_mut<Ctx: 'static>(&mut self) -> Option<&mut Ctx> {
+		self.cache.context_mut()
+	}
+
+	/// Returns the ids of the Builders resolved so far during this build.
+	///
+	/// The ids are listed in the order in which the Builders were resolved
+	/// for the first time, each id only once. This allows a Builder to
+	/// introspect its own resolution, e.g. to assert a particular order.
+	///
+	pub fn resolved_so_far(&self) -> &[BuilderId] {
+		&self.resolved
+	}
+
+	/// Resolves an Artifact to its Bin.
+	///
+	/// Returns the Artifact in its Bin. That is an `Rc<B::Artifact>` when using
+	/// the `rc` module. The Bin is useful to share an identical artifact
+	/// or one that is not `Clone` when an owned value is required or lifetime
+	/// errors occur using [`resolve_ref`].
 	///
 	/// This method will try to build the Artifact if it is not stored in the
-	/// `Cache`. The building using the Builder's `build` method could fail,
-	///  thus a `Result` is returned. An `Err` will be returned only, if the
-	/// Artifact was not cached and the Builder returned an `Err`.
+	/// corresponding `Cache`. The building using that Builder's `build` method
+	/// could fail, thus a `Result` is returned. An `Err` will be returned
+	/// only, if the Artifact was not cached and the Builder returned an `Err`.
 	///
-	/// For an overview of different accessor methods see [Artifact Accessors]
-	/// section of `Cache`.
+	/// Resolving the same Builder repeatedly within the same `build` call is
+	/// cheap, because its dependency is tracked only by the first resolution,
+	/// thereafter the cached Bin is taken right away.
 	///
-	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
+	/// Also see the corresponding [`get`] method of `Cache`.
+	///
+	/// [`resolve_ref`]: struct.Resolver.html#method.resolve_ref
 	/// [`get`]: struct.Cache.html#method.get
-	/// [`get_cloned`]: struct.Cache.html#method.get_cloned
-	/// [`boxed`]: ../boxed/index.html
-	/// [`get_mut`]: struct.Cache.html#method.get_mut
 	///
-	pub fn get_ref<AP, B: ?Sized>(
+	pub fn resolve<AP, B: ?Sized>(
 			&mut self,
 			promise: &AP
-		) -> Result<&B::Artifact, B::Err>
+		) -> Result<ArtCan::Bin, B::Err>
 			where
-				ArtCan: CanRef<B::Artifact>,
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
 				B: Builder<ArtCan, BCan>,
 				BCan: Can<AP::Builder>,
-				AP: Promise<Builder = B, BCan = BCan>  {
-
-		self.inner.get_ref(promise)
-	}
-
+				AP: Promise<Builder = B, BCan = BCan> {
 
-cfg_if! {
-	if #[cfg(feature = "mut_box")] {
-		/// Gets the Artifact by mutable reference.
-		///
-		/// Returns the Artifact as mutable reference into this `Cache`.
-		/// The mutable reference might be useful to access the Artifact in place
-		/// to mutate it.
-		///
-		/// Note: If mutation is not required [`get_ref`] should be
-		/// preferred. Also if mutation is conditional, first a shared reference
-		/// should be acquired via [`get_ref`] to test the condition and only
-		/// when necessary a mutable reference should be acquired.
-		///
-		/// **Currently, when using this method, all artifacts which depended on
-		/// accessed one will be invalidate!**
-		///
-		/// This method will try to build the Artifact if it is not stored in the
-		/// `Cache`. The building using the Builder's `build` method could fail,
-		///  thus a `Result` is returned. An `Err` will be returned only, if the
-		/// Artifact was not cached and the Builder returned an `Err`.
-		///
-		/// For an overview of different accessor methods see [Artifact Accessors]
-		/// section of `Cache`.
-		///
-		///
-		///
-		/// # Unstable
-		///
-		/// This function should be only used with care, because currently, this
-		/// function will invalidate all depending artifacts, **but this is subject
-		/// to change**.
-		///
-		/// Therefore, **this method must be considered unstable!** The semantic of
-		/// this function might change in a breaking way within a non-breaking
-		/// version update!
-		///
-		///
-		/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
-		/// [`get_ref`]: struct.Cache.html#method.get_ref
-		///
-		#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "mut_box")))]
-		pub fn get_mut<AP, B: ?Sized>(
-				&mut self,
-				promise: &AP
-			) -> Result<&mut B::Artifact, B::Err>
-				where
-					ArtCan: CanRefMut<B::Artifact>,
-					B: Builder<ArtCan, BCan>,
-					BCan: Can<AP::Builder>,
-					AP: Promise<Builder = B, BCan = BCan>  {
+		let id = promise.id();
 
-			self.inner.get_mut(promise)
+		// The dependency has already been tracked, if it has been resolved
+		// before. Yet its artifact might have been invalidated meanwhile.
+		if self.resolved_set.contains(&id) {
+			if let Some(can) = self.cache.hit_can_by_id(id) {
+				return Ok(
+					can.clone().downcast_can()
+						.expect("Cached artifact is of invalid type")
+				);
+			}
 		}
+
+		self.track_dependency(promise);
+		self.cache.get(promise)
 	}
-}
 
-	/// Get a clone of the Artifact.
-	///
-	/// Returns a clone of the Artifact. The clone is useful when cloning the
-	/// Artifact itself is viable and an owned value is required or lifetime
-	/// errors occur using [`get_ref`].
-	///
-	/// This method will try to build the Artifact if it is not stored in the
-	/// `Cache`. The building using the Builder's `build` method could fail,
-	/// thus a `Result` is returned. An `Err` will be returned only, if the
-	/// Artifact was not cached and the Builder returned an `Err`.
+	/// Resolves an Artifact to its Bin along with the id of its Builder.
 	///
-	/// For an overview of different accessor methods see [Artifact Accessors]
-	/// section of `Cache`.
+	/// This works exactly like [`resolve`], including the dependency
+	/// tracking, but additionally returns the [`BuilderId`] of the given
+	/// promise. This allows a composite Builder to record which concrete
+	/// Builder satisfied a dependency, e.g. if the dependency is selected
+	/// dynamically like by a [`SelectBuilder`].
 	///
-	/// [Artifact Accessors]: struct.Cache.html#artifact-accessors
-	/// [`get_ref`]: struct.Cache.html#method.get_ref
+	/// [`resolve`]: struct.Resolver.html#method.resolve
+	/// [`BuilderId`]: ../struct.BuilderId.html
+	/// [`SelectBuilder`]: ../utils/struct.SelectBuilder.html
 	///
-	pub fn get_cloned<AP, B: ?Sized>(
+	pub fn resolve_with_id<AP, B: ?Sized>(
 			&mut self,
 			promise: &AP
-		) -> Result<B::Artifact, B::Err>
+		) -> Result<(BuilderId, ArtCan::Bin), B::Err>
 			where
-				ArtCan: CanRef<B::Artifact>,
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
 				B: Builder<ArtCan, BCan>,
-				B::Artifact: Clone,
 				BCan: Can<AP::Builder>,
-				AP: Promise<Builder = B, BCan = BCan>  {
+				AP: Promise<Builder = B, BCan = BCan> {
 
-		self.inner.get_cloned(promise)
+		self.resolve(promise).map(|bin| (promise.id(), bin))
 	}
 
-	/// Gets the dynamic state of the given builder, if any.
+	/// Resolves an Artifact to its Bin, falling back to its stale Artifact if
+	/// the build fails.
 	///
-	/// To initialize the dynamic state when it does not exist, use the
-	/// [`dyn_state`] method instead.
+	/// This works like [`resolve`], but if the given Builder fails to build
+	/// and it had been built successfully before, the last successfully built
+	/// Artifact is returned instead of the error. This is the per-resolution
+	/// analogue of the [`RedeemingBuilder`], which allows a composite Builder
+	/// to degrade gracefully when one of its inputs fails temporarily.
 	///
-	/// [`dyn_state`]: struct.Cache.html#method.dyn_state
+	/// In order to provide this, the `Cache` retains the last Artifact of the
+	/// given Builder upon its invalidation, starting with the first call of
+	/// this method for that Builder. The retained Artifact is dropped as
+	/// soon as a new one is built, or the Builder is purged or cleared.
 	///
-	pub fn get_dyn_state<AP, B: ?Sized>(
-			&self, promise: &AP
-		) -> Option<&B::DynState>
+	/// **Beware**, the stale Artifact reflects the state before the
+	/// invalidation, e.g. an outdated dynamic state or dependency, and its
+	/// eviction hook has already been called. Thus it must be used with care.
+	///
+	/// [`resolve`]: struct.Resolver.html#method.resolve
+	/// [`RedeemingBuilder`]: ../utils/struct.RedeemingBuilder.html
+	///
+	pub fn resolve_or_stale<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<ArtCan::Bin, B::Err>
 			where
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
 				B: Builder<ArtCan, BCan>,
 				BCan: Can<AP::Builder>,
-				AP: Promise<Builder = B, BCan = BCan>  {
+				AP: Promise<Builder = B, BCan = BCan> {
 
-		self.inner.get_dyn_state(promise)
+		let id = promise.id();
+		self.cache.keep_stale(id);
+
+		self.resolve(promise).or_else(|err| {
+			match self.cache.stale_can_by_id(id) {
+				Some(can) => Ok(
+					can.clone().downcast_can()
+						.expect("Stale artifact is of invalid type")
+				),
+				None => Err(err),
+			}
+		})
 	}
 
-	/// Gets the dynamic state of the given Builder.
+	/// Resolves the Artifact of an infallible Builder to its Bin.
 	///
-	/// This method will initialize the dynamic state if
-	/// it didn't exist yet in this `Cache`.
+	/// This works like [`resolve`], but it is only available for Builders
+	/// whose `Err` type is [`Never`], thus the Bin is returned directly
+	/// instead of a `Result`.
 	///
-	/// Alternatively, [`dyn_state_mut`] can be used to acquire mutable access
-	/// to the dynamic state in order to "reconfigure" the Builder. Or
-	/// [`get_dyn_state`] to only access the dynamic state if it already exists.
+	/// Also see the corresponding [`build_infallible`] method of `Cache`.
 	///
-	/// [`get_dyn_state`]: struct.Cache.html#method.get_dyn_state
-	/// [`dyn_state_mut`]: struct.Cache.html#method.dyn_state_mut
+	/// [`resolve`]: struct.Resolver.html#method.resolve
+	/// [`Never`]: ../enum.Never.html
+	/// [`build_infallible`]: struct.Cache.html#method.build_infallible
 	///
-	pub fn dyn_state<AP, B: ?Sized>(
-			&mut self, promise: &AP
-		) -> &B::DynState
+	pub fn resolve_infallible<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> ArtCan::Bin
 			where
-				B: Builder<ArtCan, BCan>,
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan, Err = Never>,
 				BCan: Can<AP::Builder>,
-				AP: Promise<Builder = B, BCan = BCan>  {
+				AP: Promise<Builder = B, BCan = BCan> {
 
-		self.inner.dyn_state(promise)
+		self.resolve(promise).unpack()
 	}
 
-	/// Gets the mutable dynamic state of the given Builder.
+	/// Resolves an Artifact by reference.
 	///
-	/// This method will initialize the dynamic state if
-	/// it didn't exist yet in this `Cache`.
+	/// Returns the Artifact as reference into the corresponding `Cache`. The
+	/// reference is useful to access artifact for short time, as it dose not
+	/// incur any cloning overhead, thus it is the cheapest way to access an
+	/// Artifact, and should be preferred wherever possible.
 	///
-	/// As opposed to [`dyn_state`], this method will invalidate the Artifact of
-	/// the given Builder, including all depending Artifacts.
+	/// When an owned value is required instead or lifetime issues arise,
+	/// [`resolve`] and [`resolve_cloned`] are alternatives, which return a
+	/// clone of the Artifact Bin or of the Artifact itself, respectively.
 	///
-	/// [`dyn_state`]: struct.Cache.html#method.dyn_state
+	/// This method will try to build the Artifact if it is not stored in the
+	/// `Cache`. The building using the Builder's `build` method could fail,
+	///  thus a `Result` is returned. An `Err` will be returned only, if the
+	/// Artifact was not cached and the Builder returned an `Err`.
 	///
-	pub fn dyn_state_mut<AP, B: ?Sized>(
-			&mut self, promise: &AP
-		) -> &mut B::DynState
+	/// Also see the corresponding [`get_ref`] method of `Cache`.
+	///
+	/// [`resolve`]: struct.Resolver.html#method.resolve
+	/// [`resolve_cloned`]: struct.Resolver.html#method.resolve_cloned
+	/// [`get_ref`]: struct.Cache.html#method.get_ref
+	///
+	pub fn resolve_ref<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<&B::Artifact, B::Err>
 			where
+				ArtCan: CanRef<B::Artifact>,
 				B: Builder<ArtCan, BCan>,
 				BCan: Can<AP::Builder>,
 				AP: Promise<Builder = B, BCan = BCan>  {
 
-		self.inner.dyn_state_mut(promise)
-	}
-
-	/// Deletes all cached Artifacts in this cache, but keeps dynamic states.
-	///
-	pub fn clear_artifacts(&mut self) {
-		self.inner.clear_artifacts()
-	}
-
-	/// Clears the entire cache including all kept Builders, Artifacts and
-	/// dynamic states.
-	///
-	pub fn clear_all(&mut self) {
-		self.inner.clear_all()
+		self.track_dependency(promise);
+		self.cache.get_ref(promise)
 	}
 
-	/// Deletes the artifact and the dynamic state of the given builder.
+	/// Resolves an Artifact into a clone of it.
 	///
-	/// This function has the effect that all references of the given builder
-	/// held by this cache will be removed.
+	/// Returns a clone of the Artifact. The clone is useful when cloning the
+	/// Artifact itself is viable and an owned value is required or lifetime
+	/// errors occur using [`resolve_ref`].
 	///
-	/// As a consequence, all depending Artifacts (if any) will be invalidated,
-	/// but their dynamic states will be kept.
+	/// This method will try to build the Artifact if it is not stored in the
+	/// `Cache`. The building using the Builder's `build` method could fail,
+	/// thus a `Result` is returned. An `Err` will be returned only, if the
+	/// Artifact was not cached and the Builder returned an `Err`.
 	///
-	/// If you only want to remove the artifact you can use [`invalidate`]
-	/// instead.
+	/// Also see the corresponding [`get_cloned`] method of `Cache`.
 	///
-	/// [`invalidate`]: struct.Cache.html#method.invalidate
+	/// [`resolve_ref`]: struct.Resolver.html#method.resolve_ref
+	/// [`get_cloned`]: struct.Cache.html#method.get_cloned
 	///
-	pub fn purge<AP, B: ?Sized>(
+	pub fn resolve_cloned<AP, B: ?Sized>(
 			&mut self,
 			promise: &AP
-		)
+		) -> Result<B::Artifact, B::Err>
 			where
+				ArtCan: CanRef<B::Artifact>,
 				B: Builder<ArtCan, BCan>,
+				B::Artifact: Clone,
 				BCan: Can<AP::Builder>,
 				AP: Promise<Builder = B, BCan = BCan>  {
 
-		self.inner.purge(promise)
+		self.track_dependency(promise);
+		self.cache.get_cloned(promise)
 	}
 
-	/// Removes the Artifact of the given Builder from the `Cache` and
-	/// all depending Artifacts, but keep their dynamic states.
+	/// Resolves an Artifact to its Bin, collecting a possible error.
 	///
-	/// Depending Artifacts are all Artifacts, which used the former during
-	/// their building. The dependencies are automatically tracked via the
-	/// [`Resolver`] provided to the [`build`] method of a Builder.
+	/// This works like [`resolve`], but instead of returning the error of
+	/// the Builder, it is added to `errors` (converted into `E`) and `None`
+	/// is returned. Thus a Builder with multiple fallible dependencies may
+	/// resolve all of them and report every failed one at once, e.g.:
 	///
-	/// In order to remove also the dynamic state of the given builder use the
-	/// [`purge`] method.
+	/// ```
+	/// use daab::*;
+	/// use daab::cache::MultiError;
 	///
-	/// [`purge`]: struct.Cache.html#method.purge
-	/// [`Resolver`]: struct.Resolver.html
-	/// [`build`]: ../trait.Builder.html#tymethod.build
+	/// #[derive(Debug)]
+	/// struct Checked(u32);
 	///
-	pub fn invalidate<AP, B: ?Sized>(
+	/// impl rc::Builder for Checked {
+	///     type Artifact = u32;
+	///     type DynState = ();
+	///     type Err = String;
+	///
+	///     fn build(&self, _resolver: &mut rc::Resolver) -> Result<rc::BinType<u32>, String> {
+	///         if self.0 % 2 == 0 {
+	///             Ok(self.0.into())
+	///         } else {
+	///             Err(format!("{} is odd", self.0))
+	///         }
+	///     }
+	///     fn init_dyn_state(&self) -> Self::DynState {}
+	/// }
+	///
+	/// #[derive(Debug)]
+	/// struct Sum(Vec<rc::Blueprint<Checked>>);
+	///
+	/// impl rc::Builder for Sum {
+	///     type Artifact = u32;
+	///     type DynState = ();
+	///     type Err = MultiError<String>;
+	///
+	///     fn build(&self, resolver: &mut rc::Resolver) -> Result<rc::BinType<u32>, Self::Err> {
+	///         let mut errors = MultiError::new();
+	///         let values: Vec<_> = self.0.iter()
+	///             .filter_map(|bp| resolver.resolve_collecting(bp, &mut errors))
+	///             .collect();
+	///
+	///         errors.into_result()?;
+	///
+	///         Ok(values.iter().map(|v| **v).sum::<u32>().into())
+	///     }
+	///     fn init_dyn_state(&self) -> Self::DynState {}
+	/// }
+	///
+	/// let mut cache = rc::Cache::new();
+	///
+	/// let sum = rc::Blueprint::new(Sum(vec![
+	///     rc::Blueprint::new(Checked(1)),
+	///     rc::Blueprint::new(Checked(2)),
+	///     rc::Blueprint::new(Checked(3)),
+	/// ]));
+	///
+	/// assert_eq!(2, cache.get(&sum).unwrap_err().len());
+	/// ```
+	///
+	/// [`resolve`]: struct.Resolver.html#method.resolve
+	///
+	pub fn resolve_collecting<AP, B: ?Sized, E>(
 			&mut self,
-			promise: &AP
-		)
+			promise: &AP,
+			errors: &mut MultiError<E>,
+		) -> Option<ArtCan::Bin>
 			where
-				B: Debug + 'static,
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan>,
+				B::Err: Into<E>,
 				BCan: Can<AP::Builder>,
-				AP: Promise<Builder = B, BCan = BCan>  {
+				AP: Promise<Builder = B, BCan = BCan> {
 
-		self.inner.invalidate(promise)
+		match self.resolve(promise) {
+			Ok(bin) => Some(bin),
+			Err(err) => {
+				errors.push(promise.id(), err.into());
+				None
+			}
+		}
 	}
 
-	/// Invalidates all builders and their dyn state which can not be builded
-	/// any more, because there are no more references to them.
+cfg_if! {
+	if #[cfg(feature = "mut_box")] {
+		/// Resolves an Artifact by mutable reference.
+		///
+		/// Returns the Artifact as mutable reference into the corresponding
+		/// `Cache`. This allows the owning Builder to mutate the Artifact of
+		/// its dependency in place.
+		///
+		/// **Beware, just like [`Cache::get_mut`], this invalidates all
+		/// Artifacts which depended on the accessed one, except for the owning
+		/// Builder!** Thus other Builders sharing that dependency will have to
+		/// rebuild their Artifacts, which will then observe the mutation.
+		///
+		/// This method will try to build the Artifact if it is not stored in the
+		/// `Cache`. The building using the Builder's `build` method could fail,
+		/// thus a `Result` is returned. An `Err` will be returned only, if the
+		/// Artifact was not cached and the Builder returned an `Err`.
+		///
+		/// # Unstable
+		///
+		/// Like [`Cache::get_mut`], **this method must be considered
+		/// unstable!**
+		///
+		/// [`Cache::get_mut`]: struct.Cache.html#method.get_mut
+		///
+		#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "mut_box")))]
+		pub fn resolve_mut<AP, B: ?Sized>(
+				&mut self,
+				promise: &AP
+			) -> Result<&mut B::Artifact, B::Err>
+				where
+					ArtCan: CanRefMut<B::Artifact>,
+					B: Builder<ArtCan, BCan>,
+					BCan: Can<AP::Builder>,
+					AP: Promise<Builder = B, BCan = BCan>  {
+
+			// Build & invalidate the other dependents first, the owning Builder
+			// keeps its dependency, even if it resolved it before
+			self.cache.allow_nested_build(promise.id());
+			self.cache.get_mut_by(promise, self.user.id())?;
+			self.track_dependency(promise);
+
+			Ok(
+				self.cache.artifact_can_mut_by_id(promise.id())
+					.expect("Artifact is missing right after ensuring it")
+					.downcast_can_mut()
+					.expect("Cached artifact is of invalid type")
+			)
+		}
+	}
+}
+
+	/// Returns the dynamic state of the owning Builder.
 	///
-	/// This function has the complexity of `O(n)` with `n` being the [number of
-	/// known builders]. Thus this function is not light-weight, but should be
-	/// called regularly at appropriate locations, i.e. where many builders
-	/// go out of scope.
+	/// Notice, when an Artifact needs to be builded, the dynamic state of the
+	/// respective Builder will be initialized preventively, thus this method
+	/// wan always return a dynamic state without the need to create it. In
+	/// other words when an Artifact is build, it will get an dynamic state,
+	/// regardless wether this method or and other dynamic state accessor is
+	/// ever called.
 	///
-	/// If builders and dynamic states are explicitly removed before going out
-	/// of scope e.g. via [`purge`] or [`clean_all`], this method is not needed
-	/// to be called in order to prevent memory leakage. Also notice, this
-	/// method only takes efforts to reduce resource leakage, but can't give
-	/// any guarantees, nor has any functional impact.
+	pub fn my_state(&mut self) -> &mut DynState {
+		#[cfg(feature = "provenance")]
+		{
+			if self.state_reads.is_empty() {
+				self.state_reads.push(self.user.id());
+			}
+		}
+
+		// The unwrap is safe here, because Cache ensures that a DynState exists
+		// before we comme here.
+		self.cache.dyn_state_cast_mut(self.user.id()).unwrap()
+	}
+
+	/// Returns a deterministic seed for the current build of the owning
+	/// Builder.
 	///
-	/// Only those Builders will be cleaned up, for which all Cans and Bins
-	/// (e.g. `Rc`s) have been dropped. If Artifacts or dynamic states refers to
-	/// Builders, those Builders might need additional GC cycles to be cleaned
-	/// up, or in case of cyclic dependencies (e.g. between dynamic states and
-	/// Artifacts) might never be cleaned by this GC.
+	/// The seed is derived from the value of the seed provider of the `Cache`
+	/// for the owning Builder and the number of its builds so far, including
+	/// the current one. Thus a rebuild gets a different seed, but the same
+	/// sequence of builds yields the same seeds. Without a seed provider, the
+	/// seed only depends on the number of builds.
 	///
-	/// [number of known builders]: struct.Cache.html#method.number_of_known_builders
-	/// [`purge`]: struct.Cache.html#method.purge
-	/// [`clean_all`]: struct.Cache.html#method.clean_all
+	/// See [`Cache::set_seed_provider`] for details.
 	///
-	pub fn garbage_collection(&mut self) {
-		self.inner.garbage_collection()
+	/// [`Cache::set_seed_provider`]: struct.Cache.html#method.set_seed_provider
+	///
+	pub fn seed(&self) -> u64 {
+		self.cache.seed(self.user.id())
 	}
 
-	/// Returns the number of currently kept artifact promises.
-	///
-	/// This method is offered as kind of debugging or analysis tool for
-	/// keeping track of the number of active Builders.
-	///
-	/// When adding dynamic state or issuing the building of a promise may
-	/// increase the returned number. Like wise the purging of Builders
-	/// may decrement this count. Additionally, if there are
-	/// no more usable references to a Builder, the [`garbage_collection`]
-	/// method may reduce this number.
+	/// Inserts the manually built Artifact of the owning Builder.
 	///
-	/// [`is_builder_known`] can test whether individual Builders are stored in
-	/// this cache. `number_of_known_builders` returns exactly how many
-	/// Builders exist for which `is_builder_known` returns `true`.
+	/// This finishes a manual build started by [`Cache::resolver_for`], the
+	/// given promise must be the one of the owning Builder. All Artifacts
+	/// resolved by this `Resolver` are recorded as dependencies of the
+	/// inserted one. The inserted Artifact is accounted just like a built
+	/// one, e.g. in the [`stats`] and the [`rebuild_dirty`] tracking.
 	///
-	/// [`is_builder_known`]: struct.Cache.html#method.is_builder_known
-	/// [`garbage_collection`]: struct.Cache.html#method.garbage_collection
+	/// # Panics
 	///
-	pub fn number_of_known_builders(&self) -> usize {
-		self.inner.number_of_known_builders()
-	}
-}
+	/// Panics if the given promise is not the one of the owning Builder.
+	///
+	/// [`Cache::resolver_for`]: struct.Cache.html#method.resolver_for
+	/// [`stats`]: struct.Cache.html#method.stats
+	/// [`rebuild_dirty`]: struct.Cache.html#method.rebuild_dirty
+	///
+	pub fn finish<AP, B: ?Sized>(
+			self,
+			promise: &AP,
+			artifact: ArtCan::Bin,
+		)
+			where
+				ArtCan: CanSized<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		assert_eq!(promise.id(), self.user.id(),
+			"Finishing a Resolver with a foreign promise");
+
+		self.cache.record_build(promise);
 
+		#[cfg(feature = "provenance")]
+		self.cache.set_provenance(promise.id(), Provenance {
+			artifacts: self.resolved,
+			dyn_states: self.state_reads,
+		});
 
+		self.cache.insert_built(
+			promise,
+			#[cfg(feature = "diagnostics")]
+			&self.diag_builder,
+			artifact,
+		);
+	}
+}
 
 
 
-/// Resolves dependent Artifacts for Builders.
+/// Common Artifact access of `Cache` and `Resolver`.
 ///
-/// This struct is only available to the [`build`] method of Builders. It is
-/// specific to that Builder, which will be referred to as the _owning
-/// Builder_. The `Resolver` provides limited access to the [`Cache`] for which
-/// the owning Builder builds its Artifact. This access is limited to the
-/// dynamic state of the owning Builder and the Artifacts of other builders.
+/// This trait allows writing helper functions generically over anything
+/// that can resolve Artifacts, regardless whether it is a full [`Cache`] or
+/// the [`Resolver`] of a Builder. The `resolve*` methods correspond to the
+/// `get*` methods of `Cache` and to the inherent `resolve*` methods of
+/// `Resolver`, respectively, thus when used with a `Resolver` the
+/// dependencies are tracked as usual.
 ///
-/// This concept of a specific `Resolver` serves the important purpose of
-/// tracking dependencies between Artifacts. Thus all Artifacts which are
-/// retrieved through a `Resolver` create a _dependency_ of the Artifact of
-/// the owning Builder upon the resolved Artifact.
+/// # Examples
 ///
-/// These tracked dependencies are used to correctly implement the Artifact
-/// invalidation of the `Cache` through [`Cache::invalidate`].
+/// ```
+/// use std::rc::Rc;
+/// use daab::*;
+/// use daab::cache::ResolverLike;
+///
+/// fn sum<R: ResolverLike<rc::CanType, rc::BuilderCan>>(
+///         resolver: &mut R,
+///         blueprints: &[rc::Blueprint<rc::ConstBuilder<u32>>],
+///     ) -> u32 {
+///
+///     blueprints.iter()
+///         .map(|bp| resolver.resolve_cloned(bp).unpack())
+///         .sum()
+/// }
+///
+/// let blueprints = vec![
+///     rc::Blueprint::new(rc::ConstBuilder::new(Rc::new(1))),
+///     rc::Blueprint::new(rc::ConstBuilder::new(Rc::new(2))),
+/// ];
+///
+/// let mut cache = rc::Cache::new();
+/// assert_eq!(sum(&mut cache, &blueprints), 3);
+/// ```
 ///
-/// [`build`]: ../trait.Builder.html#tymethod.build
 /// [`Cache`]: struct.Cache.html
-/// [`Cache::invalidate`]: struct.Cache.html#method.invalidate
+/// [`Resolver`]: struct.Resolver.html
 ///
-pub struct Resolver<'a, ArtCan, BCan: CanStrong, DynState = ()> {
-	user: &'a BuilderEntry<BCan>,
-	cache: &'a mut RawCache<ArtCan, BCan>,
-	#[cfg(feature = "diagnostics")]
-	diag_builder: &'a BuilderHandle<BCan>,
-	_b: PhantomData<DynState>,
-}
+pub trait ResolverLike<ArtCan, BCan: CanStrong> {
+	/// Resolves an Artifact to its Bin.
+	///
+	/// See [`Cache::get`] and [`Resolver::resolve`].
+	///
+	/// [`Cache::get`]: struct.Cache.html#method.get
+	/// [`Resolver::resolve`]: struct.Resolver.html#method.resolve
+	///
+	fn resolve<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<ArtCan::Bin, B::Err>
+			where
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>;
 
-impl<'a, ArtCan, BCan, DynState> Resolver<'a, ArtCan, BCan, DynState>
-	where
-		ArtCan: Debug,
-		BCan: CanStrong,
-		DynState: 'static, {
+	/// Resolves an Artifact by reference.
+	///
+	/// See [`Cache::get_ref`] and [`Resolver::resolve_ref`].
+	///
+	/// [`Cache::get_ref`]: struct.Cache.html#method.get_ref
+	/// [`Resolver::resolve_ref`]: struct.Resolver.html#method.resolve_ref
+	///
+	fn resolve_ref<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<&B::Artifact, B::Err>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>;
 
-	/// Record a dependency upon the given promise.
+	/// Resolves an Artifact into a clone of it.
 	///
-	fn track_dependency<AP>(
+	/// See [`Cache::get_cloned`] and [`Resolver::resolve_cloned`].
+	///
+	/// [`Cache::get_cloned`]: struct.Cache.html#method.get_cloned
+	/// [`Resolver::resolve_cloned`]: struct.Resolver.html#method.resolve_cloned
+	///
+	fn resolve_cloned<AP, B: ?Sized>(
 			&mut self,
 			promise: &AP
-		)
+		) -> Result<B::Artifact, B::Err>
 			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				B::Artifact: Clone,
 				BCan: Can<AP::Builder>,
-				AP: Promise<BCan = BCan> {
+				AP: Promise<Builder = B, BCan = BCan>;
+}
 
-		cfg_if! {
-			if #[cfg(feature = "diagnostics")] {
-				self.cache.track_dependency(
-					self.user, self.diag_builder, promise)
-			} else {
-				self.cache.track_dependency(
-					self.user, promise)
+impl<ArtCan: Debug, BCan: CanStrong + Debug> ResolverLike<ArtCan, BCan>
+		for CacheDyn<ArtCan, BCan> {
+
+	fn resolve<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<ArtCan::Bin, B::Err>
+			where
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan> {
+
+		self.get(promise)
+	}
+
+	fn resolve_ref<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<&B::Artifact, B::Err>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan> {
+
+		self.get_ref(promise)
+	}
+
+	fn resolve_cloned<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<B::Artifact, B::Err>
+			where
+				ArtCan: CanRef<B::Artifact>,
+				B: Builder<ArtCan, BCan>,
+				B::Artifact: Clone,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan> {
+
+		self.get_cloned(promise)
+	}
+}
+
+cfg_if! {
+	if #[cfg(feature = "diagnostics")] {
+		impl<ArtCan, BCan, Doc> ResolverLike<ArtCan, BCan> for Cache<ArtCan, BCan, Doc>
+			where
+				ArtCan: Debug,
+				BCan: CanStrong + Debug,
+				Doc: Doctor<ArtCan, BCan> + 'static {
+
+			fn resolve<AP, B: ?Sized>(
+					&mut self,
+					promise: &AP
+				) -> Result<ArtCan::Bin, B::Err>
+					where
+						ArtCan: CanSized<B::Artifact>,
+						ArtCan: Clone,
+						B: Builder<ArtCan, BCan>,
+						BCan: Can<AP::Builder>,
+						AP: Promise<Builder = B, BCan = BCan> {
+
+				self.get(promise)
+			}
+
+			fn resolve_ref<AP, B: ?Sized>(
+					&mut self,
+					promise: &AP
+				) -> Result<&B::Artifact, B::Err>
+					where
+						ArtCan: CanRef<B::Artifact>,
+						B: Builder<ArtCan, BCan>,
+						BCan: Can<AP::Builder>,
+						AP: Promise<Builder = B, BCan = BCan> {
+
+				self.get_ref(promise)
+			}
+
+			fn resolve_cloned<AP, B: ?Sized>(
+					&mut self,
+					promise: &AP
+				) -> Result<B::Artifact, B::Err>
+					where
+						ArtCan: CanRef<B::Artifact>,
+						B: Builder<ArtCan, BCan>,
+						B::Artifact: Clone,
+						BCan: Can<AP::Builder>,
+						AP: Promise<Builder = B, BCan = BCan> {
+
+				self.get_cloned(promise)
 			}
 		}
 	}
+}
 
+impl<'a, ArtCan, BCan, DynState> ResolverLike<ArtCan, BCan> for Resolver<'a, ArtCan, BCan, DynState>
+	where
+		ArtCan: Debug,
+		BCan: CanStrong,
+		DynState: 'static, {
 
-	/// Resolves an Artifact to its Bin.
-	///
-	/// Returns the Artifact in its Bin. That is an `Rc<B::Artifact>` when using
-	/// the `rc` module. The Bin is useful to share an identical artifact
-	/// or one that is not `Clone` when an owned value is required or lifetime
-	/// errors occur using [`resolve_ref`].
-	///
-	/// This method will try to build the Artifact if it is not stored in the
-	/// corresponding `Cache`. The building using that Builder's `build` method
-	/// could fail, thus a `Result` is returned. An `Err` will be returned
-	/// only, if the Artifact was not cached and the Builder returned an `Err`.
-	///
-	/// Also see the corresponding [`get`] method of `Cache`.
-	///
-	/// [`resolve_ref`]: struct.Resolver.html#method.resolve_ref
-	/// [`get`]: struct.Cache.html#method.get
-	///
-	pub fn resolve<AP, B: ?Sized>(
+	fn resolve<AP, B: ?Sized>(
 			&mut self,
 			promise: &AP
 		) -> Result<ArtCan::Bin, B::Err>
@@ -915,33 +3691,10 @@ impl<'a, ArtCan, BCan, DynState> Resolver<'a, ArtCan, BCan, DynState>
 				BCan: Can<AP::Builder>,
 				AP: Promise<Builder = B, BCan = BCan> {
 
-		self.track_dependency(promise);
-		self.cache.get(promise)
+		Resolver::resolve(self, promise)
 	}
 
-	/// Resolves an Artifact by reference.
-	///
-	/// Returns the Artifact as reference into the corresponding `Cache`. The
-	/// reference is useful to access artifact for short time, as it dose not
-	/// incur any cloning overhead, thus it is the cheapest way to access an
-	/// Artifact, and should be preferred wherever possible.
-	///
-	/// When an owned value is required instead or lifetime issues arise,
-	/// [`resolve`] and [`resolve_cloned`] are alternatives, which return a
-	/// clone of the Artifact Bin or of the Artifact itself, respectively.
-	///
-	/// This method will try to build the Artifact if it is not stored in the
-	/// `Cache`. The building using the Builder's `build` method could fail,
-	///  thus a `Result` is returned. An `Err` will be returned only, if the
-	/// Artifact was not cached and the Builder returned an `Err`.
-	///
-	/// Also see the corresponding [`get_ref`] method of `Cache`.
-	///
-	/// [`resolve`]: struct.Resolver.html#method.resolve
-	/// [`resolve_cloned`]: struct.Resolver.html#method.resolve_cloned
-	/// [`get_ref`]: struct.Cache.html#method.get_ref
-	///
-	pub fn resolve_ref<AP, B: ?Sized>(
+	fn resolve_ref<AP, B: ?Sized>(
 			&mut self,
 			promise: &AP
 		) -> Result<&B::Artifact, B::Err>
@@ -949,29 +3702,12 @@ impl<'a, ArtCan, BCan, DynState> Resolver<'a, ArtCan, BCan, DynState>
 				ArtCan: CanRef<B::Artifact>,
 				B: Builder<ArtCan, BCan>,
 				BCan: Can<AP::Builder>,
-				AP: Promise<Builder = B, BCan = BCan>  {
+				AP: Promise<Builder = B, BCan = BCan> {
 
-		self.track_dependency(promise);
-		self.cache.get_ref(promise)
+		Resolver::resolve_ref(self, promise)
 	}
 
-	/// Resolves an Artifact into a clone of it.
-	///
-	/// Returns a clone of the Artifact. The clone is useful when cloning the
-	/// Artifact itself is viable and an owned value is required or lifetime
-	/// errors occur using [`resolve_ref`].
-	///
-	/// This method will try to build the Artifact if it is not stored in the
-	/// `Cache`. The building using the Builder's `build` method could fail,
-	/// thus a `Result` is returned. An `Err` will be returned only, if the
-	/// Artifact was not cached and the Builder returned an `Err`.
-	///
-	/// Also see the corresponding [`get_cloned`] method of `Cache`.
-	///
-	/// [`resolve_ref`]: struct.Resolver.html#method.resolve_ref
-	/// [`get_cloned`]: struct.Cache.html#method.get_cloned
-	///
-	pub fn resolve_cloned<AP, B: ?Sized>(
+	fn resolve_cloned<AP, B: ?Sized>(
 			&mut self,
 			promise: &AP
 		) -> Result<B::Artifact, B::Err>
@@ -980,26 +3716,126 @@ impl<'a, ArtCan, BCan, DynState> Resolver<'a, ArtCan, BCan, DynState>
 				B: Builder<ArtCan, BCan>,
 				B::Artifact: Clone,
 				BCan: Can<AP::Builder>,
-				AP: Promise<Builder = B, BCan = BCan>  {
+				AP: Promise<Builder = B, BCan = BCan> {
 
-		self.track_dependency(promise);
-		self.cache.get_cloned(promise)
+		Resolver::resolve_cloned(self, promise)
 	}
+}
 
-	/// Returns the dynamic state of the owning Builder.
+
+/// A type-erased Artifact, as returned by [`Cache::get_erased`].
+///
+/// [`Cache::get_erased`]: struct.Cache.html#method.get_erased
+///
+pub type ErasedArtifact = Box<dyn Any>;
+
+/// A type-erased Builder error, as returned by [`Cache::get_erased`] and
+/// [`Cache::rebuild_dirty`].
+///
+/// [`Cache::get_erased`]: struct.Cache.html#method.get_erased
+/// [`Cache::rebuild_dirty`]: struct.Cache.html#method.rebuild_dirty
+///
+pub type ErasedErr = Box<dyn Any>;
+
+/// A type-erased promise for a cloneable Artifact.
+///
+/// This trait is implemented for all promises whose Artifacts are `Clone`,
+/// and is object safe. Thus it allows collections of promises of different
+/// Builders and Artifact types, e.g. `Vec<Box<dyn ErasedPromise<_, _>>>`,
+/// which can be resolved uniformly via [`Cache::get_erased`].
+///
+/// [`Cache::get_erased`]: struct.Cache.html#method.get_erased
+///
+pub trait ErasedPromise<ArtCan, BCan: CanStrong>: Debug {
+	/// Returns the id of the inner Builder.
 	///
-	/// Notice, when an Artifact needs to be builded, the dynamic state of the
-	/// respective Builder will be initialized preventively, thus this method
-	/// wan always return a dynamic state without the need to create it. In
-	/// other words when an Artifact is build, it will get an dynamic state,
-	/// regardless wether this method or and other dynamic state accessor is
-	/// ever called.
+	fn id(&self) -> BuilderId;
+
+	/// Gets a clone of the Artifact in a `Box<dyn Any>`.
 	///
-	pub fn my_state(&mut self) -> &mut DynState {
-		// The unwrap is safe here, because Cache ensures that a DynState exists
-		// before we comme here.
-		self.cache.dyn_state_cast_mut(self.user.id()).unwrap()
+	/// Use [`Cache::get_erased`] instead.
+	///
+	/// [`Cache::get_erased`]: struct.Cache.html#method.get_erased
+	///
+	fn get_erased(&self, cache: &mut CacheDyn<ArtCan, BCan>) -> Result<ErasedArtifact, ErasedErr>;
+
+	/// Builds the Artifact, if not already cached, and returns whether it is
+	/// available.
+	///
+	/// Use [`Cache::warm`] instead.
+	///
+	/// [`Cache::warm`]: struct.Cache.html#method.warm
+	///
+	fn warm(&self, cache: &mut CacheDyn<ArtCan, BCan>) -> bool;
+}
+
+impl<AP, ArtCan, BCan> ErasedPromise<ArtCan, BCan> for AP
+	where
+		AP: Promise<BCan = BCan> + Debug,
+		AP::Builder: Builder<ArtCan, BCan>,
+		<AP::Builder as Builder<ArtCan, BCan>>::Artifact: Clone,
+		ArtCan: CanRef<<AP::Builder as Builder<ArtCan, BCan>>::Artifact>,
+		BCan: Can<AP::Builder>,
+		BCan: CanStrong + Debug, {
+
+	fn id(&self) -> BuilderId {
+		Promise::id(self)
+	}
+
+	fn get_erased(&self, cache: &mut CacheDyn<ArtCan, BCan>) -> Result<ErasedArtifact, ErasedErr> {
+		cache.get_cloned(self)
+			.map(|art| Box::new(art) as ErasedArtifact)
+			.map_err(|err| Box::new(err) as ErasedErr)
+	}
+
+	fn warm(&self, cache: &mut CacheDyn<ArtCan, BCan>) -> bool {
+		cache.get_ref(self).is_ok()
 	}
 }
 
+/// A type-erased promise declared as dependency of a Builder.
+///
+/// This trait is implemented for all promises, and is object safe. It is
+/// returned by [`Builder::declare_dependencies`] and allows
+/// [`Cache::get_iterative`] to traverse and build the declared dependencies
+/// without knowing their Builder types.
+///
+/// [`Builder::declare_dependencies`]: ../trait.Builder.html#method.declare_dependencies
+/// [`Cache::get_iterative`]: struct.Cache.html#method.get_iterative
+///
+pub trait DeclaredDependency<ArtCan, BCan: CanStrong>: Debug {
+	/// Returns the id of the inner Builder.
+	///
+	fn id(&self) -> BuilderId;
+
+	/// Returns the dependencies declared by the inner Builder.
+	///
+	fn declared_dependencies(&self) -> Vec<Box<dyn DeclaredDependency<ArtCan, BCan>>>;
+
+	/// Builds the Artifact, if not already cached, and returns whether it is
+	/// available.
+	///
+	fn warm(&self, cache: &mut CacheDyn<ArtCan, BCan>) -> bool;
+}
+
+impl<AP, ArtCan, BCan> DeclaredDependency<ArtCan, BCan> for AP
+	where
+		AP: Promise<BCan = BCan> + Debug,
+		AP::Builder: Builder<ArtCan, BCan>,
+		ArtCan: CanRef<<AP::Builder as Builder<ArtCan, BCan>>::Artifact>,
+		BCan: Can<AP::Builder>,
+		BCan: CanStrong + Debug, {
+
+	fn id(&self) -> BuilderId {
+		Promise::id(self)
+	}
+
+	fn declared_dependencies(&self) -> Vec<Box<dyn DeclaredDependency<ArtCan, BCan>>> {
+		self.builder().builder.declare_dependencies()
+	}
+
+	fn warm(&self, cache: &mut CacheDyn<ArtCan, BCan>) -> bool {
+		cache.get_ref(self).is_ok()
+	}
+}
 
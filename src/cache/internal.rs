@@ -2,13 +2,21 @@
 
 
 use std::any::Any;
+use std::any::TypeId;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::hash_map::RandomState;
 use std::fmt;
 use std::fmt::Debug;
+use std::hash::BuildHasher;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::marker::PhantomData;
+use std::rc::Rc;
+use std::time::Duration;
+use std::time::Instant;
 
 use cfg_if::cfg_if;
 
@@ -17,13 +25,25 @@ use crate::CanSized;
 use crate::CanRef;
 use crate::Can;
 use crate::CanRefMut;
+use crate::canning::CanDowngrade;
 
 use crate::Promise;
+use crate::Blueprint;
 
 use crate::Builder;
 use crate::BuilderId;
 
 use super::Resolver;
+#[cfg(feature = "checked")]
+use super::CacheError;
+use super::ArtifactStore;
+use super::CacheStats;
+use super::ClearReport;
+use super::InvalidationReport;
+use super::RebuildReport;
+use super::ErasedErr;
+#[cfg(feature = "provenance")]
+use super::Provenance;
 
 
 
@@ -53,7 +73,7 @@ impl<BCan: CanStrong> BuilderEntry<BCan> {
 	/// The id uniquely identifies the underlying builder.
 	///
 	pub(crate) fn id(&self) -> BuilderId {
-		BuilderId::new(self.builder.can_as_ptr())
+		BuilderId::new(self.builder.can_as_ptr(), || self.builder.can_liveness())
 	}
 }
 
@@ -80,6 +100,553 @@ impl<BCan: CanStrong> fmt::Pointer for BuilderEntry<BCan> {
 
 
 
+/// Times every n-th build of a `RawCache`.
+///
+struct BuildSampler {
+	/// The sampling interval.
+	every_n: usize,
+
+	/// The number of builds since this sampler has been set.
+	count: usize,
+
+	/// The callback receiving the sampled builds.
+	f: Box<dyn FnMut(BuilderId, Duration)>,
+}
+
+/// Callbacks invoked around each build of a `RawCache`.
+///
+struct BuildHooks {
+	/// Called right before a builder builds.
+	before: Box<dyn FnMut(BuilderId)>,
+
+	/// Called right after a builder built, with whether it succeeded.
+	after: Box<dyn FnMut(BuilderId, bool)>,
+}
+
+/// Scope of a single build of a `RawCache`, see `RawCache::build_with`.
+///
+/// Restores the build state of the cache when dropped, thus even if the
+/// builder panics.
+///
+struct BuildScope<'r, 'a, ArtCan, BCan: CanStrong, DynState> {
+	resolver: &'r mut Resolver<'a, ArtCan, BCan, DynState>,
+}
+
+impl<'r, 'a, ArtCan, BCan: CanStrong, DynState> BuildScope<'r, 'a, ArtCan, BCan, DynState> {
+	/// Enters the build of the given builder using the given resolver.
+	///
+	fn enter(resolver: &'r mut Resolver<'a, ArtCan, BCan, DynState>, bid: BuilderId) -> Self {
+		resolver.cache.extras.building.push(bid);
+
+		BuildScope {
+			resolver,
+		}
+	}
+}
+
+impl<'r, 'a, ArtCan, BCan: CanStrong, DynState> Drop for BuildScope<'r, 'a, ArtCan, BCan, DynState> {
+	fn drop(&mut self) {
+		let cache = &mut *self.resolver.cache;
+
+		cache.extras.building.pop();
+		cache.extras.resolving = None;
+	}
+}
+
+/// Scope of a `RawCache::get_with_ctx` call.
+///
+/// Restores the enclosing context of the cache when dropped, thus even if a
+/// builder panics.
+///
+struct ContextScope<'c, ArtCan, BCan: CanStrong> {
+	cache: &'c mut RawCache<ArtCan, BCan>,
+
+	/// The context enclosing this one, if any.
+	outer: Option<Box<dyn Any>>,
+}
+
+impl<'c, ArtCan, BCan: CanStrong> ContextScope<'c, ArtCan, BCan> {
+	/// Provides the given context to the builds of the given cache.
+	///
+	fn enter(cache: &'c mut RawCache<ArtCan, BCan>, ctx: Box<dyn Any>) -> Self {
+		let outer = cache.extras.context.replace(ctx);
+
+		ContextScope {
+			cache,
+			outer,
+		}
+	}
+
+	/// Takes back the context provided by `enter`.
+	///
+	fn leave(self) -> Box<dyn Any> {
+		self.cache.extras.context.take()
+			.expect("Build context is missing")
+	}
+}
+
+impl<'c, ArtCan, BCan: CanStrong> Drop for ContextScope<'c, ArtCan, BCan> {
+	fn drop(&mut self) {
+		self.cache.extras.context = self.outer.take();
+	}
+}
+
+/// Rebuilds the artifact of a watched builder, see `RawCache::watch`.
+///
+/// Returns `None` if the builder is gone, otherwise the result of the build
+/// with its error erased.
+///
+type Rebuilder<ArtCan, BCan> =
+	Box<dyn Fn(&mut RawCache<ArtCan, BCan>) -> Option<Result<(), ErasedErr>>>;
+
+/// The hasher of the maps and sets of a `RawCache`.
+///
+/// The hasher given to `CacheOwned::with_hasher` is erased into this state, so
+/// that it shows up neither in the type of the cache nor in the signatures of
+/// builders and resolvers. The keys of these maps are single integers (i.e.
+/// `BuilderId`s and `TypeId`s), which an `ErasedHash` folds into one word
+/// before handing it to the custom hasher.
+///
+#[derive(Clone)]
+pub(crate) enum ErasedState {
+	/// The hasher of the std `HashMap`, which is the default.
+	Std(RandomState),
+
+	/// A custom hasher, hashing the folded word.
+	Custom(Rc<dyn Fn(u64) -> u64>),
+}
+
+impl ErasedState {
+	/// Erases the given hasher.
+	///
+	pub(crate) fn new<S: BuildHasher + 'static>(hasher: S) -> Self {
+		ErasedState::Custom(Rc::new(move |word| {
+			let mut hash = hasher.build_hasher();
+			hash.write_u64(word);
+			hash.finish()
+		}))
+	}
+}
+
+impl Default for ErasedState {
+	fn default() -> Self {
+		ErasedState::Std(RandomState::new())
+	}
+}
+
+impl BuildHasher for ErasedState {
+	type Hasher = ErasedHash;
+
+	fn build_hasher(&self) -> ErasedHash {
+		match self {
+			ErasedState::Std(state) => ErasedHash::Std(state.build_hasher()),
+			ErasedState::Custom(hash) => ErasedHash::Custom(hash.clone(), 0),
+		}
+	}
+}
+
+/// The `Hasher` created by an `ErasedState`.
+///
+pub(crate) enum ErasedHash {
+	/// The hasher of the std `HashMap`.
+	Std(DefaultHasher),
+
+	/// The custom hasher and the word folded so far.
+	Custom(Rc<dyn Fn(u64) -> u64>, u64),
+}
+
+impl ErasedHash {
+	/// Folds a word into the state, keeping a single word as is.
+	///
+	fn fold(state: &mut u64, word: u64) {
+		*state = state.rotate_left(5) ^ word;
+	}
+}
+
+impl Hasher for ErasedHash {
+	fn write(&mut self, bytes: &[u8]) {
+		match self {
+			ErasedHash::Std(hasher) => hasher.write(bytes),
+			ErasedHash::Custom(_, state) => {
+				for chunk in bytes.chunks(8) {
+					let mut word = [0; 8];
+					word[..chunk.len()].copy_from_slice(chunk);
+					Self::fold(state, u64::from_le_bytes(word));
+				}
+			}
+		}
+	}
+
+	fn write_u64(&mut self, i: u64) {
+		match self {
+			ErasedHash::Std(hasher) => hasher.write_u64(i),
+			ErasedHash::Custom(_, state) => Self::fold(state, i),
+		}
+	}
+
+	fn write_usize(&mut self, i: usize) {
+		match self {
+			ErasedHash::Std(hasher) => hasher.write_usize(i),
+			ErasedHash::Custom(_, state) => Self::fold(state, i as u64),
+		}
+	}
+
+	fn finish(&self) -> u64 {
+		match self {
+			ErasedHash::Std(hasher) => hasher.finish(),
+			ErasedHash::Custom(hash, state) => hash(*state),
+		}
+	}
+}
+
+/// The artifact store of a `RawCache`.
+///
+/// The default map is kept apart from custom stores, since only boxing the
+/// latter requires the artifact can to be `'static`.
+///
+enum Artifacts<ArtCan> {
+	/// The default store.
+	Map(HashMap<BuilderId, ArtCan, ErasedState>),
+
+	/// A custom store, see `RawCache::set_store`.
+	Store(Box<dyn ArtifactStore<ArtCan>>),
+}
+
+impl<ArtCan> Artifacts<ArtCan> {
+	/// Returns the underlying store.
+	///
+	fn store(&self) -> &dyn ArtifactStore<ArtCan> {
+		match self {
+			Artifacts::Map(map) => map,
+			Artifacts::Store(store) => &**store,
+		}
+	}
+}
+
+// Matches per method instead of going through `store`, so that the default
+// map is accessed statically and only custom stores are dispatched dynamically.
+impl<ArtCan> ArtifactStore<ArtCan> for Artifacts<ArtCan> {
+	fn insert(&mut self, id: BuilderId, can: ArtCan) -> Option<ArtCan> {
+		match self {
+			Artifacts::Map(map) => map.insert(id, can),
+			Artifacts::Store(store) => store.insert(id, can),
+		}
+	}
+
+	fn get(&self, id: BuilderId) -> Option<&ArtCan> {
+		match self {
+			Artifacts::Map(map) => map.get(&id),
+			Artifacts::Store(store) => store.get(id),
+		}
+	}
+
+	fn get_mut(&mut self, id: BuilderId) -> Option<&mut ArtCan> {
+		match self {
+			Artifacts::Map(map) => map.get_mut(&id),
+			Artifacts::Store(store) => store.get_mut(id),
+		}
+	}
+
+	fn remove(&mut self, id: BuilderId) -> Option<ArtCan> {
+		match self {
+			Artifacts::Map(map) => map.remove(&id),
+			Artifacts::Store(store) => store.remove(id),
+		}
+	}
+
+	fn contains(&self, id: BuilderId) -> bool {
+		match self {
+			Artifacts::Map(map) => map.contains_key(&id),
+			Artifacts::Store(store) => store.contains(id),
+		}
+	}
+
+	fn clear(&mut self) {
+		match self {
+			Artifacts::Map(map) => map.clear(),
+			Artifacts::Store(store) => store.clear(),
+		}
+	}
+
+	fn len(&self) -> usize {
+		match self {
+			Artifacts::Map(map) => map.len(),
+			Artifacts::Store(store) => store.len(),
+		}
+	}
+
+	fn is_empty(&self) -> bool {
+		match self {
+			Artifacts::Map(map) => map.is_empty(),
+			Artifacts::Store(store) => store.is_empty(),
+		}
+	}
+
+	fn ids(&self) -> Box<dyn Iterator<Item = BuilderId> + '_> {
+		match self {
+			Artifacts::Map(map) => Box::new(map.keys().copied()),
+			Artifacts::Store(store) => store.ids(),
+		}
+	}
+}
+
+/// The `Debug` text of a builder, see `RawCache::find_duplicate_builders`.
+///
+#[cfg(feature = "diagnostics")]
+enum BuilderText<BCan> {
+	/// The text, formatted when the builder became known.
+	Eager(String),
+
+	/// Formats the text given the can of the builder, see
+	/// `Promise::debug_fn`.
+	Lazy(fn(BCan) -> Option<String>),
+}
+
+/// Formats an artifact store like a map of its artifacts.
+///
+struct ArtifactsDebug<'a, ArtCan>(&'a dyn ArtifactStore<ArtCan>);
+
+impl<ArtCan: Debug> Debug for ArtifactsDebug<'_, ArtCan> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_map()
+			.entries(self.0.ids().filter_map(|bid| self.0.get(bid).map(|can| (bid, can))))
+			.finish()
+	}
+}
+
+/// The number of recent builds per builder considered by `rebuild_rate`.
+///
+#[cfg(feature = "rebuild_rate")]
+const REBUILD_WINDOW: usize = 16;
+
+/// The bookkeeping of a `RawCache` beyond its artifacts, dyn states, and
+/// dependency graph.
+///
+/// This state backs the additional features of the cache, e.g. error
+/// caching, rebuilders, or the build hooks. It is created at once by
+/// `Extras::with_hasher`, so that all constructors of `RawCache` share it.
+///
+struct Extras<ArtCan, BCan>
+		where BCan: CanStrong {
+
+	/// Maps builder id to their cached error, for builders opting-in.
+	///
+	/// An error is kept until the respective builder is invalidated, just
+	/// like an artifact.
+	///
+	errors: HashMap<BuilderId, Box<dyn Any>, ErasedState>,
+
+	/// Maps builder id to the eviction hook of their artifact, for builders
+	/// opting-in.
+	///
+	evict_hooks: HashMap<BuilderId, fn(&ArtCan), ErasedState>,
+
+	/// User callbacks called with the builder id of each removed artifact.
+	///
+	evict_callbacks: Vec<Box<dyn FnMut(BuilderId)>>,
+
+	/// Maps builder id to the dependencies it declared as weak, for builders
+	/// with an artifact.
+	///
+	/// A builder whose dependents all refer to it weakly is subject to the
+	/// garbage collection just like a leaf builder.
+	///
+	weak_dependencies: HashMap<BuilderId, HashSet<BuilderId, ErasedState>, ErasedState>,
+
+	/// The sampler timing every n-th build, if any.
+	///
+	build_sampler: Option<BuildSampler>,
+
+	/// The hooks called around each build, if any.
+	///
+	build_hooks: Option<BuildHooks>,
+
+	/// Provides the base of the seeds handed out by `Resolver::seed`, if any.
+	///
+	seed_provider: Option<Box<dyn Fn(BuilderId) -> u64>>,
+
+	/// The number of builds of each known builder, including a build in
+	/// progress.
+	///
+	build_generations: HashMap<BuilderId, u64, ErasedState>,
+
+	/// The ids of the declared dependencies of each built builder, if any.
+	///
+	/// Unlike the declarations themselves, these are available to `plan`
+	/// for builders which are only reached by recorded edges.
+	///
+	declared: HashMap<BuilderId, Vec<BuilderId>, ErasedState>,
+
+	/// The start times of the most recent builds of each known builder, at
+	/// most `REBUILD_WINDOW` per builder.
+	///
+	#[cfg(feature = "rebuild_rate")]
+	build_times: HashMap<BuilderId, VecDeque<Instant>, ErasedState>,
+
+	/// The builders whose artifacts have been fetched directly, i.e. not by
+	/// the resolver of a build.
+	///
+	#[cfg(feature = "dag_lint")]
+	directly_fetched: HashSet<BuilderId, ErasedState>,
+
+	/// The number of artifacts inserted for each known builder.
+	///
+	#[cfg(feature = "artifact_version")]
+	artifact_versions: HashMap<BuilderId, u64, ErasedState>,
+
+	/// The context provided to the builds of the current `get_with_ctx`
+	/// call, if any.
+	///
+	context: Option<Box<dyn Any>>,
+
+	/// The builders whose artifacts have been invalidated since the last
+	/// `rebuild_dirty` call.
+	///
+	dirty: HashSet<BuilderId, ErasedState>,
+
+	/// The rebuilders of the watched builders.
+	///
+	rebuilders: HashMap<BuilderId, Rebuilder<ArtCan, BCan>, ErasedState>,
+
+	/// The builders whose last artifact is retained upon its removal, see
+	/// `Resolver::resolve_or_stale`.
+	///
+	keep_stale: HashSet<BuilderId, ErasedState>,
+
+	/// The last artifacts of the builders in `keep_stale`, which have been
+	/// removed since.
+	///
+	stale: HashMap<BuilderId, ArtCan, ErasedState>,
+
+	/// The size hint of each artifact with a non-zero size hint.
+	///
+	#[cfg(feature = "lru")]
+	artifact_sizes: HashMap<BuilderId, usize, ErasedState>,
+
+	/// The time of the last access of each artifact.
+	///
+	#[cfg(feature = "lru")]
+	last_access: HashMap<BuilderId, u64, ErasedState>,
+
+	/// The artifacts and dyn states read to build each artifact.
+	///
+	#[cfg(feature = "provenance")]
+	provenances: HashMap<BuilderId, Provenance, ErasedState>,
+
+	/// Whether a dyn state change only invalidates an artifact which has
+	/// read that dyn state, according to its provenance.
+	///
+	#[cfg(feature = "provenance")]
+	precise_invalidation: bool,
+
+	/// The sum of the size hints of all artifacts.
+	///
+	#[cfg(feature = "lru")]
+	total_size: usize,
+
+	/// The limit for `total_size`, if any.
+	///
+	#[cfg(feature = "lru")]
+	memory_budget: Option<usize>,
+
+	/// The logical clock for the access times of the artifacts.
+	///
+	#[cfg(feature = "lru")]
+	access_clock: u64,
+
+	/// The builders whose builds are currently in progress, the innermost
+	/// one last.
+	///
+	building: Vec<BuilderId>,
+
+	/// The builder which may be built while the innermost build is in
+	/// progress, because it is being resolved by the resolver of that build.
+	///
+	/// Any other build during that time is a reentrant access of the cache.
+	///
+	resolving: Option<BuilderId>,
+
+	/// The builders whose artifacts are exempt from invalidation.
+	///
+	frozen: HashSet<BuilderId, ErasedState>,
+
+	/// Whether the dyn states may not be modified, see `freeze_config`.
+	///
+	config_frozen: bool,
+
+	/// Usage counters of this cache.
+	///
+	stats: CacheStats,
+
+	/// Interned blueprints by builder type.
+	///
+	/// Each value is a `Map<u64, Vec<Blueprint<B, BCan>>>` for the
+	/// builder type `B` of its key, mapping the hash of the builders to the
+	/// blueprints with that hash.
+	///
+	interned: HashMap<TypeId, Box<dyn Any>, ErasedState>,
+
+	/// Maps builder id to the type name and `Debug` text of the builder,
+	/// see `find_duplicate_builders`.
+	///
+	#[cfg(feature = "diagnostics")]
+	builder_texts: HashMap<BuilderId, (&'static str, BuilderText<BCan>), ErasedState>,
+}
+
+impl<ArtCan, BCan> Extras<ArtCan, BCan>
+		where
+			BCan: CanStrong {
+
+	/// Creates the empty bookkeeping of a new cache using the given hasher.
+	///
+	fn with_hasher(hasher: &ErasedState) -> Self {
+		Self {
+			errors: HashMap::with_hasher(hasher.clone()),
+			evict_hooks: HashMap::with_hasher(hasher.clone()),
+			evict_callbacks: Vec::new(),
+			weak_dependencies: HashMap::with_hasher(hasher.clone()),
+			build_sampler: None,
+			build_hooks: None,
+			seed_provider: None,
+			build_generations: HashMap::with_hasher(hasher.clone()),
+			declared: HashMap::with_hasher(hasher.clone()),
+			#[cfg(feature = "rebuild_rate")]
+			build_times: HashMap::with_hasher(hasher.clone()),
+			#[cfg(feature = "dag_lint")]
+			directly_fetched: HashSet::with_hasher(hasher.clone()),
+			#[cfg(feature = "artifact_version")]
+			artifact_versions: HashMap::with_hasher(hasher.clone()),
+			context: None,
+			dirty: HashSet::with_hasher(hasher.clone()),
+			rebuilders: HashMap::with_hasher(hasher.clone()),
+			keep_stale: HashSet::with_hasher(hasher.clone()),
+			stale: HashMap::with_hasher(hasher.clone()),
+			#[cfg(feature = "lru")]
+			artifact_sizes: HashMap::with_hasher(hasher.clone()),
+			#[cfg(feature = "lru")]
+			last_access: HashMap::with_hasher(hasher.clone()),
+			#[cfg(feature = "provenance")]
+			provenances: HashMap::with_hasher(hasher.clone()),
+			#[cfg(feature = "provenance")]
+			precise_invalidation: false,
+			#[cfg(feature = "lru")]
+			total_size: 0,
+			#[cfg(feature = "lru")]
+			memory_budget: None,
+			#[cfg(feature = "lru")]
+			access_clock: 0,
+			building: Vec::new(),
+			resolving: None,
+			frozen: HashSet::with_hasher(hasher.clone()),
+			config_frozen: false,
+			stats: CacheStats::default(),
+			interned: HashMap::with_hasher(hasher.clone()),
+			#[cfg(feature = "diagnostics")]
+			builder_texts: HashMap::with_hasher(hasher.clone()),
+		}
+	}
+}
+
 /// The raw cache. Only for internal use.
 ///
 /// This struct is used by the "outer" Cache and Resolver.
@@ -98,11 +665,15 @@ pub(crate) struct RawCache<
 
 	/// Maps builder id to their Artifact can.
 	///
-	artifacts: HashMap<BuilderId, ArtCan>,
+	artifacts: Artifacts<ArtCan>,
 
 	/// Maps builder id to their DynState value.
 	///
-	dyn_states: HashMap<BuilderId, Box<dyn Any>>,
+	dyn_states: HashMap<BuilderId, Box<dyn Any>, ErasedState>,
+
+	/// The bookkeeping of the additional features of this cache.
+	///
+	extras: Extras<ArtCan, BCan>,
 
 	/// Tracks the set of direct depending builders of each builder, by id.
 	///
@@ -114,7 +685,7 @@ pub(crate) struct RawCache<
 	/// A reverse mapping is provided via `dependencies`. Both must be kept in
 	/// sync.
 	///
-	dependents: HashMap<BuilderId, HashSet<BuilderId>>,
+	dependents: HashMap<BuilderId, HashSet<BuilderId, ErasedState>, ErasedState>,
 
 	/// Tracks the set of direct dependencies of any builders, by id.
 	///
@@ -126,7 +697,7 @@ pub(crate) struct RawCache<
 	///
 	/// This is the reverse of `dependents`. Both must be kept in sync.
 	///
-	dependencies: HashMap<BuilderId, HashSet<BuilderId>>,
+	dependencies: HashMap<BuilderId, HashSet<BuilderId, ErasedState>, ErasedState>,
 
 	/// Tracks all builder id of builders which (yet) have no dependents.
 	///
@@ -134,12 +705,16 @@ pub(crate) struct RawCache<
 	/// It is the list of builders to be checked by the garbage collection, reducing the amount
 	/// of checks required.
 	///
-	known_leaf_builder: HashSet<BuilderId>,
+	known_leaf_builder: HashSet<BuilderId, ErasedState>,
 
 	/// Keeps a weak reference to all known builders that are those which are
 	/// used as builder id in any other mapping.
 	///
-	known_builders: HashMap<BuilderId, <BCan as CanStrong>::CanWeak>,
+	known_builders: HashMap<BuilderId, <BCan as CanStrong>::CanWeak, ErasedState>,
+
+	/// The hasher of all maps and sets of this cache.
+	///
+	hasher: ErasedState,
 
 	/// The doctor for error diagnostics.
 	#[cfg(feature = "diagnostics")]
@@ -160,7 +735,7 @@ cfg_if! {
 
 			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 				write!(f, "Cache {{ cache: {:?}, dependents: {:?}, doctor: {:?}, ... }}",
-					self.artifacts, self.dependents, self.doctor)
+					ArtifactsDebug(self.artifacts.store()), self.dependents, self.doctor)
 			}
 		}
 
@@ -172,13 +747,25 @@ cfg_if! {
 			/// **Notice: This function is only available if the `diagnostics` feature has been activated**.
 			///
 			pub(crate) fn new_with_doctor(doctor: Doc) -> Self {
+				Self::new_with_doctor_and_hasher(doctor, ErasedState::default())
+			}
+		}
+
+		impl<ArtCan, BCan, Doc> RawCache<ArtCan, BCan, Doc>
+			where BCan: CanStrong, Doc: Doctor<ArtCan, BCan> + 'static {
+
+			/// Creates new empty cache with given doctor and hasher.
+			///
+			pub(crate) fn new_with_doctor_and_hasher(doctor: Doc, hasher: ErasedState) -> Self {
 				Self {
-					artifacts: HashMap::new(),
-					dyn_states: HashMap::new(),
-					dependents: HashMap::new(),
-					dependencies: HashMap::new(),
-					known_builders: HashMap::new(),
-					known_leaf_builder: HashSet::new(),
+					artifacts: Artifacts::Map(HashMap::with_hasher(hasher.clone())),
+					dyn_states: HashMap::with_hasher(hasher.clone()),
+					extras: Extras::with_hasher(&hasher),
+					dependents: HashMap::with_hasher(hasher.clone()),
+					dependencies: HashMap::with_hasher(hasher.clone()),
+					known_builders: HashMap::with_hasher(hasher.clone()),
+					known_leaf_builder: HashSet::with_hasher(hasher.clone()),
+					hasher,
 
 					doctor,
 				}
@@ -193,29 +780,56 @@ cfg_if! {
 
 			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 				write!(f, "Cache {{ cache: {:?}, dependents: {:?}, ... }}",
-					self.artifacts, self.dependents)
+					ArtifactsDebug(self.artifacts.store()), self.dependents)
 			}
 		}
 
 		impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 			where BCan: CanStrong {
 
-			/// Creates a new empty cache.
+			/// Creates a new empty cache with the given hasher.
 			///
-			pub(crate) fn new() -> Self {
+			pub(crate) fn with_hasher(hasher: ErasedState) -> Self {
 				Self {
-					artifacts: HashMap::new(),
-					dyn_states: HashMap::new(),
-					dependents: HashMap::new(),
-					dependencies: HashMap::new(),
-					known_builders: HashMap::new(),
-					known_leaf_builder: HashSet::new(),
+					artifacts: Artifacts::Map(HashMap::with_hasher(hasher.clone())),
+					dyn_states: HashMap::with_hasher(hasher.clone()),
+					extras: Extras::with_hasher(&hasher),
+					dependents: HashMap::with_hasher(hasher.clone()),
+					dependencies: HashMap::with_hasher(hasher.clone()),
+					known_builders: HashMap::with_hasher(hasher.clone()),
+					known_leaf_builder: HashSet::with_hasher(hasher.clone()),
+					hasher,
 				}
 			}
 		}
 	}
 }
 
+impl<ArtCan, BCan> RawCache<ArtCan, BCan>
+		where
+			BCan: CanStrong {
+
+	/// Replaces the artifact store of this empty cache.
+	///
+	/// Panics if either this cache or the given store is not empty.
+	///
+	pub(crate) fn set_store(&mut self, store: Box<dyn ArtifactStore<ArtCan>>) {
+		assert!(self.artifacts.is_empty() && store.is_empty(),
+			"Replacing a non-empty artifact store");
+
+		self.artifacts = Artifacts::Store(store);
+	}
+
+	/// Sets whether the dyn states are frozen, returning the previous value.
+	///
+	/// While frozen, any modification of a dyn state from outside of a
+	/// build panics.
+	///
+	pub(crate) fn freeze_config(&mut self, frozen: bool) -> bool {
+		std::mem::replace(&mut self.extras.config_frozen, frozen)
+	}
+}
+
 impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 		where
 			ArtCan: Debug,
@@ -239,12 +853,15 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 		// Ensure that the given promise is known.
 		// User must exist already by contract.
 		self.make_builder_known(promise);
+		self.allow_nested_build(promise.id());
 		debug_assert!(self.is_builder_known_by_id(user.id()),
 			"Tracking dependency for unknown builder");
 
+		let hasher = &self.hasher;
+
 		// Map dependents (`promise` has new dependent `user`)
 		self.dependents.entry(promise.id())
-			.or_insert_with(HashSet::new)
+			.or_insert_with(|| HashSet::with_hasher(hasher.clone()))
 			.insert(user.id());
 
 		// Unmark the promise as leaf, since it has now at least one depenency
@@ -252,7 +869,7 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 
 		// Revers mapping (`user` depends on `promise`)
 		self.dependencies.entry(user.id())
-			.or_insert_with(HashSet::new)
+			.or_insert_with(|| HashSet::with_hasher(hasher.clone()))
 			.insert(promise.id());
 
 		// Diagnostics
@@ -277,13 +894,39 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 				BCan: Can<AP::Builder>,
 				AP: Promise<BCan = BCan> {
 
-		self.artifacts.contains_key(&promise.id())
+		self.contains_artifact_by_id(promise.id())
 	}
 
-	/// Tests whether the artifact or dyn state of the given builder is
-	/// recorded in this cache.
+	/// Gets the artifact can of the builder with the given id, if any.
 	///
-	pub(crate) fn is_builder_known<AP: ?Sized>(
+	pub(crate) fn artifact_can_by_id(&self, bid: BuilderId) -> Option<&ArtCan> {
+		self.artifacts.get(bid)
+	}
+
+	/// Gets the artifact can of the builder with the given id, if any,
+	/// counting the access as a hit.
+	///
+	pub(crate) fn hit_can_by_id(&mut self, bid: BuilderId) -> Option<&ArtCan> {
+		if self.artifacts.contains(bid) {
+			self.hit(bid);
+		}
+
+		self.artifacts.get(bid)
+	}
+
+	/// Gets the artifact can of the builder with the given id mutably, if any.
+	///
+	/// Notice, as opposed to `lookup_mut`, this does not invalidate any
+	/// dependents.
+	///
+	#[cfg(feature = "mut_box")]
+	pub(crate) fn artifact_can_mut_by_id(&mut self, bid: BuilderId) -> Option<&mut ArtCan> {
+		self.artifacts.get_mut(bid)
+	}
+
+	/// Tests whether there is a cached error for the given builder.
+	///
+	pub(crate) fn has_cached_error<AP: ?Sized>(
 			&self,
 			promise: &AP
 		) -> bool
@@ -291,13 +934,166 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 				BCan: Can<AP::Builder>,
 				AP: Promise<BCan = BCan> {
 
-		self.is_builder_known_by_id(promise.id())
+		self.extras.errors.contains_key(&promise.id())
 	}
 
-	/// Auxillary function to test whether given builder id is contained in
+	/// Auxillary function to test whether there exists an artifact for the
+	/// given builder id in this cache.
+	///
+	pub(crate) fn contains_artifact_by_id(
+			&self,
+			bid: BuilderId,
+		) -> bool {
+
+		self.artifacts.contains(bid)
+	}
+
+	/// Gets the provenance of the artifact of the given builder, if any.
+	///
+	#[cfg(feature = "provenance")]
+	pub(crate) fn provenance(&self, bid: BuilderId) -> Option<&Provenance> {
+		self.extras.provenances.get(&bid)
+	}
+
+	/// Sets whether a dyn state change only invalidates an artifact which
+	/// has read that dyn state.
+	///
+	#[cfg(feature = "provenance")]
+	pub(crate) fn set_precise_invalidation(&mut self, enabled: bool) {
+		self.extras.precise_invalidation = enabled;
+	}
+
+	/// Tests whether a change of the dyn state of the given builder requires
+	/// to invalidate its artifact.
+	///
+	/// This is always the case, unless precise invalidation is enabled, in
+	/// which case only an existing artifact which has read its dyn state
+	/// needs to be invalidated. A cached error has no provenance, thus it
+	/// is always dropped, since it might depend on the dyn state.
+	///
+	pub(crate) fn dyn_state_change_invalidates(&self, bid: BuilderId) -> bool {
+		cfg_if! {
+			if #[cfg(feature = "provenance")] {
+				!self.extras.precise_invalidation
+					|| self.extras.errors.contains_key(&bid)
+					|| self.extras.provenances.get(&bid)
+						.map_or(false, |provenance| provenance.dyn_states.contains(&bid))
+			} else {
+				let _ = bid;
+				true
+			}
+		}
+	}
+
+	/// Sets the provenance of the artifact of the given builder.
+	///
+	#[cfg(feature = "provenance")]
+	pub(crate) fn set_provenance(&mut self, bid: BuilderId, provenance: Provenance) {
+		self.extras.provenances.insert(bid, provenance);
+	}
+
+	/// Records the start of a build of the given builder.
+	///
+	#[cfg(feature = "rebuild_rate")]
+	fn record_build_time(&mut self, bid: BuilderId) {
+		let times = self.extras.build_times.entry(bid).or_default();
+		if times.len() == REBUILD_WINDOW {
+			times.pop_front();
+		}
+		times.push_back(Instant::now());
+	}
+
+	/// Returns the builds per second of the given builder, estimated from
+	/// its recent builds until now.
+	///
+	#[cfg(feature = "rebuild_rate")]
+	pub(crate) fn rebuild_rate(&self, bid: BuilderId) -> f64 {
+		let times = self.extras.build_times.get(&bid);
+
+		match times.and_then(|times| times.front().map(|oldest| (times.len(), oldest))) {
+			Some((builds, oldest)) => {
+				let builds = builds as f64;
+				let elapsed = oldest.elapsed().as_secs_f64();
+
+				if elapsed > 0.0 {
+					builds / elapsed
+				} else {
+					std::f64::INFINITY
+				}
+			}
+			None => 0.0,
+		}
+	}
+
+
+	/// Records that the artifact of the given builder is fetched directly,
+	/// unless it is resolved during a build.
+	///
+	#[cfg(feature = "dag_lint")]
+	fn mark_fetched<AP>(&mut self, promise: &AP)
+			where
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		if self.extras.building.is_empty() {
+			self.make_builder_known(promise);
+			self.extras.directly_fetched.insert(promise.id());
+		}
+	}
+
+	/// Tests whether any builder currently depends on the given builder.
+	///
+	#[cfg(feature = "dag_lint")]
+	fn is_depended_upon(&self, bid: BuilderId) -> bool {
+		self.dependents.get(&bid).map_or(false, |deps| !deps.is_empty())
+	}
+
+	/// Returns the builders which have been fetched directly, but which no
+	/// builder depends upon.
+	///
+	#[cfg(feature = "dag_lint")]
+	pub(crate) fn leaf_only_builders(&self) -> Vec<BuilderId> {
+		self.extras.directly_fetched.iter()
+			.copied()
+			.filter(|bid| !self.is_depended_upon(*bid))
+			.collect()
+	}
+
+	/// Returns the known builders which have never been fetched directly.
+	///
+	#[cfg(feature = "dag_lint")]
+	pub(crate) fn root_only_builders(&self) -> Vec<BuilderId> {
+		self.known_builders.keys()
+			.copied()
+			.filter(|bid| !self.extras.directly_fetched.contains(bid))
+			.collect()
+	}
+
+	/// Returns the number of artifacts inserted for the given builder.
+	///
+	#[cfg(feature = "artifact_version")]
+	pub(crate) fn artifact_version(&self, bid: BuilderId) -> u64 {
+		self.extras.artifact_versions.get(&bid).copied().unwrap_or(0)
+	}
+
+	/// Tests whether the artifact or dyn state of the given builder is
+	/// recorded in this cache.
+	///
+	pub(crate) fn is_builder_known<AP: ?Sized>(
+			&self,
+			promise: &AP
+		) -> bool
+			where
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.is_builder_known_by_id(promise.id())
+	}
+
+	/// Auxillary function to test whether given builder id is contained in
 	/// `known_builders`.
 	///
-	fn is_builder_known_by_id(
+	pub(crate) fn is_builder_known_by_id(
 			&self,
 			bid: BuilderId,
 		) -> bool {
@@ -320,7 +1116,7 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 
 
 		// Get the artifact from the hash map ensuring integrity
-		self.artifacts.get(&promise.id()).map(
+		self.artifacts.get(promise.id()).map(
 			|ent| {
 				// Ensure that the builder to the artifact is known
 				debug_assert!(self.is_builder_known(promise),
@@ -347,7 +1143,7 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 
 
 		// Get the artifact from the hash map ensuring integrity
-		self.artifacts.get(&promise.id()).map(
+		self.artifacts.get(promise.id()).map(
 			|ent| {
 				// Ensure that the builder to the artifact is known
 				debug_assert!(self.is_builder_known(promise),
@@ -389,7 +1185,7 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 		);
 
 		// Get the artifact from the hash map ensuring integrity
-		self.artifacts.get_mut(&id).map(
+		self.artifacts.get_mut(id).map(
 			|ent| {
 				// Ensure value type
 				ent.downcast_can_mut()
@@ -433,9 +1229,185 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 				BCan: Can<AP::Builder>,
 				AP: Promise<Builder = B, BCan = BCan>  {
 
+		self.build_with(promise, |resolver| {
+			promise.builder().builder.build(resolver)
+		})
+	}
+
+	/// Build and insert the artifact for `promise` using `build_fn` instead
+	/// of the builder's `build` method.
+	///
+	/// This is an internal function.
+	///
+	/// There must be no artifact in cache for the given builder.
+	///
+	fn build_with<AP, B: ?Sized, F>(
+			&mut self,
+			promise: &AP,
+			build_fn: F,
+		) -> Result<&mut ArtCan, B::Err>
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanSized<B::Artifact>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>,
+				F: FnOnce(&mut Resolver<ArtCan, BCan, B::DynState>)
+					-> Result<ArtCan::Bin, B::Err>  {
+
 		// Ensure that there yet is no artifact for that builder in cache
 		debug_assert!(!self.contains_artifact(promise));
 
+		// Builds may only be nested via the resolver of the enclosing build
+		if let Some(&building) = self.extras.building.last() {
+			if self.extras.resolving != Some(promise.id()) {
+				panic!("reentrant cache access detected for builder {:?}", building);
+			}
+		}
+		self.extras.resolving = None;
+
+		self.extras.stats.misses += 1;
+
+		// Replay a cached error, if any
+		if let Some(err) = self.extras.errors.get(&promise.id()) {
+			let err = err.downcast_ref()
+				.expect("Cached error is of invalid type");
+
+			if let Some(err) = promise.builder().builder.cache_error(err) {
+				return Err(err);
+			}
+		}
+
+		// Create a temporary resolver
+		let mut resolver = self.resolver_for(promise);
+
+		// Construct the artifact, timing it if sampled
+		resolver.cache.record_build(promise);
+		let scope = BuildScope::enter(&mut resolver, promise.id());
+		// Span names must be static, thus the builder type is a field
+		#[cfg(feature = "tracing")]
+		let span = tracing::debug_span!(target: "daab", "build",
+			builder = std::any::type_name::<B>(),
+			id = ?promise.id(),
+		).entered();
+		#[cfg(feature = "log")]
+		log::trace!(target: "daab", "building {:?} {}",
+			promise.id(), std::any::type_name::<B>());
+		if let Some(hooks) = &mut scope.resolver.cache.extras.build_hooks {
+			(hooks.before)(promise.id());
+		}
+		let start = if scope.resolver.cache.sample_build() {
+			Some(Instant::now())
+		} else {
+			None
+		};
+		let art_res = build_fn(
+			&mut *scope.resolver,
+		);
+		let elapsed = start.map(|start| start.elapsed());
+		if let Some(hooks) = &mut scope.resolver.cache.extras.build_hooks {
+			(hooks.after)(promise.id(), art_res.is_ok());
+		}
+		#[cfg(feature = "tracing")]
+		span.exit();
+		#[cfg(feature = "log")]
+		{
+			if art_res.is_ok() {
+				log::debug!(target: "daab", "built {:?} {}",
+					promise.id(), std::any::type_name::<B>());
+			} else {
+				log::debug!(target: "daab", "failed to build {:?} {}",
+					promise.id(), std::any::type_name::<B>());
+			}
+		}
+		drop(scope);
+
+		#[cfg(feature = "diagnostics")]
+		let diag_builder = resolver.diag_builder;
+
+		#[cfg(feature = "provenance")]
+		let provenance = Provenance {
+			artifacts: resolver.resolved,
+			dyn_states: resolver.state_reads,
+		};
+
+		if let Some(elapsed) = elapsed {
+			if let Some(sampler) = &mut self.extras.build_sampler {
+				(sampler.f)(promise.id(), elapsed);
+			}
+		}
+
+		// Cache the error, if the builder opts-in
+		if let Err(err) = &art_res {
+			if let Some(err) = promise.builder().builder.cache_error(err) {
+				self.extras.errors.insert(promise.id(), Box::new(err));
+			}
+		}
+
+		// Add artifact to cache if it was successful, otherwise just return
+		// the error
+		art_res.map(move |art_bin| {
+			#[cfg(feature = "provenance")]
+			self.extras.provenances.insert(promise.id(), provenance);
+
+			self.insert_built(
+				promise,
+				#[cfg(feature = "diagnostics")]
+				&diag_builder,
+				art_bin,
+			)
+		})
+
+	}
+
+	/// Accounts a build of the given builder, which is about to insert its
+	/// artifact via `insert_built`.
+	///
+	/// This is shared by the regular builds and the manual ones finished by
+	/// `Resolver::finish`.
+	///
+	pub(crate) fn record_build<AP>(&mut self, promise: &AP)
+			where
+				AP::Builder: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		self.extras.stats.builds += 1;
+		*self.extras.build_generations.entry(promise.id()).or_insert(0) += 1;
+		#[cfg(feature = "rebuild_rate")]
+		self.record_build_time(promise.id());
+
+		// Remember the declared dependencies for `plan`
+		let declared: Vec<_> = promise.builder().builder.declare_dependencies()
+			.iter()
+			.map(|dep| dep.id())
+			.collect();
+		if !declared.is_empty() {
+			self.extras.declared.insert(promise.id(), declared);
+		}
+	}
+
+	/// Allows the given builder to be built during the build in progress.
+	///
+	/// This is used by the resolver of that build, any other build during a
+	/// build is considered a reentrant access and panics.
+	///
+	pub(crate) fn allow_nested_build(&mut self, bid: BuilderId) {
+		self.extras.resolving = Some(bid);
+	}
+
+	/// Creates a resolver for the given builder, without building anything.
+	///
+	/// This ensures that the builder is known and that it has a dyn state.
+	///
+	pub(crate) fn resolver_for<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP,
+		) -> Resolver<'_, ArtCan, BCan, B::DynState>
+			where
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
 		// Ensure that the promise is known, because we will add its dynamic
 		// state & (possibly) its artifact.
 		self.make_builder_known(promise);
@@ -443,57 +1415,218 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 		// Ensure there is a DynState
 		self.ensure_dyn_state(promise);
 
-		// Create Resolver prerequisites
-		let ent = BuilderEntry::new(promise);
-		#[cfg(feature = "diagnostics")]
-		let diag_builder = BuilderHandle::new(promise);
-
-		// Create a temporary resolver
-		let mut resolver = Resolver {
-			user: &ent,
+		Resolver {
+			user: BuilderEntry::new(promise),
 			cache: self,
 			#[cfg(feature = "diagnostics")]
-			diag_builder: &diag_builder,
+			diag_builder: BuilderHandle::new(promise),
+			resolved: Vec::new(),
+			resolved_set: HashSet::new(),
+			#[cfg(feature = "provenance")]
+			state_reads: Vec::new(),
 			_b: PhantomData,
-		};
+		}
+	}
+
+	/// Insert the just built artifact of the given builder.
+	///
+	/// There should be no artifact in cache for the given builder.
+	///
+	pub(crate) fn insert_built<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP,
+			#[cfg(feature = "diagnostics")]
+			diag_builder: &BuilderHandle<BCan>,
+			art_bin: ArtCan::Bin,
+		) -> &mut ArtCan
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanSized<B::Artifact>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		#[cfg(feature = "lru")]
+		let size = ArtCan::bin_as_ref(&art_bin).map_or(0, |art| {
+			promise.builder().builder.artifact_size_hint(art)
+		});
 
-		// Construct the artifact
-		let art_res = promise.builder().builder.build(
-			&mut resolver,
+		// diagnostics
+		cfg_if!(
+			if #[cfg(feature = "diagnostics")] {
+				let handle = ArtifactHandle::with_builder_id(promise.id(), art_bin);
+
+				// Update doctor on diagnostics mode
+				self.doctor.build(diag_builder, &handle);
+
+				let art_can = handle.into_inner();
+			} else {
+				let art_can = ArtCan::from_bin(art_bin);
+			}
 		);
 
-		// Add artifact to cache if it was successful, otherwise just return
-		// the error
-		art_res.map(move |art_bin| {
+		// keep the id
+		let id = promise.id();
 
-			// diagnostics
-			cfg_if!(
-				if #[cfg(feature = "diagnostics")] {
-					let handle = ArtifactHandle::new(art_bin);
+		// Keep the eviction hook, if the builder opts-in
+		if let Some(hook) = promise.builder().builder.evict_hook() {
+			self.extras.evict_hooks.insert(id, hook);
+		}
 
-					// Update doctor on diagnostics mode
-					self.doctor.build(&diag_builder, &handle);
+		// Keep the weak dependencies, if the builder declares any
+		let hasher = &self.hasher;
+		let mut weak_deps: Option<HashSet<BuilderId, ErasedState>> = None;
+		promise.builder().builder.weak_dependencies(&mut |dep| {
+			weak_deps.get_or_insert_with(|| HashSet::with_hasher(hasher.clone()))
+				.insert(dep);
+		});
+		if let Some(set) = weak_deps {
+			self.extras.weak_dependencies.insert(id, set);
+		}
 
-					let art_can = handle.into_inner();
-				} else {
-					let art_can = ArtCan::from_bin(art_bin);
-				}
-			);
+		// The artifact is up to date again
+		self.extras.dirty.remove(&id);
+		self.extras.stale.remove(&id);
+		#[cfg(feature = "artifact_version")]
+		{
+			*self.extras.artifact_versions.entry(id).or_insert(0) += 1;
+		}
 
-			// keep the id
-			let id = promise.id();
+		// Insert/Replace artifact
+		self.artifacts.insert(
+			id,
+			art_can,
+		);
+		//.expect_none("Built an artifact while it was still in cache");
 
-			// Insert/Replace artifact
-			self.artifacts.insert(
-				id,
-				art_can,
-			);
-			//.expect_none("Built an artifact while it was still in cache");
+		// Account the size of the artifact
+		#[cfg(feature = "lru")]
+		{
+			if let Some(old) = self.extras.artifact_sizes.remove(&id) {
+				self.extras.total_size -= old;
+			}
+			if size > 0 {
+				self.extras.artifact_sizes.insert(id, size);
+				self.extras.total_size += size;
+			}
+			self.touch(id);
 
-			// Just unwrap, since we just inserted it
-			self.artifacts.get_mut(&id).unwrap()
-		})
+			// Stay within the memory budget, unless other builds are in progress
+			if self.extras.building.is_empty() {
+				self.enforce_memory_budget(Some(id));
+			}
+		}
+
+		// Just unwrap, since we just inserted it
+		self.artifacts.get_mut(id).unwrap()
+	}
+
+	/// Counts a cache hit on the artifact of the given builder.
+	///
+	fn hit(&mut self, bid: BuilderId) {
+		self.extras.stats.hits += 1;
+		self.touch(bid);
+	}
+
+	/// Updates the access time of the artifact of the given builder.
+	///
+	fn touch(&mut self, bid: BuilderId) {
+		cfg_if!(
+			if #[cfg(feature = "lru")] {
+				self.extras.access_clock += 1;
+				self.extras.last_access.insert(bid, self.extras.access_clock);
+			} else {
+				// Access times are only needed for the LRU eviction
+				let _ = bid;
+			}
+		);
+	}
 
+	/// Invalidates all artifacts except for the `keep` most recently accessed
+	/// ones.
+	///
+	/// Returns the ids of the evicted artifacts, which includes their
+	/// invalidated dependents.
+	///
+	#[cfg(feature = "lru")]
+	pub(crate) fn evict_lru(&mut self, keep: usize) -> Vec<BuilderId> {
+		let mut by_access: Vec<_> = self.extras.last_access.iter()
+			.map(|(bid, access)| (*access, *bid))
+			.collect();
+
+		// Most recent first
+		by_access.sort_unstable_by(|a, b| b.cmp(a));
+
+		let victims: Vec<_> = by_access.into_iter()
+			.skip(keep)
+			.map(|(_, bid)| bid)
+			.filter(|bid| !self.extras.frozen.contains(bid))
+			.collect();
+
+		self.invalidate_by_ids(&victims).invalidated_artifacts
+	}
+
+	/// Returns the sum of the size hints of all artifacts.
+	///
+	#[cfg(feature = "lru")]
+	pub(crate) fn approx_memory(&self) -> usize {
+		self.extras.total_size
+	}
+
+	/// Sets the memory budget, evicting artifacts if it is exceeded.
+	///
+	#[cfg(feature = "lru")]
+	pub(crate) fn set_memory_budget(&mut self, budget: usize) {
+		self.extras.memory_budget = Some(budget);
+		self.enforce_memory_budget(None);
+	}
+
+	/// Removes the memory budget, if any.
+	///
+	#[cfg(feature = "lru")]
+	pub(crate) fn clear_memory_budget(&mut self) {
+		self.extras.memory_budget = None;
+	}
+
+	/// Evicts the least recently accessed artifacts until the memory budget
+	/// is met.
+	///
+	/// The artifact of `keep` and its dependencies are never evicted.
+	///
+	#[cfg(feature = "lru")]
+	fn enforce_memory_budget(&mut self, keep: Option<BuilderId>) {
+		let budget = match self.extras.memory_budget {
+			Some(budget) => budget,
+			None => return,
+		};
+
+		if self.extras.total_size <= budget {
+			return;
+		}
+
+		// Evicting a dependency would invalidate `keep` too
+		let mut protected = HashSet::new();
+		let mut pending: Vec<_> = keep.into_iter().collect();
+		while let Some(bid) = pending.pop() {
+			if protected.insert(bid) {
+				if let Some(set) = self.dependencies.get(&bid) {
+					pending.extend(set.iter().copied());
+				}
+			}
+		}
+
+		while self.extras.total_size > budget {
+			let victim = self.extras.artifact_sizes.keys()
+				.filter(|bid| !protected.contains(bid) && !self.extras.frozen.contains(bid))
+				.min_by_key(|bid| self.extras.last_access.get(bid))
+				.copied();
+
+			match victim {
+				Some(bid) => {
+					self.evict_by_ids(std::slice::from_ref(&bid));
+				}
+				None => break,
+			}
+		}
 	}
 
 
@@ -510,8 +1643,11 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 				BCan: Can<AP::Builder>,
 				AP: Promise<Builder = B, BCan = BCan>  {
 
+		#[cfg(feature = "dag_lint")]
+		self.mark_fetched(promise);
 
 		if let Some(art) = self.lookup(promise) {
+			self.hit(promise.id());
 			Ok(art)
 
 		} else {
@@ -522,48 +1658,436 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 		}
 	}
 
-	/// Gets a reference to the artifact of the given builder.
+	/// Gets the bin with the artifact of the given builder, providing the
+	/// given context to all builds done meanwhile.
 	///
-	pub(crate) fn get_ref<AP, B: ?Sized>(
+	/// The context is moved into the cache for the time of the call, and
+	/// returned along with the result.
+	///
+	pub(crate) fn get_with_ctx<Ctx, AP, B: ?Sized>(
 			&mut self,
-			promise: &AP
-		) -> Result<&B::Artifact, B::Err>
+			promise: &AP,
+			ctx: Ctx,
+		) -> (Result<ArtCan::Bin, B::Err>, Ctx)
 			where
+				Ctx: 'static,
 				B: Builder<ArtCan, BCan>,
-				ArtCan: CanRef<B::Artifact>,
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
 				BCan: Can<AP::Builder>,
 				AP: Promise<Builder = B, BCan = BCan>  {
 
+		let scope = ContextScope::enter(self, Box::new(ctx));
 
-		if self.lookup_ref(promise).is_some() {
-			// Here, requires a second look up because due to the build in the
-			// else case, an `if let Some(_)` won't work due to lifetime issues
-			Ok(self.lookup_ref(promise).unwrap())
+		let res = scope.cache.get(promise);
 
-		} else {
-			self.build(promise).map(|art| {
-				art.downcast_can_ref()
-				.expect("Just build artifact is of invalid type")
-			})
-		}
+		let ctx = scope.leave().downcast()
+			.expect("Build context is of invalid type");
+
+		(res, *ctx)
 	}
 
-	/// Gets a mutable reference to the artifact of the given builder.
+	/// Retains the last artifact of the given builder upon its removal.
 	///
-	/// Unstable!
+	pub(crate) fn keep_stale(&mut self, bid: BuilderId) {
+		self.extras.keep_stale.insert(bid);
+	}
+
+	/// Gets the retained artifact of the given builder, if any.
 	///
-	pub(crate) fn get_mut<AP, B: ?Sized>(
+	pub(crate) fn stale_can_by_id(&self, bid: BuilderId) -> Option<&ArtCan> {
+		self.extras.stale.get(&bid)
+	}
+
+	/// Registers the given builder to be rebuilt by `rebuild_dirty`.
+	///
+	/// Only a weak reference to the builder is kept.
+	///
+	pub(crate) fn watch<B>(
 			&mut self,
-			promise: &AP
-		) -> Result<&mut B::Artifact, B::Err>
+			promise: &Blueprint<B, BCan>
+		)
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanRef<B::Artifact>,
+				BCan: CanDowngrade<B>,
+				BCan::Bin: AsRef<B> + Clone {
+
+		self.make_builder_known(promise);
+
+		let weak = promise.downgrade();
+		self.extras.rebuilders.insert(promise.id(), Box::new(move |cache| {
+			weak.upgrade().map(|bp| {
+				cache.get_ref(&bp)
+					.map(|_| ())
+					.map_err(|err| Box::new(err) as ErasedErr)
+			})
+		}));
+	}
+
+	/// Rebuilds the artifacts of all dirty builders and clears the dirty set.
+	///
+	/// Returns for each dirty builder, in the order of the builder ids,
+	/// whether its artifact is available. The error is `None` for builders
+	/// which can not be rebuilt, because they aren't watched, otherwise it is
+	/// the erased error of the failed build.
+	///
+	pub(crate) fn rebuild_dirty(&mut self) -> Vec<(BuilderId, Result<(), Option<ErasedErr>>)> {
+		let mut dirty: Vec<_> = self.extras.dirty.drain().collect();
+		dirty.sort();
+
+		dirty.into_iter().map(|bid| {
+			let res = match self.run_rebuilder(bid) {
+				Some(res) => res.map_err(Some),
+				None => Err(None),
+			};
+
+			(bid, res)
+		}).collect()
+	}
+
+	/// Rebuilds the artifact of the given builder via its rebuilder.
+	///
+	/// Returns `None` if the builder isn't watched or is gone, otherwise the
+	/// result of the build.
+	///
+	fn run_rebuilder(&mut self, bid: BuilderId) -> Option<Result<(), ErasedErr>> {
+		self.extras.rebuilders.remove(&bid).and_then(|rebuild| {
+			let built = rebuild(self);
+
+			// Keep the rebuilder unless the builder is gone
+			if built.is_some() {
+				self.extras.rebuilders.insert(bid, rebuild);
+			}
+
+			built
+		})
+	}
+
+	/// Returns the transitive dependents of the given builder, each one
+	/// before its own dependents.
+	///
+	fn dependents_in_build_order(&self, root: BuilderId) -> Vec<BuilderId> {
+		let mut visited = HashSet::new();
+		let mut post_order = Vec::new();
+		let mut stack = vec![(root, false)];
+
+		while let Some((bid, expanded)) = stack.pop() {
+			if expanded {
+				post_order.push(bid);
+			} else if visited.insert(bid) {
+				stack.push((bid, true));
+
+				if let Some(set) = self.dependents.get(&bid) {
+					let mut users: Vec<_> = set.iter().copied().collect();
+					users.sort();
+					stack.extend(users.into_iter().rev().map(|user| (user, false)));
+				}
+			}
+		}
+
+		// The reversed post order lists dependencies before their dependents
+		post_order.reverse();
+		post_order.retain(|bid| *bid != root);
+		post_order
+	}
+
+	/// Invalidates and rebuilds the given builder and its transitive
+	/// dependents.
+	///
+	/// Dependents can only be rebuilt if they are watched, see `watch`.
+	///
+	pub(crate) fn rebuild_subtree<AP, B: ?Sized>(
+			&mut self,
+			root: &AP,
+			stop_on_error: bool,
+		) -> RebuildReport
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanRef<B::Artifact>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		// Collect the dependents before the invalidation removes the edges
+		let dependents = self.dependents_in_build_order(root.id());
+
+		self.invalidate(root);
+
+		let mut report = RebuildReport::default();
+		let mut failed = false;
+
+		if self.get_ref(root).is_ok() {
+			report.rebuilt.push(root.id());
+		} else {
+			report.failed.push(root.id());
+			failed = true;
+		}
+
+		for bid in dependents {
+			if failed && stop_on_error {
+				report.skipped.push(bid);
+				continue;
+			}
+
+			// A dependent might have been rebuilt along with another one
+			let built = if self.artifacts.contains(bid) {
+				Some(Ok(()))
+			} else {
+				self.run_rebuilder(bid)
+			};
+
+			match built {
+				Some(Ok(())) => report.rebuilt.push(bid),
+				Some(Err(_)) => {
+					report.failed.push(bid);
+					failed = true;
+				}
+				None => report.skipped.push(bid),
+			}
+		}
+
+		report
+	}
+
+	/// Gets the context of the current `get_with_ctx` call, if it is a `Ctx`.
+	///
+	pub(crate) fn context_mut<Ctx: 'static>(&mut self) -> Option<&mut Ctx> {
+		self.extras.context.as_mut().and_then(|ctx| ctx.downcast_mut())
+	}
+
+	/// Gets the bin with the artifact of the primary builder, or if it fails,
+	/// of the secondary builder.
+	///
+	pub(crate) fn get_or<AP1, AP2, B1: ?Sized, B2: ?Sized>(
+			&mut self,
+			primary: &AP1,
+			secondary: &AP2,
+		) -> Result<ArtCan::Bin, B2::Err>
+			where
+				B1: Builder<ArtCan, BCan>,
+				B2: Builder<ArtCan, BCan, Artifact = B1::Artifact>,
+				ArtCan: CanSized<B1::Artifact>,
+				ArtCan: Clone,
+				BCan: Can<AP1::Builder>,
+				BCan: Can<AP2::Builder>,
+				AP1: Promise<Builder = B1, BCan = BCan>,
+				AP2: Promise<Builder = B2, BCan = BCan>  {
+
+		match self.get(primary) {
+			Ok(art) => Ok(art),
+			Err(_) => self.get(secondary),
+		}
+	}
+
+	/// Get the stored artifact by its bin if it exists, without panicking on
+	/// a type mismatch.
+	///
+	/// In debug builds a type mismatch still panics, as it indicates a bug.
+	///
+	#[cfg(feature = "checked")]
+	pub(crate) fn checked_lookup<AP, B: ?Sized>(
+			&self,
+			promise: &AP
+		) -> Result<Option<ArtCan::Bin>, CacheError>
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		match self.artifacts.get(promise.id()) {
+			Some(ent) => {
+				let bin = ent.clone().downcast_can();
+
+				debug_assert!(bin.is_some(), "Cached artifact is of invalid type");
+
+				bin.map(Some).ok_or(CacheError::TypeMismatch)
+			}
+			None => Ok(None),
+		}
+	}
+
+	/// Gets the bin with the artifact of the given builder, without panicking
+	/// on a type mismatch.
+	///
+	/// In debug builds a type mismatch still panics, as it indicates a bug.
+	///
+	#[cfg(feature = "checked")]
+	pub(crate) fn checked_get<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<ArtCan::Bin, CacheError<B::Err>>
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		#[cfg(feature = "dag_lint")]
+		self.mark_fetched(promise);
+
+		if let Some(art) = self.checked_lookup(promise).map_err(CacheError::cast)? {
+			self.hit(promise.id());
+			Ok(art)
+
+		} else {
+			let bin = self.build(promise)
+				.map_err(CacheError::Build)?
+				.clone()
+				.downcast_can();
+
+			debug_assert!(bin.is_some(), "Just build artifact is of invalid type");
+
+			bin.ok_or(CacheError::TypeMismatch)
+		}
+	}
+
+	/// Returns the number of strong references to the builder with the given
+	/// id, if it is known.
+	///
+	pub(crate) fn builder_strong_count(&self, bid: BuilderId) -> Option<usize> {
+		self.known_builders.get(&bid).map(|weak| {
+			// Do not count the temporary upgraded reference
+			BCan::upgrade_from_weak(weak)
+				.map_or(0, |can| can.strong_count() - 1)
+		})
+	}
+
+	/// Gets the bin with the artifact of the given builder, using `build_fn`
+	/// instead of the builder's `build` method if it is not cached.
+	///
+	pub(crate) fn get_overriding<AP, B: ?Sized, F>(
+			&mut self,
+			promise: &AP,
+			build_fn: F,
+		) -> Result<ArtCan::Bin, B::Err>
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>,
+				F: FnOnce(&mut Resolver<ArtCan, BCan, B::DynState>)
+					-> Result<B::Artifact, B::Err>  {
+
+		#[cfg(feature = "dag_lint")]
+		self.mark_fetched(promise);
+
+		if let Some(art) = self.lookup(promise) {
+			self.hit(promise.id());
+			Ok(art)
+
+		} else {
+			self.build_with(promise, |resolver| {
+				build_fn(resolver).map(ArtCan::into_bin)
+			}).map(|art| {
+				art.clone()
+				.downcast_can()
+				.expect("Just build artifact is of invalid type")
+			})
+		}
+	}
+
+	/// Gets the bin with the artifact of the given builder and whether it
+	/// has been built by this call.
+	///
+	pub(crate) fn get_fresh<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<(ArtCan::Bin, bool), B::Err>
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanSized<B::Artifact>,
+				ArtCan: Clone,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		// If there is no artifact yet, `get` will build it
+		let fresh = !self.contains_artifact(promise);
+
+		self.get(promise).map(|art| (art, fresh))
+	}
+
+	/// Gets a reference to the artifact of the given builder.
+	///
+	pub(crate) fn get_ref<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<&B::Artifact, B::Err>
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanRef<B::Artifact>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		#[cfg(feature = "dag_lint")]
+		self.mark_fetched(promise);
+
+		if self.lookup_ref(promise).is_some() {
+			self.hit(promise.id());
+
+			// Here, requires a second look up because due to the build in the
+			// else case, an `if let Some(_)` won't work due to lifetime issues
+			Ok(self.lookup_ref(promise).unwrap())
+
+		} else {
+			self.build(promise).map(|art| {
+				art.downcast_can_ref()
+				.expect("Just build artifact is of invalid type")
+			})
+		}
+	}
+
+	/// Gets a reference to the artifact and to the dynamic state of the given
+	/// builder.
+	///
+	pub(crate) fn get_with_state<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<(&B::Artifact, &B::DynState), B::Err>
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanRef<B::Artifact>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		// Ensure that the artifact exists, building it also ensures the dyn
+		// state. The returned reference is dropped to allow the shared
+		// borrows below.
+		self.get_ref(promise)?;
+
+		// Artifacts and dyn states are disjoint maps, thus both may be
+		// borrowed at the same time
+		let art = self.lookup_ref(promise)
+			.expect("Artifact is missing right after ensuring it");
+		let state = self.dyn_state_cast_ref(promise.id())
+			.expect("Dyn state of existing artifact is missing");
+
+		Ok((art, state))
+	}
+
+	/// Gets a mutable reference to the artifact of the given builder.
+	///
+	/// Unstable!
+	///
+	pub(crate) fn get_mut<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<&mut B::Artifact, B::Err>
 			where
 				B: Builder<ArtCan, BCan>,
 				ArtCan: CanRefMut<B::Artifact>,
 				BCan: Can<AP::Builder>,
 				AP: Promise<Builder = B, BCan = BCan>  {
 
+		#[cfg(feature = "dag_lint")]
+		self.mark_fetched(promise);
 
 		if self.lookup_mut(promise).is_some() {
+			self.hit(promise.id());
+
 			// Here, requires a second look up because due to the build in the
 			// else case, an `if let Some(_)` won't work due to lifetime issues
 			Ok(self.lookup_mut(promise).unwrap())
@@ -576,6 +2100,44 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 		}
 	}
 
+	/// Get the artifact of the given builder mutably on behalf of the given
+	/// owner, which is currently building.
+	///
+	/// Like `get_mut`, but only the dependents other than `owner` and the
+	/// builders whose builds are in progress are invalidated, thus they keep
+	/// their dependency upon the builder.
+	///
+	#[cfg(feature = "mut_box")]
+	pub(crate) fn get_mut_by<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP,
+			owner: BuilderId
+		) -> Result<&mut B::Artifact, B::Err>
+			where
+				B: Builder<ArtCan, BCan>,
+				ArtCan: CanRefMut<B::Artifact>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		let id = promise.id();
+
+		if self.artifacts.contains(id) {
+			self.hit(id);
+			self.invalidate_dependents_except(&id, owner);
+
+			Ok(self.artifacts.get_mut(id)
+				.expect("Artifact is missing right after ensuring it")
+				.downcast_can_mut()
+				.expect("Cached artifact is of invalid type"))
+
+		} else {
+			self.build(promise).map(|art| {
+				art.downcast_can_mut()
+				.expect("Just build artifact is of invalid type")
+			})
+		}
+	}
+
 	/// Get a clone of the artifact of the given builder.
 	///
 	pub(crate) fn get_cloned<AP, B: ?Sized>(
@@ -594,10 +2156,71 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 		})
 	}
 
+	/// Get a clone of the artifact of the given builder, without keeping it.
+	///
+	/// All artifacts built by this call are removed afterwards, without
+	/// marking their builders dirty. Further, the builders introduced by
+	/// this call, i.e. those which have been unknown before, are purged.
+	///
+	pub(crate) fn get_once<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<B::Artifact, B::Err>
+			where
+				B: Builder<ArtCan, BCan>,
+				B::Artifact: Clone,
+				ArtCan: CanRef<B::Artifact>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		let known: HashSet<_> = self.known_builders.keys().copied().collect();
+		let cached: HashSet<_> = self.artifacts.ids().collect();
+
+		let res = self.get_cloned(promise);
+
+		let built: Vec<_> = self.artifacts.ids()
+			.filter(|bid| !cached.contains(bid))
+			.collect();
+
+		self.remove_by_ids(&built, false, &[]);
+
+		let introduced: Vec<_> = self.known_builders.keys()
+			.filter(|bid| **bid != promise.id() && !known.contains(bid))
+			.copied()
+			.collect();
+
+		if !known.contains(&promise.id()) {
+			self.purge(promise);
+		}
+		for bid in introduced {
+			self.purge_by_id(bid);
+		}
+
+		res
+	}
+
+	/// Get a reference to and a clone of the artifact of the given builder.
+	///
+	pub(crate) fn get_ref_and_clone<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<(&B::Artifact, B::Artifact), B::Err>
+			where
+				B: Builder<ArtCan, BCan>,
+				B::Artifact: Clone,
+				ArtCan: CanRef<B::Artifact>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+		self.get_ref(promise).map(|art| {
+			(art, art.clone())
+		})
+	}
+
 
 	/// Ensure given dyn state exists and return it by reference.
 	///
-	fn ensure_dyn_state<AP, B: ?Sized>(
+	pub(crate) fn ensure_dyn_state<AP, B: ?Sized>(
 			&mut self, promise: &AP
 		) -> &mut B::DynState
 			where
@@ -688,25 +2311,63 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 				BCan: Can<AP::Builder>,
 				AP: Promise<Builder = B, BCan = BCan> {
 
+		self.assert_config_not_frozen();
+
+		// Diagnostics
+		#[cfg(feature = "diagnostics")]
+		self.doctor.dyn_state_changed(&BuilderHandle::new(promise));
+
 		// Since the user choses `mut` he intends to modify the dyn state this
 		// requires the rebuild the artifact.
 		// It is reasonable to invalidate it early as the cache is mutable
 		// bounded through the returned reference, so no intermediate rebuild
 		// can happen.
-		self.invalidate(promise);
+		if self.dyn_state_change_invalidates(promise.id()) {
+			self.invalidate(promise);
+		}
 
 		self.ensure_dyn_state(promise)
 	}
 
-	/// Gets the dynamic state of the given builder.
+	/// Exempts the artifact of the given builder from invalidation.
 	///
-	pub(crate) fn dyn_state<AP, B: ?Sized>(
-			&mut self, promise: &AP
-		) -> &B::DynState
+	pub(crate) fn freeze<AP>(&mut self, promise: &AP)
 			where
-				B: Builder<ArtCan, BCan>,
 				BCan: Can<AP::Builder>,
-				AP: Promise<Builder = B, BCan = BCan>  {
+				AP: Promise<BCan = BCan> {
+
+		self.make_builder_known(promise);
+		self.extras.frozen.insert(promise.id());
+	}
+
+	/// Subjects the artifact of the given builder to invalidation again.
+	///
+	pub(crate) fn unfreeze(&mut self, bid: BuilderId) {
+		self.extras.frozen.remove(&bid);
+	}
+
+	/// Tests whether the artifact of the given builder is frozen.
+	///
+	pub(crate) fn is_frozen(&self, bid: BuilderId) -> bool {
+		self.extras.frozen.contains(&bid)
+	}
+
+	/// Panics if the dyn states are frozen.
+	///
+	pub(crate) fn assert_config_not_frozen(&self) {
+		assert!(!self.extras.config_frozen,
+			"dynamic state modified while the configuration is frozen");
+	}
+
+	/// Gets the dynamic state of the given builder.
+	///
+	pub(crate) fn dyn_state<AP, B: ?Sized>(
+			&mut self, promise: &AP
+		) -> &B::DynState
+			where
+				B: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
 
 		// Here, no invalidation, because we do not allow the user to modify the
 		// dyn state.
@@ -739,73 +2400,574 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 				BCan: Can<AP::Builder>,
 				AP: Promise<Builder = B, BCan = BCan>  {
 
-		let bid = promise.id();
+		self.purge_by_id(promise.id());
+
+		#[cfg(feature = "diagnostics")]
+		self.doctor.invalidate(&BuilderHandle::new(promise));
+	}
+
+	/// Auxiliary purge function using an untyped (aka `dyn Any`)
+	/// `BuilderId`.
+	///
+	/// Unlike `purge`, this does not notify the doctor.
+	///
+	fn purge_by_id(&mut self, bid: BuilderId) {
+
+		// Purging overrides freezing
+		self.extras.frozen.remove(&bid);
 
 		// Remove weak reference of builder since we will remove all references
 		// to it
 		self.known_builders.remove(&bid);
+		#[cfg(feature = "diagnostics")]
+		self.extras.builder_texts.remove(&bid);
 
 		// Purge artifact & dyn state
-		self.artifacts.remove(&bid);
+		self.remove_artifact(bid, false);
 		self.dyn_states.remove(&bid);
 
 		// Invalidate dependents
-		self.invalidate_by_id(&promise.id());
+		self.extras.stats.invalidations += 1;
+		self.invalidate_by_id(&bid);
+
+		// The invalidation marks the builder as leaf, but it is no longer known
+		self.known_leaf_builder.remove(&bid);
+		self.extras.dirty.remove(&bid);
+		self.extras.rebuilders.remove(&bid);
+		self.extras.keep_stale.remove(&bid);
+		self.extras.stale.remove(&bid);
+		self.extras.build_generations.remove(&bid);
+		self.extras.declared.remove(&bid);
+		#[cfg(feature = "artifact_version")]
+		self.extras.artifact_versions.remove(&bid);
+		#[cfg(feature = "rebuild_rate")]
+		self.extras.build_times.remove(&bid);
+		#[cfg(feature = "dag_lint")]
+		self.extras.directly_fetched.remove(&bid);
+	}
 
-		#[cfg(feature = "diagnostics")]
-		self.doctor.invalidate(&BuilderHandle::new(promise));
+	/// Sets the sampler to time every `every_n`-th build.
+	///
+	pub(crate) fn set_build_sampler(
+			&mut self,
+			every_n: usize,
+			f: Box<dyn FnMut(BuilderId, Duration)>,
+		) {
+
+		assert!(every_n > 0, "Build sampling interval must not be zero");
+
+		self.extras.build_sampler = Some(BuildSampler {
+			every_n,
+			count: 0,
+			f,
+		});
 	}
 
-	/// Deletes all artifacts of this cache.
+	/// Removes the build sampler, if any.
 	///
-	pub(crate) fn clear_artifacts(&mut self) {
+	pub(crate) fn clear_build_sampler(&mut self) {
+		self.extras.build_sampler = None;
+	}
+
+	/// Sets the hooks called before and after each build.
+	///
+	pub(crate) fn set_build_hooks(
+			&mut self,
+			before: Box<dyn FnMut(BuilderId)>,
+			after: Box<dyn FnMut(BuilderId, bool)>,
+		) {
+
+		self.extras.build_hooks = Some(BuildHooks {
+			before,
+			after,
+		});
+	}
+
+	/// Removes the build hooks, if any.
+	///
+	pub(crate) fn clear_build_hooks(&mut self) {
+		self.extras.build_hooks = None;
+	}
+
+	/// Counts a build for the sampler and returns whether it is sampled.
+	///
+	fn sample_build(&mut self) -> bool {
+		if let Some(sampler) = &mut self.extras.build_sampler {
+			let sampled = sampler.count % sampler.every_n == 0;
+			sampler.count = sampler.count.wrapping_add(1);
+			sampled
+		} else {
+			false
+		}
+	}
+
+	/// Sets the provider of the seed base of each builder.
+	///
+	pub(crate) fn set_seed_provider(&mut self, f: Box<dyn Fn(BuilderId) -> u64>) {
+		self.extras.seed_provider = Some(f);
+	}
+
+	/// Removes the seed provider, if any.
+	///
+	pub(crate) fn clear_seed_provider(&mut self) {
+		self.extras.seed_provider = None;
+	}
+
+	/// Returns the seed for the current build of the given builder.
+	///
+	/// The seed base of the provider (or zero) is mixed with the build
+	/// generation of the builder using the SplitMix64 finalizer.
+	///
+	pub(crate) fn seed(&self, bid: BuilderId) -> u64 {
+		let base = self.extras.seed_provider.as_ref().map_or(0, |f| f(bid));
+		let generation = self.extras.build_generations.get(&bid).copied().unwrap_or(0);
+
+		let mut z = base.wrapping_add(generation.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^ (z >> 31)
+	}
+
+	/// Registers a callback to be called with the builder id of each removed
+	/// artifact.
+	///
+	pub(crate) fn on_evict(&mut self, cb: Box<dyn FnMut(BuilderId)>) {
+		self.extras.evict_callbacks.push(cb);
+	}
+
+	/// Removes the artifact of the given builder, calling its eviction hook.
+	///
+	/// If `retain` is set, the builder is marked as dirty and, if requested,
+	/// its artifact is kept as stale artifact. The cached error and the weak
+	/// dependencies of the builder are removed in any case.
+	///
+	/// Returns whether there was an artifact.
+	///
+	fn remove_artifact(&mut self, bid: BuilderId, retain: bool) -> bool {
+		let hook = self.extras.evict_hooks.remove(&bid);
+
+		self.extras.errors.remove(&bid);
+		self.extras.weak_dependencies.remove(&bid);
+
+		if let Some(can) = self.artifacts.remove(bid) {
+			if let Some(hook) = hook {
+				hook(&can);
+			}
+
+			for cb in &mut self.extras.evict_callbacks {
+				cb(bid);
+			}
+
+			if retain {
+				self.extras.dirty.insert(bid);
+			}
+
+			#[cfg(feature = "lru")]
+			{
+				if let Some(size) = self.extras.artifact_sizes.remove(&bid) {
+					self.extras.total_size -= size;
+				}
+				self.extras.last_access.remove(&bid);
+			}
+			#[cfg(feature = "provenance")]
+			self.extras.provenances.remove(&bid);
+
+			if retain && self.extras.keep_stale.contains(&bid) {
+				self.extras.stale.insert(bid, can);
+			}
+
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Removes all artifacts, calling their eviction hooks.
+	///
+	fn clear_artifacts_only(&mut self) {
+		for (bid, hook) in self.extras.evict_hooks.drain() {
+			if let Some(can) = self.artifacts.get(bid) {
+				hook(can);
+			}
+		}
+
+		for bid in self.artifacts.ids() {
+			for cb in &mut self.extras.evict_callbacks {
+				cb(bid);
+			}
+		}
+
 		self.artifacts.clear();
+		#[cfg(feature = "lru")]
+		{
+			self.extras.artifact_sizes.clear();
+			self.extras.last_access.clear();
+			self.extras.total_size = 0;
+		}
+		#[cfg(feature = "provenance")]
+		self.extras.provenances.clear();
+	}
+
+	/// Deletes all artifacts of this cache.
+	///
+	pub(crate) fn clear_artifacts(&mut self) -> ClearReport {
+		if !self.extras.frozen.is_empty() {
+			return self.clear_unfrozen_artifacts();
+		}
+
+		let report = ClearReport {
+			artifacts: self.artifacts.len(),
+			dyn_states: 0,
+			edges: self.number_of_edges(),
+		};
+
+		// Like invalidated ones, cleared artifacts are dirty
+		let cleared: Vec<_> = self.artifacts.ids().collect();
+		self.extras.dirty.extend(cleared);
+
+		self.clear_artifacts_only();
+		self.extras.stale.clear();
+		self.extras.errors.clear();
 		self.dependents.clear();
 		self.dependencies.clear();
+		self.extras.weak_dependencies.clear();
 
 		// Now, all know builders are leafs!
 		self.known_leaf_builder.extend(self.known_builders.keys());
 
+		report
+	}
+
+	/// Deletes all artifacts of this cache, except for the frozen ones.
+	///
+	fn clear_unfrozen_artifacts(&mut self) -> ClearReport {
+		let edges = self.number_of_edges();
+
+		let victims: Vec<_> = self.artifacts.ids()
+			.filter(|bid| !self.extras.frozen.contains(bid))
+			.collect();
+
+		self.invalidate_by_ids(&victims);
+		self.extras.stale.clear();
+		self.extras.errors.clear();
+
+		ClearReport {
+			artifacts: victims.len(),
+			dyn_states: 0,
+			edges: edges - self.number_of_edges(),
+		}
+	}
+
+	/// Returns the number of dependency relations between builders.
+	///
+	fn number_of_edges(&self) -> usize {
+		self.dependencies.values().map(HashSet::len).sum()
+	}
+
+	/// Deletes all cached errors of this cache.
+	///
+	pub(crate) fn clear_errors(&mut self) {
+		self.extras.errors.clear();
+	}
+
+	/// Returns the usage counters of this cache.
+	///
+	pub(crate) fn stats(&self) -> CacheStats {
+		self.extras.stats
+	}
+
+	/// Resets all usage counters of this cache.
+	///
+	pub(crate) fn reset_stats(&mut self) {
+		self.extras.stats = CacheStats::default();
 	}
 
 	/// Clears the entire cache including all kept promise, artifacts and
 	/// dynamic states.
 	///
-	pub(crate) fn clear_all(&mut self) {
-		self.artifacts.clear();
+	pub(crate) fn clear_all(&mut self) -> ClearReport {
+		let report = ClearReport {
+			artifacts: self.artifacts.len(),
+			dyn_states: self.dyn_states.len(),
+			edges: self.number_of_edges(),
+		};
+
+		self.clear_artifacts_only();
+		self.extras.errors.clear();
 		self.dyn_states.clear();
 		self.dependents.clear();
 		self.dependencies.clear();
+		self.extras.weak_dependencies.clear();
 		self.known_builders.clear();
+		#[cfg(feature = "diagnostics")]
+		self.extras.builder_texts.clear();
 		self.known_leaf_builder.clear();
+		self.extras.interned.clear();
+		self.extras.dirty.clear();
+		self.extras.rebuilders.clear();
+		self.extras.keep_stale.clear();
+		self.extras.stale.clear();
+		self.extras.build_generations.clear();
+		self.extras.declared.clear();
+		#[cfg(feature = "artifact_version")]
+		self.extras.artifact_versions.clear();
+		#[cfg(feature = "rebuild_rate")]
+		self.extras.build_times.clear();
+		#[cfg(feature = "dag_lint")]
+		self.extras.directly_fetched.clear();
+		self.extras.frozen.clear();
 
 		#[cfg(feature = "diagnostics")]
 		self.doctor.clear();
+
+		report
+	}
+
+	/// Imports the artifacts, dyn states, errors, dependency edges, and
+	/// known builders of `other`, emptying it.
+	///
+	/// Builders already known to this cache are skipped, i.e. this cache
+	/// wins on conflict. The artifacts and errors of the builders of `other`
+	/// depending on such a builder are not imported. The dependency edges of
+	/// all builders having an artifact after the import are unioned. The
+	/// watched, dirty, stale, and frozen state of the imported builders is
+	/// carried over.
+	///
+	pub(crate) fn merge_from(&mut self, other: &mut RawCache<ArtCan, BCan>) {
+		let imported: Vec<_> = other.known_builders.keys()
+			.filter(|bid| !self.known_builders.contains_key(bid))
+			.copied()
+			.collect();
+
+		// The artifacts of `other` depending on builders known to both caches
+		// might be built from other artifacts than those of this cache, which
+		// win, thus they are not imported
+		let mut outdated = HashSet::new();
+		let mut pending: Vec<_> = other.known_builders.keys()
+			.filter(|bid| self.known_builders.contains_key(bid))
+			.copied()
+			.collect();
+		while let Some(bid) = pending.pop() {
+			if let Some(set) = other.dependents.get(&bid) {
+				for dep in set {
+					if outdated.insert(*dep) {
+						pending.push(*dep);
+					}
+				}
+			}
+		}
+
+		// Import in the order of the last access in `other`
+		#[cfg(feature = "lru")]
+		let mut by_access: Vec<_> = other.extras.last_access.iter()
+			.map(|(bid, access)| (*access, *bid))
+			.collect();
+		#[cfg(feature = "lru")]
+		by_access.sort_unstable();
+
+		for bid in &imported {
+			let weak = other.known_builders.remove(bid).unwrap();
+			self.known_builders.insert(*bid, weak);
+
+			#[cfg(feature = "diagnostics")]
+			{
+				if let Some(text) = other.extras.builder_texts.remove(bid) {
+					self.extras.builder_texts.insert(*bid, text);
+				}
+			}
+			if let Some(dyn_state) = other.dyn_states.remove(bid) {
+				self.dyn_states.insert(*bid, dyn_state);
+			}
+			if let Some(rebuild) = other.extras.rebuilders.remove(bid) {
+				self.extras.rebuilders.insert(*bid, rebuild);
+			}
+			if other.extras.dirty.remove(bid) {
+				self.extras.dirty.insert(*bid);
+			}
+			if other.extras.keep_stale.remove(bid) {
+				self.extras.keep_stale.insert(*bid);
+			}
+			if let Some(can) = other.extras.stale.remove(bid) {
+				self.extras.stale.insert(*bid, can);
+			}
+			if let Some(gen) = other.extras.build_generations.remove(bid) {
+				self.extras.build_generations.insert(*bid, gen);
+			}
+			if let Some(declared) = other.extras.declared.remove(bid) {
+				self.extras.declared.insert(*bid, declared);
+			}
+			#[cfg(feature = "artifact_version")]
+			{
+				if let Some(version) = other.extras.artifact_versions.remove(bid) {
+					self.extras.artifact_versions.insert(*bid, version);
+				}
+			}
+			#[cfg(feature = "rebuild_rate")]
+			{
+				if let Some(times) = other.extras.build_times.remove(bid) {
+					self.extras.build_times.insert(*bid, times);
+				}
+			}
+
+			if outdated.contains(bid) {
+				// Treat the outdated artifact as invalidated
+				if let Some(art) = other.artifacts.remove(*bid) {
+					self.extras.dirty.insert(*bid);
+					if self.extras.keep_stale.contains(bid) {
+						self.extras.stale.insert(*bid, art);
+					}
+				}
+				continue;
+			}
+
+			if let Some(err) = other.extras.errors.remove(bid) {
+				self.extras.errors.insert(*bid, err);
+			}
+			if let Some(art) = other.artifacts.remove(*bid) {
+				self.artifacts.insert(*bid, art);
+
+				if other.extras.frozen.remove(bid) {
+					self.extras.frozen.insert(*bid);
+				}
+				if let Some(hook) = other.extras.evict_hooks.remove(bid) {
+					self.extras.evict_hooks.insert(*bid, hook);
+				}
+				#[cfg(feature = "lru")]
+				{
+					if let Some(size) = other.extras.artifact_sizes.remove(bid) {
+						self.extras.artifact_sizes.insert(*bid, size);
+						self.extras.total_size += size;
+					}
+				}
+				if let Some(weak) = other.extras.weak_dependencies.remove(bid) {
+					self.extras.weak_dependencies.insert(*bid, weak);
+				}
+				#[cfg(feature = "provenance")]
+				{
+					if let Some(provenance) = other.extras.provenances.remove(bid) {
+						self.extras.provenances.insert(*bid, provenance);
+					}
+				}
+			}
+		}
+
+		#[cfg(feature = "lru")]
+		for (_, bid) in by_access {
+			if self.artifacts.contains(bid) && !self.extras.last_access.contains_key(&bid) {
+				self.touch(bid);
+			}
+		}
+
+		let hasher = &self.hasher;
+
+		// Union the edges of all builders with an artifact, all of which
+		// are known to this cache by now
+		for (user, deps) in other.dependencies.drain() {
+			if !self.artifacts.contains(user) {
+				continue;
+			}
+			for dep in deps {
+				self.dependents.entry(dep)
+					.or_insert_with(|| HashSet::with_hasher(hasher.clone()))
+					.insert(user);
+				self.dependencies.entry(user)
+					.or_insert_with(|| HashSet::with_hasher(hasher.clone()))
+					.insert(dep);
+				self.known_leaf_builder.remove(&dep);
+			}
+		}
+
+		for bid in imported {
+			if self.dependents.get(&bid).map_or(true, |s| s.is_empty()) {
+				self.known_leaf_builder.insert(bid);
+			}
+		}
+
+		// All builders of `other` are known to this cache by now
+		#[cfg(feature = "dag_lint")]
+		self.extras.directly_fetched.extend(other.extras.directly_fetched.drain());
+
+		// Drop the remainders, which are inconsistent by now
+		other.clear_all();
+
+		#[cfg(feature = "lru")]
+		self.enforce_memory_budget(None);
 	}
 
 	/// Auxiliary invalidation function using an untyped (aka `dyn Any`)
 	/// `BuilderId`.
 	///
-	fn invalidate_by_id(&mut self, builder: &BuilderId) {
+	/// Returns which artifacts have been removed, and which dyn states have
+	/// been kept.
+	///
+	fn invalidate_by_id(&mut self, builder: &BuilderId) -> InvalidationReport {
+		self.invalidate_by_ids(std::slice::from_ref(builder))
+	}
+
+	/// Auxiliary invalidation function for multiple builders at once.
+	///
+	/// The dependents of all given builders are invalidated in a single
+	/// traversal, thus shared dependents are only processed once.
+	///
+	fn invalidate_by_ids(&mut self, builders: &[BuilderId]) -> InvalidationReport {
+		self.remove_by_ids(builders, true, &[])
+	}
+
+	/// Auxiliary eviction function for multiple builders at once.
+	///
+	/// Like `invalidate_by_ids` but, since eviction only happens to reclaim
+	/// memory, the removed artifacts are neither kept as stale artifacts nor
+	/// are their builders marked as dirty.
+	///
+	#[cfg(feature = "lru")]
+	fn evict_by_ids(&mut self, builders: &[BuilderId]) -> InvalidationReport {
+		self.remove_by_ids(builders, false, &[])
+	}
+
+	/// Removes the artifacts of the given builders and their dependents.
+	///
+	/// If `retain` is set, the removed artifacts are kept as stale artifacts
+	/// where requested and their builders are marked as dirty, see
+	/// `remove_artifact`.
+	///
+	/// The `keep` builders are spared just like frozen ones.
+	///
+	fn remove_by_ids(
+			&mut self,
+			builders: &[BuilderId],
+			retain: bool,
+			keep: &[BuilderId]
+		) -> InvalidationReport {
+
+		let mut report = InvalidationReport::default();
 
 		// Remember already processed builders, because they have no more
 		// dependencies mapping.
 		let mut processed = HashSet::new();
-		processed.insert(*builder);
 
 		// Stack of builder to be invalidated.
-		let mut pending = Vec::new();
-		pending.push(*builder);
+		let mut pending = builders.to_vec();
 
 
 		while let Some(bid) = pending.pop() {
-			// Mark builder as processed
-			processed.insert(bid);
+			// Mark builder as processed, skip it if it was already
+			if self.extras.frozen.contains(&bid) || keep.contains(&bid) || !processed.insert(bid) {
+				continue;
+			}
 
-			// Get all dependents and invalidate them too
-			if let Some(set) = self.dependents.remove(&bid) {
-				for dep in set {
-					pending.push(dep);
+			// Get all dependents and invalidate them too, except for frozen
+			// ones, which keep their artifact and thus their relation
+			if let Some(mut set) = self.dependents.remove(&bid) {
+				set.retain(|dep| {
+					let frozen = self.extras.frozen.contains(dep) || keep.contains(dep);
+					if !frozen {
+						pending.push(*dep);
+					}
+					frozen
+				});
+
+				if !set.is_empty() {
+					self.dependents.insert(bid, set);
 				}
 			}
 
@@ -838,8 +3000,90 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 			// leaf now!
 			self.known_leaf_builder.insert(bid);
 
-			self.artifacts.remove(&bid);
+			if self.remove_artifact(bid, retain) {
+				#[cfg(feature = "log")]
+				log::debug!(target: "daab", "invalidated {:?}", bid);
+
+				report.invalidated_artifacts.push(bid);
+			}
+			if self.dyn_states.contains_key(&bid) {
+				report.preserved_dyn_states.push(bid);
+			}
+
+		}
+
+		report
+	}
+
+	/// Auxiliary depth limited invalidation function using an untyped (aka
+	/// `dyn Any`) `BuilderId`.
+	///
+	/// Invalidates the given builder and its dependents up to `max_depth`
+	/// hops. The dependents of the builders at `max_depth` keep their
+	/// artifacts and also their dependency relation.
+	///
+	fn invalidate_to_depth_by_id(&mut self, builder: &BuilderId, max_depth: usize) {
+
+		// Remember already processed builders, since the BFS visits each
+		// builder first with its minimal depth.
+		let mut processed = HashSet::new();
+
+		// Queue of builders to be invalidated along with their depth.
+		let mut pending = VecDeque::new();
+		pending.push_back((*builder, 0));
+
+
+		while let Some((bid, depth)) = pending.pop_front() {
+			if self.extras.frozen.contains(&bid) || !processed.insert(bid) {
+				continue;
+			}
+
+			// Get dependents and invalidate them too, unless too deep or
+			// frozen. In the latter case, they keep depending on `bid`.
+			if depth < max_depth {
+				if let Some(mut set) = self.dependents.remove(&bid) {
+					set.retain(|dep| {
+						let frozen = self.extras.frozen.contains(dep);
+						if !frozen {
+							pending.push_back((*dep, depth + 1));
+						}
+						frozen
+					});
+
+					if !set.is_empty() {
+						self.dependents.insert(bid, set);
+					}
+				}
+			}
+
+			// Remove dependencies too
+			if let Some(set) = self.dependencies.remove(&bid) {
+				for dep in set {
+					// The dependents of processed builders might have been
+					// removed already, or they might have been kept due to
+					// the depth limit.
+					if let Some(dependents) = self.dependents.get_mut(&dep) {
+						dependents.remove(&bid);
+
+						// Check whether this depenencies has other dependents
+						// left, or whether it became a leaf now.
+						if dependents.is_empty() {
+							self.known_leaf_builder.insert(dep);
+						}
+					}
+				}
+			}
+
+			// bid is a leaf now, unless it kept its dependents
+			let is_leaf = self.dependents.get(&bid).map_or(true, HashSet::is_empty);
+			if is_leaf {
+				self.known_leaf_builder.insert(bid);
+			}
 
+			if self.remove_artifact(bid, true) {
+				#[cfg(feature = "log")]
+				log::debug!(target: "daab", "invalidated {:?}", bid);
+			}
 		}
 
 	}
@@ -848,22 +3092,90 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 	/// `BuilderId`, only invalidates dependents not the given build itself.
 	///
 	fn invalidate_dependents(&mut self, builder: &BuilderId) {
-		if let Some(set) = self.dependents.remove(builder) {
-			for dep in set {
-				self.invalidate_by_id(&dep);
-			}
-		}
+		// Keep the set in place, the invalidation of each dependent removes
+		// itself from it via its dependencies
+		let deps: Vec<_> = self.dependents.get(builder)
+			.map(|set| set.iter().copied().collect())
+			.unwrap_or_default();
+
+		self.invalidate_by_ids(&deps);
 
 		// Now, `builder` has no more depenencies, i.e. it is a leaf
 		self.known_leaf_builder.insert(*builder);
 	}
 
+	/// Like `invalidate_dependents`, but the given owner keeps its artifact
+	/// and its dependency upon `builder`.
+	///
+	/// The same holds for the builders whose builds are in progress, since
+	/// invalidating them would drop the dependencies they resolved so far.
+	///
+	#[cfg(feature = "mut_box")]
+	fn invalidate_dependents_except(&mut self, builder: &BuilderId, owner: BuilderId) {
+		let mut keep = self.extras.building.clone();
+		keep.push(owner);
+
+		let deps: Vec<_> = self.dependents.get(builder)
+			.map(|set| set.iter().copied().filter(|dep| !keep.contains(dep)).collect())
+			.unwrap_or_default();
+
+		self.remove_by_ids(&deps, true, &keep);
+
+		// `builder` is a leaf now, unless a kept builder depends on it
+		if self.dependents.get(builder).map_or(true, HashSet::is_empty) {
+			self.known_leaf_builder.insert(*builder);
+		}
+	}
+
 	/// Removes the given promise with its cached artifact from the cache and
 	/// all depending artifacts (with their promises).
 	///
 	pub(crate) fn invalidate<AP, B: ?Sized>(
 			&mut self,
 			promise: &AP
+		) -> InvalidationReport
+			where
+				B: Debug + 'static,
+				BCan: Can<AP::Builder>,
+				AP: Promise<Builder = B, BCan = BCan>  {
+
+
+		self.extras.stats.invalidations += 1;
+		let report = self.invalidate_by_id(&promise.id());
+
+		#[cfg(feature = "diagnostics")]
+		self.doctor.invalidate(&BuilderHandle::new(promise));
+
+		report
+	}
+
+	/// Invalidates the given builders in a single traversal.
+	///
+	/// The builders are expected to be distinct.
+	///
+	pub(crate) fn invalidate_batch(
+			&mut self,
+			builders: &[BuilderId],
+			#[cfg(feature = "diagnostics")]
+			diag_builders: &[BuilderHandle<BCan>],
+		) {
+
+		self.extras.stats.invalidations += builders.len() as u64;
+		self.invalidate_by_ids(builders);
+
+		#[cfg(feature = "diagnostics")]
+		for handle in diag_builders {
+			self.doctor.invalidate(handle);
+		}
+	}
+
+	/// Removes the given promise with its cached artifact from the cache and
+	/// the depending artifacts up to `max_depth` hops away.
+	///
+	pub(crate) fn invalidate_to_depth<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP,
+			max_depth: usize,
 		)
 			where
 				B: Debug + 'static,
@@ -871,75 +3183,440 @@ impl<ArtCan, BCan> RawCache<ArtCan, BCan>
 				AP: Promise<Builder = B, BCan = BCan>  {
 
 
-		self.invalidate_by_id(&promise.id());
+		self.extras.stats.invalidations += 1;
+		self.invalidate_to_depth_by_id(&promise.id(), max_depth);
+
+		#[cfg(feature = "diagnostics")]
+		self.doctor.invalidate(&BuilderHandle::new(promise));
+
+	}
+
+	/// Invalidates all builders and their dyn state which can not be builded
+	/// any more, because there are no more references to them.
+	///
+	/// Frozen builders are roots of the collection, i.e. a builder with a
+	/// frozen dependent is not collected, since the frozen artifact keeps
+	/// depending on it. An unreachable frozen builder itself is collected,
+	/// which unfreezes it.
+	///
+	/// Returns the ids of the removed builders.
+	///
+	pub(crate) fn garbage_collection(&mut self) -> Vec<BuilderId> {
+
+		// Just checking there is programming flaw here, it's really not relevant for run time.
+		debug_assert!(
+			// Check invarinant. All known builders are either leaf builders or have at least one
+			// dependent.
+			self.known_builders.keys()
+				// Fine if registered as leaf_builder
+				.filter(|bid| !self.known_leaf_builder.contains(bid))
+				// Enuse the others have a depenent
+				.all(|bid| !self.dependents[bid].is_empty())
+		);
+
+		// Removing artifacts and dyn states may drop the last references to
+		// further builders (e.g. promises stored in a dyn state), thus repeat
+		// until no more builders become unreachable.
+		let mut purged = Vec::new();
+
+		loop {
+			// Only check the leaf builders and those only weakly depended on
+			let unreachable_builder_ids: Vec<_> = self.known_leaf_builder.iter()
+				.chain(
+					self.dependents.iter()
+						.filter(|(bid, deps)| {
+							!deps.is_empty() && deps.iter().all(|dep| {
+								self.extras.weak_dependencies.get(dep)
+									.map_or(false, |weak| weak.contains(bid))
+							})
+						})
+						.map(|(bid, _)| bid)
+				)
+				// Keep those a frozen artifact depends on
+				.filter(|bid| {
+					!self.dependents.get(bid).into_iter().flatten()
+						.any(|dep| self.extras.frozen.contains(dep))
+				})
+				// Only retain those which can't be upgraded (i.e. no strong
+				// references exist any more).
+				.filter(|bid| BCan::upgrade_from_weak(&self.known_builders[bid]).is_none())
+				.copied()
+				.collect();
+
+			if unreachable_builder_ids.is_empty() {
+				break;
+			}
+
+			for &bid in &unreachable_builder_ids {
+				#[cfg(feature = "log")]
+				log::debug!(target: "daab", "collected {:?}", bid);
+
+				self.extras.frozen.remove(&bid);
+				self.invalidate_by_id(&bid);
+				self.dyn_states.remove(&bid);
+				self.known_builders.remove(&bid);
+				self.known_leaf_builder.remove(&bid);
+				self.extras.dirty.remove(&bid);
+				self.extras.rebuilders.remove(&bid);
+				self.extras.keep_stale.remove(&bid);
+				self.extras.stale.remove(&bid);
+				self.extras.build_generations.remove(&bid);
+				self.extras.declared.remove(&bid);
+				#[cfg(feature = "artifact_version")]
+				self.extras.artifact_versions.remove(&bid);
+				#[cfg(feature = "rebuild_rate")]
+				self.extras.build_times.remove(&bid);
+				#[cfg(feature = "dag_lint")]
+				self.extras.directly_fetched.remove(&bid);
+				#[cfg(feature = "diagnostics")]
+				self.extras.builder_texts.remove(&bid);
+			}
+
+			purged.extend(unreachable_builder_ids);
+		}
+
+		purged
+	}
+
+	/// Returns the interned blueprint equal to the given builder, creating
+	/// and interning one if there is none yet.
+	///
+	pub(crate) fn intern<B>(&mut self, builder: B) -> Blueprint<B, BCan>
+			where
+				B: Hash + Eq + Debug + 'static,
+				BCan: CanSized<B>,
+				BCan::Bin: AsRef<B> + Clone {
+
+		let mut hasher = DefaultHasher::new();
+		builder.hash(&mut hasher);
+		let hash = hasher.finish();
+
+		let buckets = self.extras.interned.entry(TypeId::of::<B>())
+			.or_insert_with(
+				|| Box::new(HashMap::<u64, Vec<Blueprint<B, BCan>>>::new())
+			)
+			.downcast_mut::<HashMap<u64, Vec<Blueprint<B, BCan>>>>()
+			.expect("Interned blueprints are of invalid type");
+
+		let bucket = buckets.entry(hash).or_default();
+
+		if let Some(bp) = bucket.iter().find(|bp| bp.builder().builder == &builder) {
+			bp.clone()
+		} else {
+			let bp = Blueprint::new(builder);
+			bucket.push(bp.clone());
+			bp
+		}
+	}
+
+	/// Forgets all interned blueprints.
+	///
+	pub(crate) fn clear_interned(&mut self) {
+		self.extras.interned.clear();
+	}
+
+	/// Checks all internal invariants, panicking on the first violation.
+	///
+	#[cfg(any(test, feature = "validate"))]
+	pub(crate) fn assert_consistent(&self) {
+		// All mapped builders must be known
+		let artifact_ids: Vec<_> = self.artifacts.ids().collect();
+		let mapped = artifact_ids.iter()
+			.chain(self.dyn_states.keys())
+			.chain(self.extras.errors.keys())
+			.chain(self.extras.evict_hooks.keys())
+			.chain(self.extras.weak_dependencies.keys())
+			.chain(self.dependents.keys())
+			.chain(self.dependents.values().flatten())
+			.chain(self.dependencies.keys())
+			.chain(self.dependencies.values().flatten())
+			.chain(self.known_leaf_builder.iter())
+			.chain(self.extras.dirty.iter())
+			.chain(self.extras.rebuilders.keys())
+			.chain(self.extras.keep_stale.iter())
+			.chain(self.extras.stale.keys())
+			.chain(self.extras.build_generations.keys())
+			.chain(self.extras.declared.keys())
+			.chain(self.extras.frozen.iter());
+
+		for bid in mapped {
+			assert!(self.known_builders.contains_key(bid),
+				"Builder {:?} is mapped, but not known", bid);
+		}
+		#[cfg(feature = "artifact_version")]
+		for bid in self.extras.artifact_versions.keys() {
+			assert!(self.known_builders.contains_key(bid),
+				"Builder {:?} has an artifact version, but is not known", bid);
+		}
+		#[cfg(feature = "rebuild_rate")]
+		for bid in self.extras.build_times.keys() {
+			assert!(self.known_builders.contains_key(bid),
+				"Builder {:?} has build times, but is not known", bid);
+		}
+		#[cfg(feature = "dag_lint")]
+		for bid in self.extras.directly_fetched.iter() {
+			assert!(self.known_builders.contains_key(bid),
+				"Builder {:?} is fetched directly, but not known", bid);
+		}
+		#[cfg(feature = "diagnostics")]
+		for bid in self.extras.builder_texts.keys() {
+			assert!(self.known_builders.contains_key(bid),
+				"Builder {:?} has a text, but is not known", bid);
+		}
+
+		// Artifacts require a dyn state, and eviction hooks an artifact
+		for bid in &artifact_ids {
+			assert!(self.dyn_states.contains_key(bid),
+				"Builder {:?} has an artifact, but no dyn state", bid);
+		}
+		for bid in self.extras.evict_hooks.keys() {
+			assert!(self.artifacts.contains(*bid),
+				"Builder {:?} has an eviction hook, but no artifact", bid);
+		}
+		#[cfg(feature = "lru")]
+		for bid in self.extras.artifact_sizes.keys().chain(self.extras.last_access.keys()) {
+			assert!(self.artifacts.contains(*bid),
+				"Builder {:?} has a size or access time, but no artifact", bid);
+		}
+		#[cfg(feature = "provenance")]
+		for bid in self.extras.provenances.keys() {
+			assert!(self.artifacts.contains(*bid),
+				"Builder {:?} has a provenance, but no artifact", bid);
+		}
+		#[cfg(feature = "lru")]
+		assert_eq!(self.extras.total_size,
+			self.extras.artifact_sizes.values().sum::<usize>(),
+			"Total size does not match the artifact sizes");
+		for bid in self.extras.weak_dependencies.keys() {
+			assert!(self.artifacts.contains(*bid),
+				"Builder {:?} has weak dependencies, but no artifact", bid);
+		}
+
+		// `dependents` and `dependencies` must mirror each other
+		for (bid, set) in &self.dependents {
+			for dep in set {
+				assert!(self.dependencies.get(dep).map_or(false, |s| s.contains(bid)),
+					"Builder {:?} is dependent of {:?}, but lacks the dependency",
+					dep, bid);
+			}
+		}
+		for (bid, set) in &self.dependencies {
+			for dep in set {
+				assert!(self.dependents.get(dep).map_or(false, |s| s.contains(bid)),
+					"Builder {:?} is dependency of {:?}, but lacks the dependent",
+					dep, bid);
+			}
+		}
+
+		// Known builders are either leafs or have a dependent
+		for bid in self.known_builders.keys() {
+			assert!(
+				self.known_leaf_builder.contains(bid)
+					|| self.dependents.get(bid).map_or(false, |s| !s.is_empty()),
+				"Builder {:?} is neither a leaf nor has a dependent", bid);
+		}
+	}
+
+	/// Enlist given builder as known builder, that is to keep its weak
+	/// reference while it is used in `cache` or `dyn_state`.
+	fn make_builder_known<AP>(
+			&mut self,
+			promise: &AP
+		)
+			where
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan>  {
+
+		let bid = promise.id();
+
+		let leafs = &mut self.known_leaf_builder;
+		#[cfg(feature = "diagnostics")]
+		let texts = &mut self.extras.builder_texts;
+
+		self.known_builders.entry(bid).or_insert_with(
+			|| {
+				// Here, the builder was not known befor!
+				// Thus it must be a leaf
+				leafs.insert(bid);
+
+				// Keep its text for finding duplicates
+				#[cfg(feature = "diagnostics")]
+				texts.insert(bid, (
+					std::any::type_name::<AP::Builder>(),
+					match promise.debug_fn() {
+						Some(f) => BuilderText::Lazy(f),
+						None => BuilderText::Eager(
+							format!("{:?}", promise.builder().builder)),
+					},
+				));
+
+				// Return downgraded can
+				promise.canned().can.downgrade()
+			}
+		);
+	}
+
+	/// Groups the known builders with identical type and `Debug` text.
+	///
+	/// Only groups of at least two builders are returned. Each group is
+	/// sorted, as is the list of groups.
+	///
+	#[cfg(feature = "diagnostics")]
+	pub(crate) fn find_duplicate_builders(&self) -> Vec<Vec<BuilderId>> {
+		let mut groups: HashMap<(&'static str, String), Vec<BuilderId>> = HashMap::new();
+
+		for (bid, (type_name, text)) in &self.extras.builder_texts {
+			let text = match text {
+				BuilderText::Eager(text) => text.clone(),
+				BuilderText::Lazy(f) => {
+					// Builders which are gone can't be duplicates any more
+					match BCan::upgrade_from_weak(&self.known_builders[bid]).and_then(f) {
+						Some(text) => text,
+						None => continue,
+					}
+				}
+			};
+
+			groups.entry((type_name, text)).or_default().push(*bid);
+		}
+
+		let mut duplicates: Vec<_> = groups.values_mut()
+			.filter(|group| group.len() > 1)
+			.map(|group| {
+				group.sort();
+				std::mem::take(group)
+			})
+			.collect();
+
+		duplicates.sort();
+		duplicates
+	}
+
+	/// Returns the number of currently kept artifact promises.
+	///
+	pub(crate) fn number_of_known_builders(&self) -> usize {
+		self.known_builders.len()
+	}
+
+	/// Returns the ids of the builders which would be built by a `get` of
+	/// the given promise, as far as it can be determined in advance.
+	///
+	/// Walks the recorded `dependencies` and the declared dependencies
+	/// starting at the given promise and collects all builders which lack an
+	/// artifact. Builders with an artifact are not descended into, since they
+	/// would not be built.
+	///
+	/// The edges of both kinds are united at every builder. The declared
+	/// dependencies of builders reached only by recorded edges are taken
+	/// from their last build, since there is no typed access to them.
+	///
+	pub(crate) fn plan<AP: ?Sized>(
+			&self,
+			promise: &AP
+		) -> Vec<BuilderId>
+			where
+				AP::Builder: Builder<ArtCan, BCan>,
+				BCan: Can<AP::Builder>,
+				AP: Promise<BCan = BCan> {
+
+		let mut planned = Vec::new();
+
+		if self.artifacts.contains(promise.id()) {
+			return planned;
+		}
 
-		#[cfg(feature = "diagnostics")]
-		self.doctor.invalidate(&BuilderHandle::new(promise));
+		// Remember visited builders, because the DAG may contain diamonds
+		let mut visited = HashSet::new();
 
-	}
+		// Remember the builders whose declared dependencies are descended
+		// into, since a builder might be reached first by a recorded edge
+		let mut expanded = HashSet::new();
+		expanded.insert(promise.id());
 
-	/// Invalidates all builders and their dyn state which can not be builded
-	/// any more, because there are no more references to them.
-	///
-	pub(crate) fn garbage_collection(&mut self) {
+		// Stack of builder to be checked, along with the declaration of its
+		// dependencies, if known. The given promise is checked first.
+		let mut pending: Vec<_> = promise.builder().builder.declare_dependencies()
+			.into_iter()
+			.map(|dep| (dep.id(), Some(dep)))
+			.collect();
+		pending.push((promise.id(), None));
 
-		// Just checking there is programming flaw here, it's really not relevant for run time.
-		debug_assert!(
-			// Check invarinant. All known builders are either leaf builders or have at least one
-			// dependent.
-			self.known_builders.keys()
-				// Fine if registered as leaf_builder
-				.filter(|bid| !self.known_leaf_builder.contains(bid))
-				// Enuse the others have a depenent
-				.all(|bid| !self.dependents[bid].is_empty())
-		);
+		while let Some((bid, declared)) = pending.pop() {
+			if self.artifacts.contains(bid) {
+				continue;
+			}
 
-		// Only check the leaf builders
-		let unreachable_builder_ids: Vec<_> = self.known_leaf_builder.iter()
-			// Only retain those which can't be upgraded (i.e. no strong
-			// references exist any more).
-			.filter(|bid| BCan::upgrade_from_weak(&self.known_builders[bid]).is_none())
-			.copied()
-			.collect();
+			// Descend into the dependencies of the last build, if recorded
+			if visited.insert(bid) {
+				planned.push(bid);
+
+				if let Some(set) = self.dependencies.get(&bid) {
+					pending.extend(set.iter().map(|dep| (*dep, None)));
+				}
+				if let Some(ids) = self.extras.declared.get(&bid) {
+					pending.extend(ids.iter().map(|dep| (*dep, None)));
+				}
+			}
 
-		for bid in unreachable_builder_ids {
-			self.invalidate_by_id(&bid);
-			self.dyn_states.remove(&bid);
-			self.known_builders.remove(&bid);
-			self.known_leaf_builder.remove(&bid);
+			// Descend into the declared dependencies
+			if let Some(declared) = declared {
+				if expanded.insert(bid) {
+					pending.extend(declared.declared_dependencies().into_iter().map(|dep| {
+						(dep.id(), Some(dep))
+					}));
+				}
+			}
 		}
+
+		planned
 	}
+}
 
-	/// Enlist given builder as known builder, that is to keep its weak
-	/// reference while it is used in `cache` or `dyn_state`.
-	fn make_builder_known<AP>(
+impl<BCan> RawCache<crate::rc::CanType, BCan>
+		where
+			BCan: CanStrong {
+
+	/// Gets a mutable reference to the artifact of the given builder,
+	/// cloning it first if it is shared.
+	///
+	/// All dependents are invalidated, as with `get_mut`.
+	///
+	pub(crate) fn make_mut<AP, B: ?Sized>(
 			&mut self,
 			promise: &AP
-		)
+		) -> Result<&mut B::Artifact, B::Err>
 			where
+				B: Builder<crate::rc::CanType, BCan>,
+				B::Artifact: Clone,
 				BCan: Can<AP::Builder>,
-				AP: Promise<BCan = BCan>  {
+				AP: Promise<Builder = B, BCan = BCan>  {
 
-		let bid = promise.id();
+		let id = promise.id();
 
-		let leafs = &mut self.known_leaf_builder;
+		// Ensure the artifact exists, dropping the returned `Rc` right away
+		self.get(promise)?;
 
-		self.known_builders.entry(bid).or_insert_with(
-			|| {
-				// Here, the builder was not known befor!
-				// Thus it must be a leaf
-				leafs.insert(bid);
+		// The artifact is going to be modified
+		self.invalidate_dependents(&id);
 
-				// Return downgraded can
-				promise.canned().can.downgrade()
-			}
-		);
-	}
+		let can = self.artifacts.get_mut(id)
+			.expect("Just gotten artifact is missing");
 
-	/// Returns the number of currently kept artifact promises.
-	///
-	pub(crate) fn number_of_known_builders(&self) -> usize {
-		self.known_builders.len()
+		// Replace a shared artifact by a private clone (copy-on-write)
+		if std::rc::Rc::get_mut(can).is_none() {
+			let art: B::Artifact = can.downcast_ref::<B::Artifact>()
+				.expect("Cached artifact is of invalid type")
+				.clone();
+
+			*can = std::rc::Rc::new(art);
+		}
+
+		Ok(
+			std::rc::Rc::get_mut(can)
+				.expect("Cached artifact is still shared")
+				.downcast_mut()
+				.expect("Cached artifact is of invalid type")
+		)
 	}
 }
 
@@ -951,6 +3628,7 @@ mod test {
 	use super::*;
 	use crate::prelude::*;
 	use crate::Blueprint;
+	use crate::Never;
 	use crate::test::*;
 	use std::rc::Rc;
 	use std::sync::Arc;
@@ -1022,7 +3700,7 @@ mod test {
 		}
 		else {
 			fn new_cache_rc() -> RawCache<Rc<dyn Any>, Rc<dyn Any>> {
-				RawCache::new()
+				RawCache::with_hasher(ErasedState::default())
 			}
 		}
 	}
@@ -1035,7 +3713,7 @@ mod test {
 		}
 		else {
 			fn new_cache_box() -> RawCache<Box<dyn Any>, Arc<dyn Any + Send + Sync>> {
-				RawCache::new()
+				RawCache::with_hasher(ErasedState::default())
 			}
 		}
 	}
@@ -1064,6 +3742,30 @@ mod test {
 		assert!(!cache.contains_artifact(&bp));
 	}
 
+	#[test]
+	fn contains_artifact_by_id() {
+		let builder = BuilderLeaf::new();
+		let bp = Blueprint::new(builder);
+		let id = bp.id();
+
+		let mut cache_owned = new_cache_rc();
+		let cache: &mut RawCache<Rc<dyn Any>, Rc<dyn Any>> = &mut cache_owned;
+
+		assert!(!cache.contains_artifact_by_id(id));
+
+		cache.dyn_state(&bp);
+
+		assert!(!cache.contains_artifact_by_id(id));
+
+		cache.build(&bp).unpack();
+
+		assert!(cache.contains_artifact_by_id(id));
+
+		cache.invalidate(&bp);
+
+		assert!(!cache.contains_artifact_by_id(id));
+	}
+
 	#[test]
 	fn is_builder_known() {
 		let builder = BuilderLeaf::new();
@@ -1345,6 +4047,50 @@ mod test {
 		assert_eq!(Some(art_n), cache.lookup(&bp));
 	}
 
+	#[test]
+	#[cfg(feature = "checked")]
+	fn checked_get() {
+		let builder = BuilderLeafFallible::new();
+		let bp = Blueprint::new(builder);
+
+		let mut cache_owned = new_cache_rc();
+		let cache: &mut RawCache<Rc<dyn Any>, Rc<dyn Any>> = &mut cache_owned;
+
+		assert_eq!(Ok(None), cache.checked_lookup(&bp));
+
+		*cache.dyn_state_mut(&bp) = false;
+		assert_eq!(Err(CacheError::Build(())), cache.checked_get(&bp));
+
+		*cache.dyn_state_mut(&bp) = true;
+		let art = cache.checked_get(&bp).unwrap();
+		assert!(Rc::ptr_eq(&art, &cache.checked_lookup(&bp).unwrap().unwrap()));
+		assert!(Rc::ptr_eq(&art, &cache.checked_get(&bp).unwrap()));
+	}
+
+	#[test]
+	fn get_fresh() {
+		let builder = BuilderLeaf::new();
+		let bp = Blueprint::new(builder);
+
+		let mut cache_owned = new_cache_rc();
+		let cache: &mut RawCache<Rc<dyn Any>, Rc<dyn Any>> = &mut cache_owned;
+
+		let (art, fresh) = cache.get_fresh(&bp).unpack();
+		assert!(fresh);
+		assert_eq!(Some(art), cache.lookup(&bp));
+
+		let (art, fresh) = cache.get_fresh(&bp).unpack();
+		assert!(!fresh);
+		assert!(Rc::ptr_eq(&art, &cache.lookup(&bp).unwrap()));
+
+		// Invalidate to retrieve a fresh artifact
+		cache.invalidate(&bp);
+
+		let (art_n, fresh) = cache.get_fresh(&bp).unpack();
+		assert!(fresh);
+		assert!(!Rc::ptr_eq(&art, &art_n));
+	}
+
 	#[test]
 	fn get_ref() {
 		let builder = BuilderLeaf::new();
@@ -1423,6 +4169,22 @@ mod test {
 		assert_ne!(old_art, art_n);
 	}
 
+	#[test]
+	fn get_ref_and_clone() {
+		let builder = BuilderLeaf::new();
+		let bp = Blueprint::new(builder);
+
+		let mut cache_owned = new_cache_box();
+		let cache: &mut RawCache<Box<dyn Any>, Arc<dyn Any + Send + Sync>> = &mut cache_owned;
+
+		let (art_ref, art) = cache.get_ref_and_clone(&bp).unpack();
+		assert_eq!(art_ref, &art);
+		let art_ptr = art_ref as *const _;
+
+		assert_eq!(cache.lookup_ref(&bp).map(|a| a as *const _), Some(art_ptr));
+		assert_eq!(Some(art), cache.lookup_cloned(&bp));
+	}
+
 	#[test]
 	fn ensure_dyn_state() {
 		let builder = BuilderLeafFallible::new();
@@ -1501,6 +4263,26 @@ mod test {
 		assert_eq!(dyn_state_ptr, ptr(cache.dyn_state(&bp)));
 	}
 
+	#[test]
+	fn get_with_state() {
+		let builder = BuilderLeafFallible::new();
+		let bp = Blueprint::new(builder);
+
+		let mut cache_owned = new_cache_rc();
+		let cache: &mut RawCache<Rc<dyn Any>, Rc<dyn Any>> = &mut cache_owned;
+
+		*cache.dyn_state_mut(&bp) = false;
+		assert_eq!(Err(()), cache.get_with_state(&bp).map(|_| ()));
+
+		*cache.dyn_state_mut(&bp) = true;
+		let (art, state) = cache.get_with_state(&bp).unwrap();
+		let art_ptr = ptr(art);
+		assert!(*state);
+
+		assert_eq!(art_ptr, ptr(cache.lookup_ref(&bp).unwrap()));
+		assert_eq!(art_ptr, ptr(cache.get_with_state(&bp).unwrap().0));
+	}
+
 	#[test]
 	fn dyn_state_mut() {
 		let builder = BuilderLeafFallible::new();
@@ -1941,9 +4723,8 @@ mod test {
 		drop(end_bp);
 
 		// Clean only mid & end
-		cache.garbage_collection();
-		// BuilderVariableNode requires additional GC cycles
-		// because it stores APs in its dyn state!
+		// Even though BuilderVariableNode stores APs in its dyn state, a
+		// single GC run suffices
 		cache.garbage_collection();
 
 		assert_eq!(1, cache.number_of_known_builders());
@@ -1954,6 +4735,221 @@ mod test {
 		assert!(cache.get_dyn_state(&base_bp).is_some());
 	}
 
+	#[test]
+	fn plan() {
+		let base_bp = Blueprint::new(BuilderLeafFallible::new());
+
+		let builder = BuilderVariableNode::new::<Rc<dyn Any>, Rc<dyn Any>>(base_bp.clone());
+		let mid_bp = Blueprint::new(builder);
+
+		let builder = BuilderVariableNode::new::<Rc<dyn Any>, Rc<dyn Any>>(mid_bp.clone());
+		let end_bp = Blueprint::new(builder);
+
+		let mut cache_owned = new_cache_rc();
+		let cache: &mut RawCache<Rc<dyn Any>, Rc<dyn Any>> = &mut cache_owned;
+
+		// Unknown builders are planned by themselves
+		assert_eq!(vec![end_bp.id()], cache.plan(&end_bp));
+
+		cache.get(&end_bp).unwrap();
+
+		// Nothing to be built
+		assert!(cache.plan(&end_bp).is_empty());
+		assert!(cache.plan(&base_bp).is_empty());
+
+		cache.invalidate(&base_bp);
+
+		// Invalidation drops the dependency records, thus only the root
+		assert_eq!(vec![end_bp.id()], cache.plan(&end_bp));
+		assert_eq!(vec![mid_bp.id()], cache.plan(&mid_bp));
+	}
+
+	/// Builder which accesses its cache directly, bypassing its resolver.
+	///
+	struct BuilderBypassing {
+		bypass: Box<dyn Fn()>,
+	}
+
+	impl fmt::Debug for BuilderBypassing {
+		fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			f.debug_struct("BuilderBypassing").finish()
+		}
+	}
+
+	impl Builder<Rc<dyn Any>, Rc<dyn Any>> for BuilderBypassing {
+		type Artifact = ();
+		type DynState = ();
+		type Err = Never;
+
+		fn build(&self, _resolver: &mut Resolver<Rc<dyn Any>, Rc<dyn Any>>) -> Result<Rc<()>, Never> {
+			(self.bypass)();
+
+			Ok(Rc::new(()))
+		}
+
+		fn init_dyn_state(&self) -> Self::DynState {
+			// empty
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "reentrant cache access detected for builder")]
+	#[cfg_attr(test, allow(unsafe_code))]
+	fn reentrant_build() {
+		let mut cache_owned = new_cache_rc();
+		let cache: &mut RawCache<Rc<dyn Any>, Rc<dyn Any>> = &mut cache_owned;
+		let cache: *mut _ = cache;
+
+		let leaf = Blueprint::new(BuilderLeaf::new());
+		let bp = Blueprint::new(BuilderBypassing {
+			bypass: Box::new(move || {
+				// Simulates a builder which got hold of its cache
+				unsafe { &mut *cache }.get(&leaf).unwrap();
+			}),
+		});
+
+		unsafe { &mut *cache }.get(&bp).unwrap();
+	}
+
+	#[cfg(feature = "lru")]
+	#[test]
+	fn memory_budget_eviction() {
+		let bp = Blueprint::new(BuilderSized { size: 10 });
+
+		let mut cache_owned = new_cache_rc();
+		let cache: &mut RawCache<Rc<dyn Any>, Rc<dyn Any>> = &mut cache_owned;
+
+		cache.keep_stale(bp.id());
+		cache.watch(&bp);
+		cache.get(&bp).unwrap();
+
+		cache.set_memory_budget(5);
+		assert!(!cache.contains_artifact(&bp));
+
+		// Evicted artifacts are neither retained nor rebuilt
+		assert!(cache.stale_can_by_id(bp.id()).is_none());
+		assert!(cache.rebuild_dirty().is_empty());
+	}
+
+	#[test]
+	fn build_after_panic() {
+		let bp = Blueprint::new(BuilderPanicking);
+
+		let mut cache_owned = new_cache_rc();
+		let cache: &mut RawCache<Rc<dyn Any>, Rc<dyn Any>> = &mut cache_owned;
+
+		let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+			cache.get(&bp).unwrap();
+		}));
+		assert!(res.is_err());
+		assert!(cache.extras.building.is_empty());
+		assert_eq!(None, cache.extras.resolving);
+
+		// Subsequent builds are not considered to be reentrant
+		let leaf = Blueprint::new(BuilderLeaf::new());
+		cache.get(&leaf).unwrap();
+
+		// The budget is still enforced
+		#[cfg(feature = "lru")]
+		{
+			let sized = Blueprint::new(BuilderSized { size: 10 });
+			cache.get(&sized).unwrap();
+
+			cache.set_memory_budget(5);
+			assert!(!cache.contains_artifact(&sized));
+		}
+	}
+
+	#[test]
+	fn builder_strong_count() {
+		let bp = Blueprint::new(BuilderLeaf::new());
+		let id = bp.id();
+
+		let mut cache_owned = new_cache_rc();
+		let cache: &mut RawCache<Rc<dyn Any>, Rc<dyn Any>> = &mut cache_owned;
+
+		assert_eq!(None, cache.builder_strong_count(id));
+
+		cache.get(&bp).unwrap();
+		assert_eq!(Some(1), cache.builder_strong_count(id));
+
+		let bp2 = bp.clone();
+		assert_eq!(Some(2), cache.builder_strong_count(id));
+
+		drop(bp);
+		drop(bp2);
+		assert_eq!(Some(0), cache.builder_strong_count(id));
+
+		cache.garbage_collection();
+		assert_eq!(None, cache.builder_strong_count(id));
+	}
+
+	#[test]
+	fn get_overriding() {
+		let base_bp = Blueprint::new(BuilderLeafFallible::new());
+		let other_bp = Blueprint::new(BuilderLeaf::new());
+
+		let mut cache_owned = new_cache_rc();
+		let cache: &mut RawCache<Rc<dyn Any>, Rc<dyn Any>> = &mut cache_owned;
+
+		let other_art = cache.get(&other_bp).unwrap();
+
+		// The builder itself would fail
+		*cache.dyn_state_mut(&base_bp) = false;
+
+		// Resolve the other builder instead
+		let art = cache.get_overriding(&base_bp, |resolver| {
+			resolver.resolve_cloned(&other_bp).map_err(|n| n.into_any())
+		}).unwrap();
+		assert_eq!(*other_art, *art);
+
+		// The overriding artifact is cached normally
+		assert!(Rc::ptr_eq(&art, &cache.get(&base_bp).unwrap()));
+
+		// Resolved dependencies are tracked
+		cache.invalidate(&other_bp);
+		assert!(!cache.contains_artifact(&base_bp));
+	}
+
+	#[test]
+	fn invalidate_to_depth() {
+		let base_bp = Blueprint::new(BuilderLeafFallible::new());
+
+		let builder = BuilderVariableNode::new::<Rc<dyn Any>, Rc<dyn Any>>(base_bp.clone());
+		let mid_bp = Blueprint::new(builder);
+
+		let builder = BuilderVariableNode::new::<Rc<dyn Any>, Rc<dyn Any>>(mid_bp.clone());
+		let end_bp = Blueprint::new(builder);
+
+		let mut cache_owned = new_cache_rc();
+		let cache: &mut RawCache<Rc<dyn Any>, Rc<dyn Any>> = &mut cache_owned;
+
+		cache.get(&end_bp).unwrap();
+		let end_art = cache.lookup(&end_bp).unwrap();
+
+		cache.invalidate_to_depth(&base_bp, 1);
+
+		assert!(!cache.contains_artifact(&base_bp));
+		assert!(!cache.contains_artifact(&mid_bp));
+		assert!(cache.contains_artifact(&end_bp));
+		assert!(Rc::ptr_eq(&end_art, &cache.lookup(&end_bp).unwrap()));
+
+		// The deeper dependent still depends on the invalidated one
+		cache.get(&mid_bp).unwrap();
+		cache.invalidate(&mid_bp);
+		assert!(!cache.contains_artifact(&end_bp));
+
+		cache.get(&end_bp).unwrap();
+
+		// Depth zero only invalidates the builder itself
+		cache.invalidate_to_depth(&mid_bp, 0);
+		assert!(cache.contains_artifact(&base_bp));
+		assert!(!cache.contains_artifact(&mid_bp));
+		assert!(cache.contains_artifact(&end_bp));
+
+		cache.garbage_collection();
+	}
+
 }
 
 
@@ -89,6 +89,27 @@ pub trait CanBase: Debug + Sized + 'static {
 	/// Returns the pointer to the inner value.
 	///
 	fn can_as_ptr(&self) -> *const dyn Any;
+
+	/// Returns the type name of this Can as of `std::any::type_name`.
+	///
+	/// This is used for diagnostics, e.g. to distinguish different Can types
+	/// in traces.
+	///
+	fn can_type_name(&self) -> &'static str {
+		std::any::type_name::<Self>()
+	}
+
+	/// Returns a check whether the inner value is still alive.
+	///
+	/// The returned function must not keep the inner value alive, but keep
+	/// its address reserved, i.e. it holds a weak reference. It is used by
+	/// the `deterministic_ids` feature to forget the ids of dropped Builders.
+	///
+	/// The default implementation returns `None`, i.e. liveness is unknown.
+	///
+	fn can_liveness(&self) -> Option<Box<dyn Fn() -> bool>> {
+		None
+	}
 }
 
 /// Represents an opaque wrapper for `dyn Any` which has a transparent
@@ -129,6 +150,26 @@ pub trait Can<T: ?Sized>: CanBase {
 	/// Returns the pointer to inner value.
 	///
 	fn bin_as_ptr(b: &Self::Bin) -> *const ();
+
+	/// Returns a reference to the inner value, if it is accessible.
+	///
+	/// The default implementation returns `None`.
+	///
+	fn bin_as_ref(_b: &Self::Bin) -> Option<&T> {
+		None
+	}
+
+	/// Returns a check whether the inner value is still alive.
+	///
+	/// This is the `Bin` counterpart of [`can_liveness`].
+	///
+	/// The default implementation returns `None`, i.e. liveness is unknown.
+	///
+	/// [`can_liveness`]: trait.CanBase.html#method.can_liveness
+	///
+	fn bin_liveness(_b: &Self::Bin) -> Option<Box<dyn Fn() -> bool>> {
+		None
+	}
 }
 
 cfg_if! {
@@ -222,6 +263,62 @@ pub trait CanStrong: CanBase {
 	/// Tries to upgrade a weak to a strong value, if there was any other
 	/// strong value left.
 	fn upgrade_from_weak(weak: &Self::CanWeak) -> Option<Self>;
+
+	/// Returns the number of strong values (including `self`) sharing the
+	/// same inner value.
+	fn strong_count(&self) -> usize;
+}
+
+/// Can whose `Bin` has a weak representation.
+///
+/// This allows to refer to a `Bin` without keeping it alive. With the
+/// `cyclic` feature, it also allows to create a `Bin` for `T` while giving
+/// `T` access to a weak representation of that very `Bin`, analogous to
+/// `Rc::new_cyclic`.
+///
+/// For instance `Rc<dyn Any>`, which implements `CanCyclic`, defines
+/// `std::rc::Weak<T>` as its `WeakBin`.
+///
+// Impl for Rc, Arc
+pub trait CanCyclic<T>: CanSized<T> {
+	/// The weak representation of the `Bin`.
+	type WeakBin: Debug + Clone + 'static;
+
+	/// Creates a `Bin` from the `T` returned by `f`, which gets a weak
+	/// representation of the `Bin` under construction.
+	///
+	/// **Notice: This function is only available if the `cyclic` feature has
+	/// been activated**.
+	///
+	#[cfg(feature = "cyclic")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "cyclic")))]
+	fn new_cyclic_bin<F: FnOnce(&Self::WeakBin) -> T>(f: F) -> Self::Bin;
+
+	/// Tries to upgrade a weak `Bin` to a strong one, if there was any other
+	/// strong value left.
+	fn upgrade_bin(weak: &Self::WeakBin) -> Option<Self::Bin>;
+
+	/// Returns the pointer to the inner value, as given by [`bin_as_ptr`].
+	///
+	/// **Notice: This function is only available if the `cyclic` feature has
+	/// been activated**.
+	///
+	/// [`bin_as_ptr`]: trait.Can.html#tymethod.bin_as_ptr
+	///
+	#[cfg(feature = "cyclic")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "cyclic")))]
+	fn weak_bin_as_ptr(weak: &Self::WeakBin) -> *const ();
+}
+
+/// Cyclic Can whose `Bin` may be downgraded to its weak representation.
+///
+/// For instance `Rc<dyn Any>`, which implements `CanDowngrade`, uses
+/// `Rc::downgrade`.
+///
+// Impl for Rc, Arc
+pub trait CanDowngrade<T>: CanCyclic<T> {
+	/// Creates a weak representation of the given `Bin`.
+	fn downgrade_bin(bin: &Self::Bin) -> Self::WeakBin;
 }
 
 /// Can with reference access.
@@ -395,6 +492,11 @@ impl CanBase for Rc<dyn Any> {
 	fn can_as_ptr(&self) -> *const dyn Any {
 		self.deref()
 	}
+
+	fn can_liveness(&self) -> Option<Box<dyn Fn() -> bool>> {
+		let weak = Rc::downgrade(self);
+		Some(Box::new(move || weak.upgrade().is_some()))
+	}
 }
 
 impl CanStrong for Rc<dyn Any> {
@@ -407,6 +509,10 @@ impl CanStrong for Rc<dyn Any> {
 	fn upgrade_from_weak(weak: &Self::CanWeak) -> Option<Self> {
 		weak.upgrade()
 	}
+
+	fn strong_count(&self) -> usize {
+		Rc::strong_count(self)
+	}
 }
 
 impl<T: ?Sized + Debug + 'static> Can<T> for Rc<dyn Any> {
@@ -415,6 +521,13 @@ impl<T: ?Sized + Debug + 'static> Can<T> for Rc<dyn Any> {
 	fn bin_as_ptr(b: &Self::Bin) -> *const () {
 		b.deref() as *const T as *const ()
 	}
+	fn bin_as_ref(b: &Self::Bin) -> Option<&T> {
+		Some(b)
+	}
+	fn bin_liveness(b: &Self::Bin) -> Option<Box<dyn Fn() -> bool>> {
+		let weak = Rc::downgrade(b);
+		Some(Box::new(move || weak.upgrade().is_some()))
+	}
 }
 
 cfg_if! {
@@ -455,6 +568,28 @@ impl<T: Debug + 'static> CanSized<T> for Rc<dyn Any> {
 	}
 }
 
+impl<T: Debug + 'static> CanCyclic<T> for Rc<dyn Any> {
+	type WeakBin = WeakRc<T>;
+
+	#[cfg(feature = "cyclic")]
+	fn new_cyclic_bin<F: FnOnce(&Self::WeakBin) -> T>(f: F) -> Self::Bin {
+		Rc::new_cyclic(f)
+	}
+	fn upgrade_bin(weak: &Self::WeakBin) -> Option<Self::Bin> {
+		weak.upgrade()
+	}
+	#[cfg(feature = "cyclic")]
+	fn weak_bin_as_ptr(weak: &Self::WeakBin) -> *const () {
+		weak.as_ptr() as *const ()
+	}
+}
+
+impl<T: Debug + 'static> CanDowngrade<T> for Rc<dyn Any> {
+	fn downgrade_bin(bin: &Self::Bin) -> Self::WeakBin {
+		Rc::downgrade(bin)
+	}
+}
+
 impl<ArtCan: 'static, Artifact, DynState, Err, B> CanBuilder<ArtCan, Artifact, DynState, Err, B> for Rc<dyn Any>
 	where
 		B: Builder<ArtCan, Self, Artifact=Artifact, DynState=DynState, Err=Err> + 'static,
@@ -498,6 +633,9 @@ impl<T: ?Sized + Debug + 'static> Can<T> for Box<dyn Any> {
 	fn bin_as_ptr(b: &Self::Bin) -> *const () {
 		b.deref() as *const T as *const ()
 	}
+	fn bin_as_ref(b: &Self::Bin) -> Option<&T> {
+		Some(b)
+	}
 }
 
 cfg_if! {
@@ -553,6 +691,11 @@ impl CanBase for Arc<dyn Any + Send + Sync> {
 	fn can_as_ptr(&self) -> *const dyn Any {
 		self.deref()
 	}
+
+	fn can_liveness(&self) -> Option<Box<dyn Fn() -> bool>> {
+		let weak = Arc::downgrade(self);
+		Some(Box::new(move || weak.upgrade().is_some()))
+	}
 }
 
 impl CanStrong for Arc<dyn Any + Send + Sync> {
@@ -565,6 +708,10 @@ impl CanStrong for Arc<dyn Any + Send + Sync> {
 	fn upgrade_from_weak(weak: &Self::CanWeak) -> Option<Self> {
 		weak.upgrade()
 	}
+
+	fn strong_count(&self) -> usize {
+		Arc::strong_count(self)
+	}
 }
 
 impl<T: ?Sized + Debug + Send + Sync + 'static> Can<T> for Arc<dyn Any + Send + Sync> {
@@ -573,6 +720,13 @@ impl<T: ?Sized + Debug + Send + Sync + 'static> Can<T> for Arc<dyn Any + Send +
 	fn bin_as_ptr(b: &Self::Bin) -> *const () {
 		b.deref() as *const T as *const ()
 	}
+	fn bin_as_ref(b: &Self::Bin) -> Option<&T> {
+		Some(b)
+	}
+	fn bin_liveness(b: &Self::Bin) -> Option<Box<dyn Fn() -> bool>> {
+		let weak = Arc::downgrade(b);
+		Some(Box::new(move || weak.upgrade().is_some()))
+	}
 }
 
 cfg_if! {
@@ -608,6 +762,28 @@ impl<T: Debug + Send + Sync + 'static> CanSized<T> for Arc<dyn Any + Send + Sync
 	}
 }
 
+impl<T: Debug + Send + Sync + 'static> CanCyclic<T> for Arc<dyn Any + Send + Sync> {
+	type WeakBin = WeakArc<T>;
+
+	#[cfg(feature = "cyclic")]
+	fn new_cyclic_bin<F: FnOnce(&Self::WeakBin) -> T>(f: F) -> Self::Bin {
+		Arc::new_cyclic(f)
+	}
+	fn upgrade_bin(weak: &Self::WeakBin) -> Option<Self::Bin> {
+		weak.upgrade()
+	}
+	#[cfg(feature = "cyclic")]
+	fn weak_bin_as_ptr(weak: &Self::WeakBin) -> *const () {
+		weak.as_ptr() as *const ()
+	}
+}
+
+impl<T: Debug + Send + Sync + 'static> CanDowngrade<T> for Arc<dyn Any + Send + Sync> {
+	fn downgrade_bin(bin: &Self::Bin) -> Self::WeakBin {
+		Arc::downgrade(bin)
+	}
+}
+
 /*
 impl<ArtCan: 'static, Artifact: 'static, DynState, Err, B> CanBuilderSync<ArtCan, Artifact, DynState, Err, B> for Arc<dyn Any + Send + Sync>
 	where
@@ -671,6 +847,9 @@ cfg_if! {
 			fn bin_as_ptr(b: &Self::Bin) -> *const () {
 				b.builder_ptr()
 			}
+			fn bin_as_ref(b: &Self::Bin) -> Option<&Bp<B, BCan>> {
+				Some(b)
+			}
 		}
 
 		impl<BCan: 'static, B: 'static + Debug> CanSized<Bp<B,BCan>> for BuilderArtifact<BCan>
@@ -700,6 +879,9 @@ cfg_if! {
 			fn bin_as_ptr(b: &Self::Bin) -> *const () {
 				b.deref().builder_ptr()
 			}
+			fn bin_as_ref(b: &Self::Bin) -> Option<&Bpu<B, BCan>> {
+				Some(b)
+			}
 		}
 
 		impl<BCan: 'static, B: 'static + Debug> CanSized<Bpu<B,BCan>> for BuilderArtifact<BCan>
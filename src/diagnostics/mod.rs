@@ -14,7 +14,8 @@
 //! See the respective method of the `Doctor` for details.
 //!
 //! Additionally, to the generic `Doctor` trait, there are several pre-implemented
-//! Doctors such as: [`VisgraphDoc`] or [`TextualDoc`].
+//! Doctors such as: [`VisgraphDoc`] or [`TextualDoc`]. Their output can be
+//! inspected while the cache is still alive by writing to a [`SharedSink`].
 //!
 //![`ArtifactCache`]: ../struct.ArtifactCache.html
 //![`Doctor`]: trait.Doctor.html
@@ -23,13 +24,19 @@
 //![`BuilderHandle`]: struct.BuilderHandle.html
 //![`VisgraphDoc`]: struct.VisgraphDoc.html
 //![`TextualDoc`]: struct.TextualDoc.html
+//![`SharedSink`]: struct.SharedSink.html
 //!
 
 
 use std::any::Any;
+use std::cell::Ref;
+use std::cell::RefCell;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::fmt::Debug;
+use std::io;
+use std::io::Write;
+use std::rc::Rc;
 
 use crate::canning::Can;
 use crate::canning::CanBase;
@@ -107,6 +114,17 @@ pub trait Doctor<ArtCan, BCan> {
 	fn invalidate(&mut self, _builder: &BuilderHandle<BCan>) {
 		// NOOP
 	}
+
+	/// The dynamic state of the given `Builder` is changed.
+	///
+	/// This method is called when the dynamic state is accessed mutably via
+	/// `Cache::dyn_state_mut` or set via `BatchCtx::set_dyn_state`, right
+	/// before the resulting invalidation of `builder`. Thus it allows to
+	/// correlate a reconfiguration with the following rebuilds.
+	///
+	fn dyn_state_changed(&mut self, _builder: &BuilderHandle<BCan>) {
+		// NOOP
+	}
 }
 
 
@@ -124,9 +142,15 @@ pub struct ArtifactHandle<ArtCan> {
 	/// The actual artifact value.
 	pub value: ArtCan,
 
+	/// The unique id of the builder which produced this artifact, if known.
+	pub builder_id: Option<BuilderId>,
+
 	/// The type name of the artifact as of `std::any::type_name`.
 	pub type_name: &'static str,
 
+	/// The type name of the Can of the artifact as of `CanBase::can_type_name`.
+	pub can_type_name: &'static str,
+
 	/// The value of the artifact as of `std::fmt::Debug`.
 	pub dbg_text: String,
 }
@@ -138,14 +162,29 @@ impl<ArtCan> ArtifactHandle<ArtCan> {
 		where ArtCan: CanSized<T> {
 
 		let dbg_text = format!("{:#?}", value);
+		let value = ArtCan::from_bin(value);
 
 		ArtifactHandle {
-			value: ArtCan::from_bin(value),
+			can_type_name: value.can_type_name(),
+			value,
+			builder_id: None,
 			type_name: std::any::type_name::<T>(),
 			dbg_text,
 		}
 	}
 
+	/// Constructs a new artifact handle with the given value, as produced by
+	/// the builder with the given id.
+	///
+	pub fn with_builder_id<T: Any + Debug>(builder_id: BuilderId, value: ArtCan::Bin) -> Self
+		where ArtCan: CanSized<T> {
+
+		ArtifactHandle {
+			builder_id: Some(builder_id),
+			.. Self::new(value)
+		}
+	}
+
 	/// Extract artifact from handle.
 	///
 	/// Since the artifact is canned it is only useful it the exact type of the
@@ -155,6 +194,16 @@ impl<ArtCan> ArtifactHandle<ArtCan> {
 	pub fn into_inner(self) -> ArtCan {
 		self.value
 	}
+
+	/// The unique id of the builder which produced this artifact, if known.
+	///
+	/// This is the same id as of the corresponding `BuilderHandle`, thus it
+	/// allows to correlate artifacts to builders, e.g. in a `Doctor`. The
+	/// handles passed by the `Cache` always carry the id.
+	///
+	pub fn builder_id(&self) -> Option<BuilderId> {
+		self.builder_id
+	}
 }
 
 
@@ -196,6 +245,9 @@ pub struct BuilderHandle<BCan> {
 	/// The type name of the builder as of `std::any::type_name`.
 	pub type_name: &'static str,
 
+	/// The type name of the Can of the builder as of `CanBase::can_type_name`.
+	pub can_type_name: &'static str,
+
 	/// The value of the builder as of `std::fmt::Debug`.
 	pub dbg_text: String,
 }
@@ -210,9 +262,11 @@ impl<BCan> BuilderHandle<BCan> {
 
 		let dbg_text = format!("{:#?}", &value.builder().builder);
 		let id = value.id();
+		let can = value.canned().can;
 
 		BuilderHandle {
-			value: value.canned().can,
+			can_type_name: can.can_type_name(),
+			value: can,
 			id,
 			type_name: std::any::type_name::<AP::Builder>(),
 			dbg_text,
@@ -272,5 +326,92 @@ impl Default for NoopDoctor {
 }
 
 
+/// A shared `Write` sink for Doctors.
+///
+/// **Notice: This struct is only available if the `diagnostics` feature has been activated**.
+///
+/// Usually, a Doctor such as the [`TextualDoc`] owns its output, thus it can
+/// only be inspected after dissolving the cache via `into_doctor()`. A
+/// `SharedSink` wraps a `Rc<RefCell<W>>` instead, so a clone of it may be
+/// kept to peek at the output written so far, e.g. in interactive debugging.
+///
+/// Notice, that writing panics if the inner `W` is borrowed at that time,
+/// thus a borrow obtained by [`borrow`] must not be held across cache
+/// accesses.
+///
+/// ## Example
+///
+/// ```
+/// use daab::rc::Cache;
+/// use daab::diagnostics::{SharedSink, TextualDoc, TextualDocOptions};
+///
+/// let sink = SharedSink::new(Vec::new());
+///
+/// let mut cache = Cache::new_with_doctor(
+///     TextualDoc::new(TextualDocOptions::default(), sink.clone())
+/// );
+///
+/// //...
+///
+/// // Inspect the output without dissolving the cache
+/// println!("{}", String::from_utf8_lossy(&sink.borrow()));
+/// ```
+///
+///[`TextualDoc`]: struct.TextualDoc.html
+///[`borrow`]: struct.SharedSink.html#method.borrow
+///
+#[derive(Debug, Default)]
+pub struct SharedSink<W> {
+	inner: Rc<RefCell<W>>,
+}
+
+impl<W> SharedSink<W> {
+	/// Creates a new sink writing to given `Write`.
+	///
+	pub fn new(output: W) -> Self {
+		Self::from(Rc::new(RefCell::new(output)))
+	}
+
+	/// Immutably borrows the inner `Write`, e.g. to inspect the output.
+	///
+	/// # Panics
+	///
+	/// Panics if the inner `Write` is currently being written to.
+	///
+	pub fn borrow(&self) -> Ref<'_, W> {
+		self.inner.borrow()
+	}
+
+	/// Returns the shared inner `Write`.
+	///
+	pub fn into_inner(self) -> Rc<RefCell<W>> {
+		self.inner
+	}
+}
+
+impl<W> Clone for SharedSink<W> {
+	fn clone(&self) -> Self {
+		SharedSink {
+			inner: self.inner.clone(),
+		}
+	}
+}
+
+impl<W> From<Rc<RefCell<W>>> for SharedSink<W> {
+	fn from(inner: Rc<RefCell<W>>) -> Self {
+		SharedSink {
+			inner,
+		}
+	}
+}
+
+impl<W: Write> Write for SharedSink<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.inner.borrow_mut().write(buf)
+	}
 
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.borrow_mut().flush()
+	}
+}
 
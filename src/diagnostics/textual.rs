@@ -4,8 +4,12 @@ use super::Doctor;
 use super::BuilderHandle;
 use super::ArtifactHandle;
 use super::CanBase;
+use super::SharedSink;
 
+use std::cell::RefCell;
+use std::fmt;
 use std::io::Write;
+use std::rc::Rc;
 use cfg_if::cfg_if;
 
 /// Output options for [`TextualDoc`].
@@ -23,6 +27,7 @@ use cfg_if::cfg_if;
 ///     show_artifact_values: false,
 ///     show_addresses: false,
 ///     tynm_m_n: Some((0,0)),
+///     filter: None,
 /// };
 /// assert_eq!(opts, TextualDocOptions::default());
 /// ```
@@ -41,7 +46,7 @@ use cfg_if::cfg_if;
 ///[`tynm`]: https://crates.io/crates/tynm
 ///[tynm docs]: https://docs.rs/tynm/
 ///
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone)]
 pub struct TextualDocOptions {
 	/// Configures whether builders should be only visualized by their
 	/// value (`true`) instead of by their type (`false`)
@@ -69,6 +74,45 @@ pub struct TextualDocOptions {
 	///[tynm docs]: https://docs.rs/tynm/
 	///
 	pub tynm_m_n: Option<(usize, usize)>,
+
+	/// Configures which events should be written based on the type name of
+	/// their builder.
+	///
+	/// If `Some`, only those events are written for which the given function
+	/// returns `true` when called with the builder's type name as of
+	/// `std::any::type_name()` (i.e. not abbreviated). `None` writes all
+	/// events.
+	///
+	/// Notice, that clear events have no builder and are always written.
+	///
+	pub filter: Option<fn(&str) -> bool>,
+}
+
+// Manual impl, because `fn(&str)` pointers are only `Debug` on recent Rust
+impl fmt::Debug for TextualDocOptions {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.debug_struct("TextualDocOptions")
+			.field("show_builder_values", &self.show_builder_values)
+			.field("show_artifact_values", &self.show_artifact_values)
+			.field("show_addresses", &self.show_addresses)
+			.field("tynm_m_n", &self.tynm_m_n)
+			.field("filter", &self.filter.map(|f| f as *const ()))
+			.finish()
+	}
+}
+
+// Manual impl, because comparing `fn` pointers directly is linted as unreliable
+impl PartialEq for TextualDocOptions {
+	fn eq(&self, other: &Self) -> bool {
+		self.show_builder_values == other.show_builder_values
+			&& self.show_artifact_values == other.show_artifact_values
+			&& self.show_addresses == other.show_addresses
+			&& self.tynm_m_n == other.tynm_m_n
+			&& self.filter.map(|f| f as usize) == other.filter.map(|f| f as usize)
+	}
+}
+
+impl Eq for TextualDocOptions {
 }
 
 impl Default for TextualDocOptions {
@@ -78,6 +122,7 @@ impl Default for TextualDocOptions {
 			show_artifact_values: false,
 			show_addresses: false,
 			tynm_m_n: Some((0,0)),
+			filter: None,
 		}
 	}
 }
@@ -104,6 +149,7 @@ impl Default for TextualDocOptions {
 ///             show_artifact_values: true,
 ///             show_addresses: false,
 ///             tynm_m_n: Some((0,0)),
+///             filter: None,
 ///         },
 ///         stdout()
 ///     )
@@ -136,6 +182,19 @@ pub struct TextualDoc<W: Write> {
 	count: (u64, u64),
 }
 
+impl<W: Write> TextualDoc<SharedSink<W>> {
+	/// Creates a new Textual Doctor writing to the given shared output.
+	///
+	/// This allows to inspect the output via another `Rc` to it while the
+	/// cache is still alive, see [`SharedSink`].
+	///
+	/// [`SharedSink`]: struct.SharedSink.html
+	///
+	pub fn new_shared(opts: TextualDocOptions, output: Rc<RefCell<W>>) -> Self {
+		Self::new(opts, SharedSink::from(output))
+	}
+}
+
 impl<W: Write> TextualDoc<W> {
 	/// Creates a new Textual Doctor
 	///
@@ -177,6 +236,14 @@ impl<W: Write> TextualDoc<W> {
 		}
 	}
 	
+	/// Tests whether events of the given builder pass the filter.
+	fn is_shown<BCan>(&self, builder: &BuilderHandle<BCan>) -> bool {
+		match self.opts.filter {
+			Some(f) => f(builder.type_name),
+			None => true,
+		}
+	}
+	
 	/// Auxiliary to get the output by `&mut`.
 	/// depricated
 	fn output(&mut self) -> &mut W {
@@ -193,6 +260,10 @@ impl<W: Write> TextualDoc<W> {
 impl<ArtCan: CanBase, BCan, W: Write> Doctor<ArtCan, BCan> for TextualDoc<W> {
 	fn resolve(&mut self, builder: &BuilderHandle<BCan>, used: &BuilderHandle<BCan>) {
 	
+		if !self.is_shown(builder) {
+			return;
+		}
+		
 		let bs = self.builder_str(builder);
 		let us = self.builder_str(used);
 		
@@ -217,6 +288,13 @@ impl<ArtCan: CanBase, BCan, W: Write> Doctor<ArtCan, BCan> for TextualDoc<W> {
 	fn build(&mut self, builder: &BuilderHandle<BCan>, artifact: &ArtifactHandle<ArtCan>) {
 		let count = self.count;
 		
+		// Count the artifact regardless of the filter to keep numbering stable
+		self.count.1 += 1;
+		
+		if !self.is_shown(builder) {
+			return;
+		}
+		
 		let bs = self.builder_str(builder);
 		if self.opts.show_addresses {
 			write!(self.output(),
@@ -250,9 +328,6 @@ impl<ArtCan: CanBase, BCan, W: Write> Doctor<ArtCan, BCan> for TextualDoc<W> {
 		}
 		
 		self.output().flush().unwrap();
-		
-		self.count.1 += 1;
-		
 	}
 	
 	fn clear(&mut self) {
@@ -271,6 +346,14 @@ impl<ArtCan: CanBase, BCan, W: Write> Doctor<ArtCan, BCan> for TextualDoc<W> {
 	fn invalidate(&mut self, builder: &BuilderHandle<BCan>) {
 		let count = self.count;
 		
+		// Generations inc
+		self.count.0 += 1;
+		self.count.1 = 0;
+		
+		if !self.is_shown(builder) {
+			return;
+		}
+		
 		write!(self.output(),
 			r"Invalidates generation #{} targeting ",
 			count.0,
@@ -289,10 +372,6 @@ impl<ArtCan: CanBase, BCan, W: Write> Doctor<ArtCan, BCan> for TextualDoc<W> {
 				bs,
 			).unwrap();
 		}
-		
-		// Generations inc
-		self.count.0 += 1;
-		self.count.1 = 0;
 	}
 }
 
@@ -4,8 +4,14 @@ use super::CanBase;
 use super::Doctor;
 use super::BuilderHandle;
 use super::ArtifactHandle;
+use super::BuilderId;
+use super::SharedSink;
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::cell::RefCell;
 use std::io::Write;
+use std::rc::Rc;
 
 
 /// Output options for `VisgrapDoc`.
@@ -21,6 +27,7 @@ use std::io::Write;
 /// let opts = VisgraphDocOptions {
 ///     show_builder_values: false,
 ///     show_artifact_values: true,
+///     root_filter: None,
 /// };
 /// assert_eq!(opts, VisgraphDocOptions::default());
 /// ```
@@ -35,6 +42,14 @@ pub struct VisgraphDocOptions {
 	/// Configures whether artifacts should be only visualized by their
 	/// value (`true`) instead of by their type (`false`)
 	pub show_artifact_values: bool,
+
+	/// Configures whether only the subgraph reachable from the given builder
+	/// should be visualized (`Some`) instead of the whole graph (`None`).
+	///
+	/// Since the reachable subgraph is only known at the end, the output is
+	/// kept in memory and only written when the `VisgraphDoc` is dropped or
+	/// dissolved via `into_inner`.
+	pub root_filter: Option<BuilderId>,
 }
 
 impl Default for VisgraphDocOptions {
@@ -42,6 +57,7 @@ impl Default for VisgraphDocOptions {
 		VisgraphDocOptions {
 			show_builder_values: false,
 			show_artifact_values: true,
+			root_filter: None,
 		}
 	}
 }
@@ -65,6 +81,7 @@ impl Default for VisgraphDocOptions {
 ///         VisgraphDocOptions {
 ///             show_builder_values: false,
 ///             show_artifact_values: true,
+///             root_filter: None,
 ///         },
 ///         File::create("test-graph.dot").unwrap()
 ///     )
@@ -103,6 +120,25 @@ pub struct VisgraphDoc<W: Write> {
 	/// The generation increases whenever a artifact might be recreated
 	/// i.e. after a call to `clear()` or `invalidate()`.
 	count: (u64, u64),
+
+	/// Withheld output per emitting builder, used with `root_filter`.
+	pending: Vec<(BuilderId, String)>,
+
+	/// Resolve edges between builders, used with `root_filter`.
+	edges: HashMap<BuilderId, HashSet<BuilderId>>,
+}
+
+impl<W: Write> VisgraphDoc<SharedSink<W>> {
+	/// Creates a new Visgraph Doctor writing to the given shared output.
+	///
+	/// This allows to inspect the output via another `Rc` to it while the
+	/// cache is still alive, see [`SharedSink`].
+	///
+	/// [`SharedSink`]: struct.SharedSink.html
+	///
+	pub fn new_shared(opts: VisgraphDocOptions, output: Rc<RefCell<W>>) -> Self {
+		Self::new(opts, SharedSink::from(output))
+	}
 }
 
 impl<W: Write> VisgraphDoc<W> {
@@ -117,6 +153,8 @@ impl<W: Write> VisgraphDoc<W> {
 			opts,
 			output: Some(output),
 			count: (0, 0),
+			pending: Vec::new(),
+			edges: HashMap::new(),
 		}
 	}
 	
@@ -132,8 +170,45 @@ impl<W: Write> VisgraphDoc<W> {
 	fn output(&mut self) -> &mut W {
 		self.output.as_mut().unwrap()
 	}
-	
+
+	/// Outputs given text emitted for given builder.
+	///
+	/// When filtering by root, the text is withheld until `finish`.
+	fn emit(&mut self, builder: BuilderId, text: String) {
+		if self.opts.root_filter.is_some() {
+			self.pending.push((builder, text));
+		} else {
+			self.output().write_all(text.as_bytes()).unwrap();
+			self.output().flush().unwrap();
+		}
+	}
+
+	/// Outputs the withheld text of all builders reachable from the root.
+	fn emit_reachable(&mut self, root: BuilderId) {
+		let mut reachable = HashSet::new();
+		let mut stack = vec![root];
+
+		while let Some(bid) = stack.pop() {
+			if reachable.insert(bid) {
+				if let Some(used) = self.edges.get(&bid) {
+					stack.extend(used);
+				}
+			}
+		}
+
+		let pending = std::mem::take(&mut self.pending);
+		for (bid, text) in pending {
+			if reachable.contains(&bid) {
+				self.output().write_all(text.as_bytes()).unwrap();
+			}
+		}
+	}
+
 	fn finish(&mut self) {
+		if let Some(root) = self.opts.root_filter {
+			self.emit_reachable(root);
+		}
+
 		writeln!(self.output(), "}}").unwrap();
 	}
 	
@@ -156,27 +231,29 @@ impl<W: Write> Drop for VisgraphDoc<W> {
 impl<ArtCan: CanBase, BCan, W: Write> Doctor<ArtCan, BCan> for VisgraphDoc<W> {
 	fn resolve(&mut self, builder: &BuilderHandle<BCan>, used: &BuilderHandle<BCan>) {
 
-		let s = self.builder_str(builder);
-		writeln!(self.output(),
-			r#"  "{:p}" [label = {:?}]"#,
+		let mut text = format!(
+			"  \"{:p}\" [label = {:?}]\n",
 			builder.id(),
-			s
-		).unwrap();
+			self.builder_str(builder)
+		);
 
-		let s = self.builder_str(used);
-		writeln!(self.output(),
-			r#"  "{:p}" [label = {:?}]"#,
+		text += &format!(
+			"  \"{:p}\" [label = {:?}]\n",
 			used.id(),
-			s
-		).unwrap();
+			self.builder_str(used)
+		);
 
-		writeln!(self.output(),
-			r#"  "{:p}" -> "{:p}""#,
+		text += &format!(
+			"  \"{:p}\" -> \"{:p}\"\n",
 			builder.id(),
 			used.id()
-		).unwrap();
+		);
 
-		self.output().flush().unwrap();
+		if self.opts.root_filter.is_some() {
+			self.edges.entry(builder.id()).or_default().insert(used.id());
+		}
+
+		self.emit(builder.id(), text);
 
 	}
 	
@@ -184,12 +261,11 @@ impl<ArtCan: CanBase, BCan, W: Write> Doctor<ArtCan, BCan> for VisgraphDoc<W> {
 	fn build(&mut self, builder: &BuilderHandle<BCan>, artifact: &ArtifactHandle<ArtCan>) {
 		let count = self.count;
 		
-		let s = self.builder_str(builder);
-		writeln!(self.output(),
-			r#"  "{:p}" [label = {:?}]"#,
+		let mut text = format!(
+			"  \"{:p}\" [label = {:?}]\n",
 			builder.id(),
-			s
-		).unwrap();
+			self.builder_str(builder)
+		);
 		
 		let s = if self.opts.show_artifact_values {
 			format!(" :\n{}", artifact.dbg_text)
@@ -197,24 +273,24 @@ impl<ArtCan: CanBase, BCan, W: Write> Doctor<ArtCan, BCan> for VisgraphDoc<W> {
 			"".into()
 		};
 		
-		writeln!(self.output(),
-			r##"  "{0}.{1}-{2:p}" [label = "#{0}.{1} {3}{4}", shape = box]"##,
+		text += &format!(
+			"  \"{0}.{1}-{2:p}\" [label = \"#{0}.{1} {3}{4}\", shape = box]\n",
 			count.0,
 			count.1,
 			artifact.value.can_as_ptr(),
 			artifact.type_name,
 			s
-		).unwrap();
+		);
 			
-		writeln!(self.output(),
-			r#"  "{:p}" -> "{}.{}-{:p}" [arrowhead = "none"]"#,
+		text += &format!(
+			"  \"{:p}\" -> \"{}.{}-{:p}\" [arrowhead = \"none\"]\n",
 			builder.id(),
 			count.0,
 			count.1,
 			artifact.value.can_as_ptr()
-		).unwrap();
+		);
 		
-		self.output().flush().unwrap();
+		self.emit(builder.id(), text);
 			
 		
 		self.count.1 += 1;
@@ -234,6 +234,9 @@
 //! `diagnostics` feature can be easily converted to using
 //! `diagnostics`, usually by just replacing `Cache::new()`
 //! with `Cache::new_with_doctor()`.
+//! Without `diagnostics`, `Cache::new_with_doctor()` is available too, but
+//! ignores the given doctor, thus the same construction compiles under both
+//! feature settings.
 //! In order to store the `Doctor` the `Cache` is generic to a doctor,
 //! which is important on its creation and for storing it by value.
 //! The rest of the time the `Cache` uses `dyn Doctor` as its default
@@ -261,11 +264,81 @@
 //!   `Doctor`s, hence it is only useful in connection with the `diagnostics`
 //!   feature.
 //!
+//! - **`checked`** adds the [`Cache::checked_get`] and
+//!   [`Cache::checked_lookup`] methods, which return a
+//!   [`CacheError::TypeMismatch`] instead of panicking if a cached Artifact
+//!   has an unexpected type.
+//!
+//! - **`validate`** adds the [`Cache::assert_consistent`] method, which
+//!   checks the internal invariants of a `Cache`, e.g. for property tests.
+//!
+//! - **`provenance`** records for each Artifact which Artifacts and dynamic
+//!   states have been read to build it, see [`Cache::artifact_provenance`].
+//!   This adds some overhead to every build.
+//!
+//! - **`rebuild_rate`** records the times of the recent builds of each
+//!   Builder, to detect Builders which are rebuilt too often, see
+//!   [`Cache::rebuild_rate`].
+//!
+//! - **`dag_lint`** records which Builders are fetched directly from the
+//!   `Cache`, to inspect the shape of the dependency graph, see
+//!   [`Cache::leaf_only_builders`] and [`Cache::root_only_builders`].
+//!
+//! - **`lru`** records the size hints and access times of the Artifacts,
+//!   see [`Builder::artifact_size_hint`], to evict the least recently
+//!   used ones, see [`Cache::evict_lru`] and [`Cache::set_memory_budget`].
+//!   This adds some overhead to every access of an Artifact.
+//!
+//! - **`artifact_version`** counts the Artifacts built for each Builder,
+//!   see [`Cache::artifact_version`].
+//!
+//! - **`log`** enables the optional dependency on the [`log`] crate and emits
+//!   log records with the target `"daab"` for builds, invalidations, and
+//!   the garbage collection, giving a zero-config insight into the `Cache`
+//!   without implementing a `Doctor`. Builds are logged at the `debug`
+//!   level along with the type name of the Builder, their start at the
+//!   `trace` level.
+//!
+//! - **`tracing`** enables the optional dependency on the [`tracing`] crate
+//!   and wraps each build in a `debug` span named `build` with the target
+//!   `"daab"`, having the type name of the Builder as `builder` field and its
+//!   [`BuilderId`] as `id` field. Since dependencies are built while their
+//!   dependent is building, the spans are nested just like the dependency
+//!   graph.
+//!
+//! - **`deterministic_ids`** replaces the address-based [`BuilderId`]s by
+//!   sequential numbers assigned in the order in which the ids are first
+//!   queried on each thread. This makes ids stable across runs, e.g. for
+//!   snapshot tests of `Doctor` output. **This feature is meant for tests
+//!   only**: the same Builder gets different ids on different threads, thus
+//!   it breaks `Cache`s shared across threads, and the registry entries of
+//!   boxed Builders are kept until [`BuilderId::reset_deterministic_ids`].
+//!
+//! - **`cyclic`** adds [`Blueprint::new_with_self`], which allows a Builder
+//!   to refer to its own `Blueprint`. **This feature requires Rust 1.60**.
+//!
 //! - **`unsized`** enables better conversion between unsized Builders with
 //!   [`BlueprintUnsized::into_unsized`]. **This feature requires Nightly
 //!   Rust**.
 //!
 //![`tynm`]: https://crates.io/crates/tynm
+//![`log`]: https://crates.io/crates/log
+//![`tracing`]: https://crates.io/crates/tracing
+//![`Cache::checked_get`]: cache/struct.Cache.html#method.checked_get
+//![`Cache::checked_lookup`]: cache/struct.Cache.html#method.checked_lookup
+//![`Cache::assert_consistent`]: cache/struct.Cache.html#method.assert_consistent
+//![`Cache::artifact_provenance`]: cache/struct.Cache.html#method.artifact_provenance
+//![`Cache::rebuild_rate`]: cache/struct.Cache.html#method.rebuild_rate
+//![`Cache::leaf_only_builders`]: cache/struct.Cache.html#method.leaf_only_builders
+//![`Cache::root_only_builders`]: cache/struct.Cache.html#method.root_only_builders
+//![`Builder::artifact_size_hint`]: trait.Builder.html#method.artifact_size_hint
+//![`Cache::evict_lru`]: cache/struct.Cache.html#method.evict_lru
+//![`Cache::set_memory_budget`]: cache/struct.Cache.html#method.set_memory_budget
+//![`Cache::artifact_version`]: cache/struct.Cache.html#method.artifact_version
+//![`CacheError::TypeMismatch`]: cache/enum.CacheError.html#variant.TypeMismatch
+//![`BuilderId`]: struct.BuilderId.html
+//![`BuilderId::reset_deterministic_ids`]: struct.BuilderId.html#method.reset_deterministic_ids
+//![`Blueprint::new_with_self`]: blueprint/struct.Blueprint.html#method.new_with_self
 //![`BlueprintUnsized::into_unsized`]: blueprint/struct.BlueprintUnsized.html#method.into_unsized
 //!
 
@@ -300,6 +373,7 @@ use blueprint::BlueprintDyn;
 use cache::Cache;
 use cache::CacheOwned;
 use cache::Resolver;
+use cache::DeclaredDependency;
 
 cfg_if! {
 	if #[cfg(feature = "unsized")] {
@@ -332,6 +406,7 @@ cfg_if! {
 pub mod prelude {
 	pub use crate::Unpacking as _;
 	pub use crate::blueprint::Promise as _;
+	pub use crate::utils::BuilderExt as _;
 }
 
 
@@ -459,6 +534,17 @@ impl<T> Unpacking for Result<T,Never> {
 /// Thus if a Builder can always produce an Artifact, its `Err` type should be
 /// that [`Never`] type.
 ///
+/// Errors are generally not cached, but a Builder may opt-in via
+/// [`cache_error`].
+///
+/// Notice that a build failure is different from an Artifact which happens
+/// to be a `Result`. An `Err` returned from `build` means that no Artifact
+/// could be produced, thus the Builder is retried on the next access. In
+/// contrast, if the failure is a legitimate outcome, such as a validation
+/// result, the Builder should use a `Result` as its `Artifact` (and [`Never`]
+/// as its `Err`), then both arms are cached like any other Artifact until
+/// the Builder is invalidated. See [`ResultBuilder`] for a helper.
+///
 ///
 ///
 /// [`Artifact`]: trait.Builder.html#associatedtype.Artifact
@@ -471,6 +557,8 @@ impl<T> Unpacking for Result<T,Never> {
 /// [`RedeemingBuilder`]: utils/struct.RedeemingBuilder.html
 /// [`Never`]: enum.Never.html
 /// [`Err`]: trait.Builder.html#associatedtype.Err
+/// [`cache_error`]: trait.Builder.html#method.cache_error
+/// [`ResultBuilder`]: utils/struct.ResultBuilder.html
 /// [unpacking]: trait.Unpacking.html
 ///
 pub trait Builder<ArtCan, BCan>: Debug + 'static
@@ -506,6 +594,127 @@ pub trait Builder<ArtCan, BCan>: Debug + 'static
 	/// to obtain an initial value for the dynamic state of this builder.
 	///
 	fn init_dyn_state(&self) -> Self::DynState;
+
+	/// Returns a copy of the given error, if it shall be cached.
+	///
+	/// By default, failed builds are not cached, thus a failing Builder is
+	/// rebuilt on every access. For expensive Builders which fail
+	/// deterministically, this can be avoided by returning a copy of `err`
+	/// here (typically `Some(err.clone())`). Then the `Cache` keeps that copy
+	/// and returns further copies of it (again obtained from this method)
+	/// instead of rebuilding, until the Builder is invalidated.
+	///
+	/// The default implementation returns `None`, i.e. errors are not cached.
+	///
+	fn cache_error(&self, _err: &Self::Err) -> Option<Self::Err> {
+		None
+	}
+
+	/// Returns a hook to be called with the Artifact when it is evicted.
+	///
+	/// If a hook is returned, the `Cache` keeps it along with the Artifact
+	/// and calls it right before the Artifact is removed from the `Cache`,
+	/// e.g. due to invalidation or purging. This allows cleanup beyond `Drop`,
+	/// such as flushing. The hook gets the Artifact in its opaque Can, for
+	/// Artifacts implementing [`OnEvict`] the [`on_evict_hook`] function can
+	/// be used as hook.
+	///
+	/// The default implementation returns `None`, i.e. there is no hook.
+	///
+	/// [`OnEvict`]: trait.OnEvict.html
+	/// [`on_evict_hook`]: utils/fn.on_evict_hook.html
+	///
+	fn evict_hook(&self) -> Option<fn(&ArtCan)> {
+		None
+	}
+
+	/// Declares the dependencies which this Builder refers to weakly, by
+	/// calling `declare` with the id of each.
+	///
+	/// A Builder may hold some of its dependencies only via a
+	/// [`WeakPromise`], so that they are not kept alive by it. However,
+	/// resolving such a dependency in `build` makes the `Cache` track it,
+	/// which would prevent [`Cache::garbage_collection`] from reclaiming it.
+	/// The dependencies declared here are treated as weak instead: they are
+	/// reclaimed once no strong reference remains elsewhere, which also
+	/// invalidates the Artifact of this Builder.
+	///
+	/// This method is called after each build of this Builder. The default
+	/// implementation declares no dependencies.
+	///
+	/// [`WeakPromise`]: blueprint/struct.WeakPromise.html
+	/// [`Cache::garbage_collection`]: cache/struct.Cache.html#method.garbage_collection
+	///
+	fn weak_dependencies(&self, _declare: &mut dyn FnMut(BuilderId)) {
+		// none
+	}
+
+	/// Returns the approximate memory size of the given Artifact in bytes.
+	///
+	/// The `Cache` sums up these hints of all its Artifacts, see
+	/// [`Cache::approx_memory`], and evicts the least recently accessed
+	/// Artifacts if that sum exceeds its memory budget, see
+	/// [`Cache::set_memory_budget`]. The hint is queried once, when the
+	/// Artifact is built. Artifacts whose `ArtCan` does not provide access to
+	/// them, see [`Can::bin_as_ref`], are not queried and count as `0`.
+	///
+	/// The default implementation returns `0`, i.e. the Artifact does not
+	/// count towards the memory budget.
+	///
+	/// **Notice: This method is only available if the `lru` feature
+	/// has been activated**.
+	///
+	/// [`Cache::approx_memory`]: cache/struct.Cache.html#method.approx_memory
+	/// [`Cache::set_memory_budget`]: cache/struct.Cache.html#method.set_memory_budget
+	/// [`Can::bin_as_ref`]: canning/trait.Can.html#method.bin_as_ref
+	///
+	#[cfg(feature = "lru")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "lru")))]
+	fn artifact_size_hint(&self, _artifact: &Self::Artifact) -> usize {
+		0
+	}
+
+	/// Returns the dependencies of this Builder, declared up front.
+	///
+	/// This allows the `Cache` to know the dependencies of a Builder without
+	/// executing `build`. They are used by [`Cache::plan`] to predict which
+	/// Builders would be built, and by [`Cache::get_iterative`] to build them
+	/// bottom-up using an explicit work stack, instead of recursively from
+	/// within `build`.
+	///
+	/// Declared dependencies are only advisory. The authoritative
+	/// dependencies, as used e.g. for invalidation, are still tracked from the
+	/// actual `resolve` calls within `build`. Builders which can not cheaply
+	/// declare their dependencies, e.g. because they depend on their dynamic
+	/// state, should just return an empty list.
+	///
+	/// The default implementation declares no dependencies.
+	///
+	/// [`Cache::plan`]: cache/struct.Cache.html#method.plan
+	/// [`Cache::get_iterative`]: cache/struct.Cache.html#method.get_iterative
+	///
+	fn declare_dependencies(&self) -> Vec<Box<dyn DeclaredDependency<ArtCan, BCan>>> {
+		Vec::new()
+	}
+}
+
+/// Explicit cleanup for Artifacts when they are evicted from a `Cache`.
+///
+/// Implementing this trait alone has no effect, the respective Builder has
+/// to opt-in via [`Builder::evict_hook`] e.g. by returning
+/// [`on_evict_hook`].
+///
+/// Since Artifacts might be shared (e.g. when using the `rc` module), only
+/// shared access is given, and the Artifact might still be alive after its
+/// eviction.
+///
+/// [`Builder::evict_hook`]: trait.Builder.html#method.evict_hook
+/// [`on_evict_hook`]: utils/fn.on_evict_hook.html
+///
+pub trait OnEvict {
+	/// Performs the cleanup for this Artifact.
+	///
+	fn on_evict(&self);
 }
 
 
@@ -516,17 +725,158 @@ pub trait Builder<ArtCan, BCan>: Debug + 'static
 /// Consequentially, a `BuilderId`s validity is limited to the life time of
 /// the respective `Builder`.
 ///
+/// With the `deterministic_ids` feature, the ids are instead numbered in the
+/// order in which they are first queried on each thread, see
+/// [`reset_deterministic_ids`].
+///
+/// [`reset_deterministic_ids`]: struct.BuilderId.html#method.reset_deterministic_ids
+///
 #[derive(Clone, Debug, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct BuilderId(usize);
 
+/// Checks whether a Builder is still alive, see `CanBase::can_liveness`.
+#[cfg(feature = "deterministic_ids")]
+type Liveness = Box<dyn Fn() -> bool>;
+
+/// Registry of the deterministic ids of one thread.
+///
+#[cfg(feature = "deterministic_ids")]
+#[derive(Default)]
+struct DeterministicIds {
+	/// The ids and liveness checks, keyed by the builder addresses.
+	ids: std::collections::HashMap<usize, (usize, Option<Liveness>)>,
+	/// The next id to assign.
+	next: usize,
+	/// The registry size at which the entries of dropped Builders are purged.
+	purge_at: usize,
+}
+
+#[cfg(feature = "deterministic_ids")]
+impl DeterministicIds {
+	/// Minimal registry size at which dropped Builders are purged.
+	const MIN_PURGE: usize = 64;
+
+	fn id(&mut self, addr: usize, liveness: Option<Liveness>) -> usize {
+		if let Some((id, live)) = self.ids.get_mut(&addr) {
+			// An entry with a liveness check reserves its address, thus a hit
+			// is the same Builder. Entries without one are upgraded here.
+			if live.is_none() {
+				*live = liveness;
+			}
+
+			return *id;
+		}
+
+		if self.ids.len() >= self.purge_at {
+			// Forget the dropped Builders, amortized by doubling the threshold
+			self.ids.retain(|_, (_, live)| match live {
+				Some(live) => live(),
+				None => true,
+			});
+			self.purge_at = (self.ids.len() * 2).max(Self::MIN_PURGE);
+		}
+
+		let id = self.next;
+		self.next += 1;
+		self.ids.insert(addr, (id, liveness));
+
+		id
+	}
+}
+
+#[cfg(feature = "deterministic_ids")]
+thread_local! {
+	/// The deterministic ids of this thread.
+	static DETERMINISTIC_IDS: std::cell::RefCell<DeterministicIds> =
+		std::cell::RefCell::new(DeterministicIds::default());
+}
+
 impl BuilderId {
-	fn new(ptr: *const dyn Any) -> Self {
-		BuilderId(ptr as *const () as usize)
+	/// Creates the id of the Builder at `ptr`.
+	///
+	/// `liveness` is only called with the `deterministic_ids` feature, see
+	/// [`CanBase::can_liveness`]. That feature is meant for tests only: the
+	/// registry is thread local, and the entries of Builders without a
+	/// liveness check (e.g. boxed ones) are only dropped by
+	/// [`reset_deterministic_ids`], thus such an entry also survives its
+	/// Builder and is handed to any later Builder at the same address.
+	///
+	/// [`CanBase::can_liveness`]: canning/trait.CanBase.html#method.can_liveness
+	/// [`reset_deterministic_ids`]: struct.BuilderId.html#method.reset_deterministic_ids
+	///
+	#[cfg_attr(not(feature = "deterministic_ids"), allow(unused_variables))]
+	fn new<F>(ptr: *const dyn Any, liveness: F) -> Self
+			where F: FnOnce() -> Option<Box<dyn Fn() -> bool>> {
+
+		let addr = ptr as *const () as usize;
+
+		cfg_if! {
+			if #[cfg(feature = "deterministic_ids")] {
+				// Number the builders in the order of their first id query
+				let liveness = liveness();
+				DETERMINISTIC_IDS.with(|ids| {
+					BuilderId(ids.borrow_mut().id(addr, liveness))
+				})
+			} else {
+				BuilderId(addr)
+			}
+		}
+	}
+
+	/// Restarts the numbering of the deterministic ids of the current thread.
+	///
+	/// With the `deterministic_ids` feature, each Builder gets the next
+	/// number as its id when its id is queried the first time on the
+	/// current thread. Calling this function at the start of a test ensures
+	/// that the ids do not depend on the tests run before on the same
+	/// thread.
+	///
+	/// **Beware**, this must only be called while no Builder whose id has
+	/// been queried is alive, otherwise ids might be assigned twice.
+	///
+	/// **Notice: This function is only available if the `deterministic_ids`
+	/// feature has been activated**.
+	///
+	#[cfg(feature = "deterministic_ids")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "deterministic_ids")))]
+	pub fn reset_deterministic_ids() {
+		DETERMINISTIC_IDS.with(|ids| *ids.borrow_mut() = DeterministicIds::default());
 	}
 
 	fn as_ptr(&self) -> *const () {
 		self.0 as *const ()
 	}
+
+	/// Returns the raw value of this id.
+	///
+	/// This allows to store the id outside of Rust types, e.g. across an FFI
+	/// boundary or in a serialized log. Use [`from_usize`] to reconstruct
+	/// the id.
+	///
+	/// [`from_usize`]: struct.BuilderId.html#method.from_usize
+	///
+	pub fn as_usize(&self) -> usize {
+		self.0
+	}
+
+	/// Reconstructs an id from its raw value as returned by [`as_usize`].
+	///
+	/// **Beware**, a `BuilderId` is only meaningful as long as its Builder is
+	/// alive. Once the Builder has been dropped, its id might be reused by
+	/// any other Builder. Thus a reconstructed id must only be used if the
+	/// original Builder is known to be still alive, otherwise it might refer
+	/// to an unrelated Builder or to no Builder at all. Also, a raw value is
+	/// only valid within the process which created it.
+	///
+	/// This function never causes undefined behavior, since ids are never
+	/// dereferenced, but using a stale id with a `Cache` yields arbitrary
+	/// results.
+	///
+	/// [`as_usize`]: struct.BuilderId.html#method.as_usize
+	///
+	pub fn from_usize(n: usize) -> BuilderId {
+		BuilderId(n)
+	}
 }
 
 impl fmt::Pointer for BuilderId {
@@ -11,6 +11,9 @@ use cfg_if::cfg_if;
 use crate::Doctor;
 
 use crate::BlueprintDyn;
+use crate::Promise;
+use crate::BuilderId;
+use crate::cache::DeclaredDependency;
 use crate::Never;
 
 
@@ -41,6 +44,10 @@ pub type BuilderCan = CanType;
 ///
 pub type Blueprint<B> = crate::Blueprint<B, CanType>;
 
+/// A weak reference to a `Blueprint`, see `Blueprint::new_with_self`.
+///
+pub type WeakPromise<B> = crate::blueprint::WeakPromise<B, CanType>;
+
 cfg_if! {
 	if #[cfg(feature = "unsized")] {
 		/// The unsized variant of `Blueprint`.
@@ -54,6 +61,20 @@ cfg_if! {
 pub type DynamicBlueprint<Artifact, Err=Never, DynState=()> =
 	BlueprintDyn<CanType, BuilderCan, Artifact, Err, DynState>;
 
+/// Wraps the given Builder as `DynamicBlueprint`.
+///
+/// This is a shorthand of `DynamicBlueprint::new` for the common case of an
+/// infallible Builder without dynamic state, i.e. with `Err = Never` and
+/// `DynState = ()`. It allows to mix different Builders of the same
+/// Artifact type, e.g. in a `Vec`, without spelling out the full type.
+///
+pub fn dyn_blueprint<B>(builder: B) -> DynamicBlueprint<B::Artifact>
+		where
+			B: crate::Builder<CanType, BuilderCan, Err = Never, DynState = ()> {
+
+	DynamicBlueprint::new(builder)
+}
+
 pub type ConstBuilder<T> = crate::utils::ConstBuilder<CanType, BuilderCan, BinType<T>, T>;
 pub type ConfigurableBuilder<T> = crate::utils::ConfigurableBuilder<CanType, BuilderCan, T>;
 
@@ -100,6 +121,87 @@ cfg_if::cfg_if!{
 ///
 pub type CacheOwned = crate::CacheOwned<CanType, CanType>;
 
+impl Cache {
+	/// Gets the Artifact in an `Rc`.
+	///
+	/// This is just an alias for [`get`], which returns the Artifact in its
+	/// Bin, i.e. an `Rc` for this module. As opposed to [`get_ref`], the
+	/// returned `Rc` is not bound to the borrow of this `Cache`, thus it is
+	/// the accessor of choice if the Artifact shall be kept around or if
+	/// lifetime errors occur using [`get_ref`].
+	///
+	/// The `Rc` keeps the Artifact alive even if it is invalidated in this
+	/// `Cache` afterwards, in which case it refers to the old, detached
+	/// Artifact.
+	///
+	/// [`get`]: ../cache/struct.Cache.html#method.get
+	/// [`get_ref`]: ../cache/struct.Cache.html#method.get_ref
+	///
+	pub fn get_rc<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<BinType<B::Artifact>, B::Err>
+			where
+				B: crate::Builder<CanType, CanType>,
+				AP: Promise<Builder = B, BCan = CanType>  {
+
+		self.get(promise)
+	}
+
+	/// Gets the Artifact by mutable reference, cloning it if it is shared.
+	///
+	/// Since `Rc` does not permit mutation of a shared value, there is no
+	/// `get_mut` for this module. Instead, this method follows the semantics
+	/// of [`Rc::make_mut`]: if the `Rc` stored in this `Cache` is the only
+	/// one, the Artifact is mutated in place. Otherwise, e.g. because an `Rc`
+	/// returned by [`get`] is still around, the Artifact is cloned and the
+	/// clone replaces the stored one. Consequently, previously returned `Rc`s
+	/// keep pointing at the unmodified Artifact.
+	///
+	/// This method will try to build the Artifact if it is not stored in the
+	/// `Cache`, thus a `Result` is returned. An `Err` will be returned only,
+	/// if the Artifact was not cached and the Builder returned an `Err`.
+	///
+	/// Just like [`get_mut`] of the `boxed` module, this invalidates all
+	/// Artifacts depending on the given one.
+	///
+	/// [`Rc::make_mut`]: https://doc.rust-lang.org/std/rc/struct.Rc.html#method.make_mut
+	/// [`get`]: ../cache/struct.Cache.html#method.get
+	/// [`get_mut`]: ../cache/struct.Cache.html#method.get_mut
+	///
+	pub fn make_mut<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<&mut B::Artifact, B::Err>
+			where
+				B: crate::Builder<CanType, CanType>,
+				B::Artifact: Clone,
+				AP: Promise<Builder = B, BCan = CanType>  {
+
+		self.inner.make_mut(promise)
+	}
+}
+
+impl<'a, T: 'static> Resolver<'a, T> {
+	/// Resolves the Artifact in an `Rc`.
+	///
+	/// This is just an alias for [`resolve`], see [`Cache::get_rc`].
+	///
+	/// [`resolve`]: ../cache/struct.Resolver.html#method.resolve
+	/// [`Cache::get_rc`]: ../cache/struct.Cache.html#method.get_rc
+	///
+	pub fn resolve_rc<AP, B: ?Sized>(
+			&mut self,
+			promise: &AP
+		) -> Result<BinType<B::Artifact>, B::Err>
+			where
+				B: crate::Builder<CanType, CanType>,
+				AP: Promise<Builder = B, BCan = CanType>  {
+
+		self.resolve(promise)
+	}
+}
+
 
 
 /*
@@ -186,6 +288,51 @@ pub trait Builder: Debug + 'static {
 	/// Return an inital dynamic state for this builder.
 	///
 	fn init_dyn_state(&self) -> Self::DynState;
+
+	/// Returns a copy of the given error, if it shall be cached.
+	///
+	/// See the `cache_error` method of the general `Builder` trait.
+	///
+	fn cache_error(&self, _err: &Self::Err) -> Option<Self::Err> {
+		None
+	}
+
+	/// Returns a hook to be called with the Artifact when it is evicted.
+	///
+	/// See the `evict_hook` method of the general `Builder` trait.
+	///
+	fn evict_hook(&self) -> Option<fn(&CanType)> {
+		None
+	}
+
+	/// Declares the dependencies which this Builder refers to weakly.
+	///
+	/// See the `weak_dependencies` method of the general `Builder` trait.
+	///
+	fn weak_dependencies(&self, _declare: &mut dyn FnMut(BuilderId)) {
+		// none
+	}
+
+	/// Returns the approximate memory size of the given Artifact in bytes.
+	///
+	/// See the `artifact_size_hint` method of the general `Builder` trait.
+	///
+	/// **Notice: This method is only available if the `lru` feature
+	/// has been activated**.
+	///
+	#[cfg(feature = "lru")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "lru")))]
+	fn artifact_size_hint(&self, _artifact: &Self::Artifact) -> usize {
+		0
+	}
+
+	/// Returns the dependencies of this Builder, declared up front.
+	///
+	/// See the `declare_dependencies` method of the general `Builder` trait.
+	///
+	fn declare_dependencies(&self) -> Vec<Box<dyn DeclaredDependency<CanType, CanType>>> {
+		Vec::new()
+	}
 }
 
 impl<B: ?Sized + Builder> crate::Builder<CanType, CanType> for B {
@@ -202,6 +349,27 @@ impl<B: ?Sized + Builder> crate::Builder<CanType, CanType> for B {
 	fn init_dyn_state(&self) -> Self::DynState {
 		self.init_dyn_state()
 	}
+
+	fn cache_error(&self, err: &Self::Err) -> Option<Self::Err> {
+		self.cache_error(err)
+	}
+
+	fn evict_hook(&self) -> Option<fn(&CanType)> {
+		self.evict_hook()
+	}
+
+	fn weak_dependencies(&self, declare: &mut dyn FnMut(BuilderId)) {
+		self.weak_dependencies(declare)
+	}
+
+	#[cfg(feature = "lru")]
+	fn artifact_size_hint(&self, artifact: &Self::Artifact) -> usize {
+		self.artifact_size_hint(artifact)
+	}
+
+	fn declare_dependencies(&self) -> Vec<Box<dyn DeclaredDependency<CanType, CanType>>> {
+		self.declare_dependencies()
+	}
 }
 
 /*
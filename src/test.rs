@@ -8,6 +8,7 @@ use pretty_assertions::{assert_eq, assert_ne};
 use std::marker::PhantomData;
 
 use crate::*;
+use crate::blueprint::BorrowPromise;
 
 // Dummy counter to differentiate instances
 static COUNTER: AtomicU32 = AtomicU32::new(0);
@@ -395,6 +396,166 @@ fn test_boxed_mut() {
 	assert_ne!(as_ptr_mut(cache.get_mut(&leaf1)), as_ptr_mut(cache.get_mut(&leaf2)));
 }
 
+#[cfg(feature = "mut_box")]
+#[derive(Debug)]
+pub(crate) struct BuilderMutatingBox {
+	leaf: crate::boxed::Blueprint<BuilderLeafBox>,
+}
+
+#[cfg(feature = "mut_box")]
+impl crate::boxed::Builder for BuilderMutatingBox {
+	type Artifact = u32;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, cache: &mut crate::boxed::Resolver) -> Result<Box<Self::Artifact>, Never> {
+		let leaf = cache.resolve_mut(&self.leaf)?;
+		leaf.id = 42;
+
+		Ok(Box::new(leaf.id))
+	}
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+#[test]
+#[cfg(feature = "mut_box")]
+fn test_resolve_mut() {
+	let mut cache = crate::boxed::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeafBox::new());
+	let node = Blueprint::new(BuilderMutatingBox {
+		leaf: leaf.clone(),
+	});
+
+	assert_eq!(cache.get_ref(&node).unpack(), &42);
+	assert_eq!(cache.get_ref(&leaf).unpack().id, 42);
+
+	// The dependency is tracked
+	cache.invalidate(&leaf);
+	assert!(!cache.contains_artifact_id(node.id()));
+}
+
+#[cfg(feature = "mut_box")]
+#[derive(Debug)]
+pub(crate) struct BuilderResolvingMutBox {
+	dep: crate::boxed::Blueprint<BuilderLeafBox>,
+	leaf: crate::boxed::Blueprint<BuilderLeafBox>,
+}
+
+#[cfg(feature = "mut_box")]
+impl crate::boxed::Builder for BuilderResolvingMutBox {
+	type Artifact = u32;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, cache: &mut crate::boxed::Resolver) -> Result<Box<Self::Artifact>, Never> {
+		cache.resolve_ref(&self.dep)?;
+		let before = cache.resolve_ref(&self.leaf)?.id;
+
+		let leaf = cache.resolve_mut(&self.leaf)?;
+		leaf.id = before + 1;
+
+		Ok(Box::new(leaf.id))
+	}
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+#[test]
+#[cfg(feature = "mut_box")]
+fn test_resolve_mut_resolved() {
+	let mut cache = crate::boxed::Cache::new();
+
+	let dep = Blueprint::new(BuilderLeafBox::new());
+	let leaf = Blueprint::new(BuilderLeafBox::new());
+	let node = Blueprint::new(BuilderResolvingMutBox {
+		dep: dep.clone(),
+		leaf: leaf.clone(),
+	});
+	let other = Blueprint::new(BuilderMutatingBox {
+		leaf: leaf.clone(),
+	});
+
+	cache.get_ref(&other).unpack();
+	let id = *cache.get_ref(&node).unpack();
+	assert_eq!(cache.get_ref(&leaf).unpack().id, id);
+
+	// Only the other dependent has been invalidated
+	assert!(cache.contains_artifact_id(node.id()));
+	assert!(!cache.contains_artifact_id(other.id()));
+	cache.assert_consistent();
+
+	// The dependencies resolved before `resolve_mut` are still tracked
+	cache.invalidate(&dep);
+	assert!(!cache.contains_artifact_id(node.id()));
+	cache.assert_consistent();
+
+	cache.get_ref(&node).unpack();
+	cache.invalidate(&leaf);
+	assert!(!cache.contains_artifact_id(node.id()));
+	cache.assert_consistent();
+}
+
+#[cfg(feature = "mut_box")]
+#[derive(Debug)]
+pub(crate) struct BuilderNestedMutBox {
+	dep: crate::boxed::Blueprint<BuilderLeafBox>,
+	leaf: crate::boxed::Blueprint<BuilderLeafBox>,
+	inner: crate::boxed::Blueprint<BuilderMutatingBox>,
+}
+
+#[cfg(feature = "mut_box")]
+impl crate::boxed::Builder for BuilderNestedMutBox {
+	type Artifact = u32;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, cache: &mut crate::boxed::Resolver) -> Result<Box<Self::Artifact>, Never> {
+		cache.resolve_ref(&self.dep)?;
+		cache.resolve_ref(&self.leaf)?;
+
+		// Mutates the leaf, while this build is in progress
+		let inner = *cache.resolve_ref(&self.inner)?;
+
+		Ok(Box::new(inner))
+	}
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+#[test]
+#[cfg(feature = "mut_box")]
+fn test_resolve_mut_nested() {
+	let mut cache = crate::boxed::Cache::new();
+
+	let dep = Blueprint::new(BuilderLeafBox::new());
+	let leaf = Blueprint::new(BuilderLeafBox::new());
+	let node = Blueprint::new(BuilderNestedMutBox {
+		dep: dep.clone(),
+		leaf: leaf.clone(),
+		inner: Blueprint::new(BuilderMutatingBox {
+			leaf: leaf.clone(),
+		}),
+	});
+
+	assert_eq!(cache.get_ref(&node).unpack(), &42);
+	cache.assert_consistent();
+
+	// The enclosing build keeps the dependencies resolved before
+	cache.invalidate(&dep);
+	assert!(!cache.contains_artifact_id(node.id()));
+	cache.assert_consistent();
+
+	cache.get_ref(&node).unpack();
+	cache.invalidate(&leaf);
+	assert!(!cache.contains_artifact_id(node.id()));
+	cache.assert_consistent();
+}
+
 // Tests whether it is valid to get a Cache by &mut
 fn ref_function<Art, B, P>(cache: &mut crate::Cache<Art, B>, l: Art::Bin, ap: &P)
 	where
@@ -716,3 +877,2775 @@ fn test_complex_invalidate() {
 
 }
 
+
+#[test]
+fn test_id_accessors() {
+	let mut cache = rc::Cache::new();
+
+	let leaf1 = Blueprint::new(BuilderLeaf::new());
+	let id = leaf1.id();
+
+	assert!(!cache.is_builder_known_id(id));
+	assert!(!cache.contains_artifact_id(id));
+
+	cache.get(&leaf1).unpack();
+
+	assert!(cache.is_builder_known_id(id));
+	assert!(cache.contains_artifact_id(id));
+
+	cache.invalidate(&leaf1);
+
+	assert!(cache.is_builder_known_id(id));
+	assert!(!cache.contains_artifact_id(id));
+
+	cache.purge(&leaf1);
+
+	assert!(!cache.is_builder_known_id(id));
+	assert!(!cache.contains_artifact_id(id));
+}
+
+// Generic over the previous Builder, because a Builder recursively storing a
+// `Blueprint` of its own type overflows older compilers
+#[derive(Debug)]
+struct BuilderSlowChainNode<B: Debug + Send + Sync + 'static> {
+	prev: arc::Blueprint<B>,
+	delay: std::time::Duration,
+}
+
+impl<B> Builder<arc::CanType, arc::BuilderCan> for BuilderSlowChainNode<B>
+	where
+		B: Builder<arc::CanType, arc::BuilderCan, Artifact = u32, Err = Never>,
+		B: Debug + Send + Sync + 'static, {
+
+	type Artifact = u32;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, resolver: &mut arc::Resolver) -> Result<arc::BinType<Self::Artifact>, Never> {
+		std::thread::sleep(self.delay);
+
+		let len = *resolver.resolve(&self.prev).unpack() + 1;
+
+		Ok(arc::BinType::new(len))
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+
+	fn declare_dependencies(&self) -> Vec<Box<dyn cache::DeclaredDependency<arc::CanType, arc::CanType>>> {
+		vec![Box::new(self.prev.clone())]
+	}
+}
+
+#[test]
+fn test_get_partial() {
+	use std::time::Duration;
+
+	let mut cache = arc::Cache::new();
+
+	// Only the second node is slow, which gives generous margins to the
+	// deadline below
+	let fast = Duration::from_secs(0);
+	let slow = Duration::from_millis(500);
+	let first = Blueprint::new(arc::ConstBuilder::new(arc::BinType::new(1)));
+	let second = Blueprint::new(BuilderSlowChainNode { prev: first.clone(), delay: slow });
+	let third = Blueprint::new(BuilderSlowChainNode { prev: second.clone(), delay: fast });
+	let fourth = Blueprint::new(BuilderSlowChainNode { prev: third.clone(), delay: fast });
+	let head = &Blueprint::new(BuilderSlowChainNode { prev: fourth.clone(), delay: fast });
+	let chain = [first.id(), second.id(), third.id(), fourth.id()];
+
+	// Nothing is built after the deadline
+	let partial = cache.get_partial(head, Duration::from_secs(0));
+	assert!(!partial.is_complete());
+	assert!(partial.built.is_empty());
+	assert_eq!(partial.pending.len(), 4);
+
+	// The fast first dependency is built, as is the slow second one, which
+	// starts before but ends after the deadline
+	let partial = cache.get_partial(head, Duration::from_millis(250));
+	assert!(!partial.is_complete());
+	assert_eq!(partial.built, vec![chain[0], chain[1]]);
+	assert_eq!(partial.pending, vec![chain[2], chain[3]]);
+	for id in &partial.built {
+		assert!(cache.contains_artifact_id(*id));
+	}
+
+	// Continues where the last call stopped
+	let partial = cache.get_partial(head, Duration::from_secs(10));
+	assert_eq!(partial.artifact.map(|res| *res.unpack()), Some(5));
+	assert!(partial.pending.is_empty());
+	assert_eq!(partial.built, vec![chain[2], chain[3]]);
+}
+
+#[cfg(feature = "cyclic")]
+#[derive(Debug)]
+struct BuilderSelfRef {
+	me: rc::WeakPromise<BuilderSelfRef>,
+}
+
+#[cfg(feature = "cyclic")]
+impl rc::SimpleBuilder for BuilderSelfRef {
+	type Artifact = rc::WeakPromise<BuilderSelfRef>;
+
+	fn build(&self, _resolver: &mut rc::Resolver) -> Self::Artifact {
+		self.me.clone()
+	}
+}
+
+#[cfg(feature = "cyclic")]
+#[test]
+fn test_new_with_self() {
+	let mut cache = rc::Cache::new();
+
+	let mut early_id = None;
+	let bp = rc::Blueprint::new_with_self(|me| {
+		// Not yet upgradable, but already identifiable
+		assert!(me.upgrade().is_none());
+		early_id = Some(me.id());
+
+		BuilderSelfRef {
+			me,
+		}
+	});
+
+	assert_eq!(Some(bp.id()), early_id);
+	assert_eq!(Some(bp.clone()), cache.get(&bp).unpack().upgrade());
+
+	let weak = (*cache.get(&bp).unpack()).clone();
+	drop(bp);
+	cache.garbage_collection();
+	assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn test_garbage_collection_dyn_state() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeafFallible::new());
+	let leaf_id = leaf.id();
+	let node: rc::Blueprint<_> = Blueprint::new(BuilderVariableNode::new::<rc::CanType, rc::BuilderCan>(leaf));
+	let node_id = node.id();
+
+	cache.get(&node).unwrap();
+	assert_eq!(2, cache.number_of_known_builders());
+
+	// The dyn state of the node keeps the last reference to the leaf
+	drop(node);
+
+	// A single GC run cleans up both
+	cache.garbage_collection();
+	assert!(!cache.is_builder_known_id(node_id));
+	assert!(!cache.is_builder_known_id(leaf_id));
+	assert_eq!(0, cache.number_of_known_builders());
+
+	cache.assert_consistent();
+}
+
+#[test]
+fn test_purge_unreachable() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeafFallible::new());
+	let leaf_id = leaf.id();
+	let node: rc::Blueprint<_> = Blueprint::new(BuilderVariableNode::new::<rc::CanType, rc::BuilderCan>(leaf));
+	let node_id = node.id();
+	let other = Blueprint::new(BuilderLeaf::new());
+
+	cache.get(&node).unwrap();
+	cache.get(&other).unpack();
+	assert!(cache.purge_unreachable().is_empty());
+
+	// The leaf only becomes unreachable after the node has been removed
+	drop(node);
+	assert_eq!(cache.purge_unreachable(), vec![node_id, leaf_id]);
+	assert!(cache.purge_unreachable().is_empty());
+	assert!(cache.is_builder_known(&other));
+
+	cache.assert_consistent();
+}
+
+#[derive(Debug)]
+struct BuilderWeakNode {
+	leaf: rc::WeakPromise<BuilderLeaf>,
+}
+
+impl rc::Builder for BuilderWeakNode {
+	type Artifact = Option<rc::BinType<Leaf>>;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, resolver: &mut rc::Resolver) -> Result<rc::BinType<Self::Artifact>, Never> {
+		let leaf = self.leaf.upgrade()
+			.map(|leaf| resolver.resolve(&leaf).unpack());
+
+		Ok(rc::BinType::new(leaf))
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+
+	fn weak_dependencies(&self, declare: &mut dyn FnMut(BuilderId)) {
+		declare(self.leaf.id());
+	}
+}
+
+#[test]
+fn test_weak_dependencies() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = rc::Blueprint::new(BuilderLeaf::new());
+	let leaf_id = leaf.id();
+	let node = rc::Blueprint::new(BuilderWeakNode {
+		leaf: leaf.downgrade(),
+	});
+
+	assert!(cache.get(&node).unpack().is_some());
+	assert_eq!(2, cache.number_of_known_builders());
+
+	// The leaf is still alive
+	cache.garbage_collection();
+	assert!(cache.is_builder_known_id(leaf_id));
+	assert!(cache.contains_artifact_id(node.id()));
+
+	// The node only refers weakly to the leaf, so it does not keep it
+	drop(leaf);
+	cache.garbage_collection();
+	assert!(!cache.is_builder_known_id(leaf_id));
+	assert!(!cache.contains_artifact_id(node.id()));
+	assert_eq!(1, cache.number_of_known_builders());
+
+	assert!(cache.get(&node).unpack().is_none());
+
+	cache.assert_consistent();
+}
+
+#[test]
+fn test_weak_dependencies_invalidate_to_depth() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = rc::Blueprint::new(BuilderLeaf::new());
+	let node = rc::Blueprint::new(BuilderWeakNode {
+		leaf: leaf.downgrade(),
+	});
+
+	cache.get(&node).unpack();
+
+	// The weak dependencies go along with the artifact
+	cache.invalidate_to_depth(&leaf, 1);
+	assert!(!cache.contains_artifact_id(node.id()));
+	cache.assert_consistent();
+
+	cache.get(&node).unpack();
+	cache.invalidate_to_depth(&node, 0);
+	assert!(!cache.contains_artifact_id(node.id()));
+	cache.assert_consistent();
+}
+
+#[test]
+fn test_weak_dependencies_frozen() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = rc::Blueprint::new(BuilderLeaf::new());
+	let leaf_id = leaf.id();
+	let node = rc::Blueprint::new(BuilderWeakNode {
+		leaf: leaf.downgrade(),
+	});
+
+	cache.get(&node).unpack();
+	cache.freeze(&node);
+
+	// The frozen artifact keeps its weak dependency alive
+	drop(leaf);
+	cache.garbage_collection();
+	assert!(cache.is_builder_known_id(leaf_id));
+	assert!(cache.contains_artifact_id(node.id()));
+	cache.assert_consistent();
+
+	// Until it is unfrozen
+	cache.unfreeze(&node);
+	cache.garbage_collection();
+	assert!(!cache.is_builder_known_id(leaf_id));
+	assert!(!cache.contains_artifact_id(node.id()));
+	cache.assert_consistent();
+
+	// An unreachable frozen builder is collected along with its dependencies
+	let leaf = rc::Blueprint::new(BuilderLeaf::new());
+	let leaf_id = leaf.id();
+	let node = rc::Blueprint::new(BuilderWeakNode {
+		leaf: leaf.downgrade(),
+	});
+	let node_id = node.id();
+
+	cache.get(&node).unpack();
+	cache.freeze(&node);
+
+	drop(leaf);
+	drop(node);
+	cache.garbage_collection();
+	assert!(!cache.is_builder_known_id(leaf_id));
+	assert!(!cache.is_builder_known_id(node_id));
+	cache.assert_consistent();
+}
+
+#[cfg(feature = "unsized")]
+#[test]
+fn test_blueprint_unsized_eq() {
+	use std::any::Any;
+	use std::rc::Rc;
+	use blueprint::BlueprintUnsized;
+
+	type DynLeafBuilder = dyn Builder<Rc<dyn Any>, Rc<dyn Any>, Artifact=Leaf, DynState=(), Err=Never>;
+
+	let mut cache = rc::Cache::new();
+
+	let leaf1 = rc::Blueprint::new(BuilderLeaf::new());
+	let leaf2 = rc::Blueprint::new(BuilderLeaf::new());
+
+	let leaf1_unsized: rc::BlueprintUnsized<BuilderLeaf> = leaf1.clone().into();
+	let leaf1_dyn: rc::BlueprintUnsized<DynLeafBuilder> = BlueprintUnsized::from_sized_bp(leaf1.clone());
+
+	assert_eq!(leaf1.id(), leaf1_unsized.id());
+	assert_eq!(leaf1.id(), leaf1_dyn.id());
+
+	assert!(leaf1 == leaf1_unsized);
+	assert!(leaf1_unsized == leaf1);
+	assert!(leaf1 == leaf1_dyn);
+	assert!(leaf1_dyn == leaf1);
+	assert!(leaf2 != leaf1_unsized);
+	assert!(leaf1_dyn != leaf2);
+
+	// Lookups hit across the different handles
+	let art = cache.get(&leaf1).unpack();
+	assert_eq!(Some(art.clone()), cache.lookup(&leaf1_unsized));
+	assert_eq!(Some(art), cache.lookup(&leaf1_dyn));
+}
+
+#[test]
+fn test_builder_ext() {
+	use utils::BuilderExt;
+
+	let mut cache = rc::Cache::new();
+
+	let leaf = rc::Blueprint::new(BuilderLeaf::new());
+	let leaf_id = cache.get_cloned(&leaf).unpack().id;
+
+	let mapped = leaf.clone().mapped(|l: &Leaf| l.id + 1);
+	assert_eq!(leaf_id + 1, cache.get_cloned(&mapped).unpack());
+
+	let cloned = leaf.clone().cloned();
+	assert_eq!(cache.get_cloned(&leaf).unpack(), cache.get_cloned(&cloned).unpack());
+
+	let forwarded = leaf.clone().forwarded().logged(1);
+	assert_eq!(cache.get(&leaf).unpack(), cache.get(&forwarded).unpack());
+	assert_eq!(1, cache.get_dyn_state(&forwarded).unwrap().len());
+
+	let feigned = leaf.clone().feigning::<()>();
+	assert_eq!(Ok(cache.get(&leaf).unpack()), cache.get(&feigned));
+
+	let redeemed = leaf.redeeming(None);
+	assert_eq!(Ok(leaf_id), cache.get_cloned(&redeemed).map(|l| l.id));
+}
+
+#[test]
+fn test_resolver_debug() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let other = Blueprint::new(BuilderLeaf::new());
+
+	let mut debug = String::new();
+	cache.get_overriding(&leaf, |resolver| {
+		debug = format!("{:?}", resolver);
+		resolver.resolve_cloned(&other)
+	}).unpack();
+
+	assert_eq!(
+		format!("Resolver {{id: {:p}, diagnostics: {}}}", leaf.id(), cfg!(feature = "diagnostics")),
+		debug
+	);
+}
+
+#[derive(Debug, Default)]
+struct BuilderCachingFailure {
+	builds: AtomicU32,
+}
+
+impl rc::Builder for BuilderCachingFailure {
+	type Artifact = ();
+	type DynState = ();
+	type Err = u32;
+
+	fn build(&self, _resolver: &mut rc::Resolver) -> Result<std::rc::Rc<()>, u32> {
+		Err(self.builds.fetch_add(1, Ordering::SeqCst))
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+
+	fn cache_error(&self, err: &u32) -> Option<u32> {
+		Some(*err)
+	}
+}
+
+#[test]
+fn test_cache_error() {
+	let mut cache = rc::Cache::new();
+
+	let failing = rc::Blueprint::new(BuilderCachingFailure::default());
+	let forwarding = rc::Blueprint::new(utils::ForwardingBuilder::new(failing.clone()));
+
+	// The error is only built once
+	assert_eq!(Err(0), cache.get(&failing));
+	assert_eq!(Err(0), cache.get(&failing));
+	assert_eq!(Err(0), cache.get(&forwarding));
+
+	// Invalidation clears the cached error
+	cache.invalidate(&failing);
+	assert_eq!(Err(1), cache.get(&forwarding));
+	assert_eq!(Err(1), cache.get(&failing));
+
+	assert!(cache.has_cached_error(&failing));
+	assert!(!cache.has_cached_error(&forwarding));
+
+	cache.clear_errors();
+	assert!(!cache.has_cached_error(&failing));
+	assert_eq!(Err(2), cache.get(&failing));
+}
+
+#[test]
+fn test_lookup_ref_meta() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+
+	assert!(cache.lookup_ref_meta(&leaf).is_none());
+
+	let art = cache.get_cloned(&leaf).unpack();
+	let art_ref = cache.lookup_ref_meta(&leaf).unwrap();
+
+	assert_eq!(leaf.id(), art_ref.id());
+	assert_eq!(std::any::type_name::<Leaf>(), art_ref.type_name());
+	assert_eq!(art.id, art_ref.id);
+	assert_eq!(&art, art_ref.into_inner());
+}
+
+#[test]
+fn test_resolve_memo() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderLeaf::new());
+
+	cache.get_overriding(&node, |resolver| {
+		let first = resolver.resolve(&leaf).unpack();
+		let second = resolver.resolve(&leaf).unpack();
+
+		assert!(std::rc::Rc::ptr_eq(&first, &second));
+
+		Ok((*first).clone())
+	}).unpack();
+
+	// The repeated resolution counts as hit
+	assert_eq!(cache.stats().hits, 1);
+
+	// The dependency is tracked nonetheless
+	cache.invalidate(&leaf);
+	assert!(!cache.contains_artifact_id(node.id()));
+}
+
+#[test]
+fn test_stats() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	assert_eq!(cache.stats(), crate::cache::CacheStats::default());
+
+	cache.get(&node).unpack();
+	cache.get(&leaf).unpack();
+
+	let stats = cache.stats();
+	assert_eq!(stats.hits, 1);
+	assert_eq!(stats.misses, 2);
+	assert_eq!(stats.builds, 2);
+	assert_eq!(stats.invalidations, 0);
+
+	cache.invalidate(&leaf);
+	cache.get(&node).unpack();
+
+	let stats = cache.stats();
+	assert_eq!(stats.hits, 1);
+	assert_eq!(stats.misses, 4);
+	assert_eq!(stats.builds, 4);
+	assert_eq!(stats.invalidations, 1);
+
+	cache.reset_stats();
+	assert_eq!(cache.stats(), crate::cache::CacheStats::default());
+}
+
+#[test]
+fn test_invalidate_report() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	cache.get(&node).unpack();
+
+	let report = cache.invalidate_report(&leaf);
+	assert_eq!(report.invalidated_artifacts.len(), 2);
+	assert!(report.invalidated_artifacts.contains(&leaf.id()));
+	assert!(report.invalidated_artifacts.contains(&node.id()));
+	assert_eq!(report.preserved_dyn_states.len(), 2);
+	assert!(report.preserved_dyn_states.contains(&leaf.id()));
+	assert!(report.preserved_dyn_states.contains(&node.id()));
+
+	// Nothing left to invalidate
+	let report = cache.invalidate_report(&leaf);
+	assert!(report.invalidated_artifacts.is_empty());
+}
+
+fn get_owned_leaf<AP, P>(cache: &mut rc::Cache, promise: AP) -> rc::BinType<Leaf>
+		where
+			AP: BorrowPromise<P>,
+			P: Promise<Builder = BuilderLeaf, BCan = rc::CanType> {
+
+	cache.get(promise.borrow_promise()).unpack()
+}
+
+#[test]
+fn test_borrow_promise() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+
+	let art = get_owned_leaf(&mut cache, &leaf);
+	assert!(rc::BinType::ptr_eq(&art, &cache.get(&leaf).unpack()));
+
+	// Passing the Promise by value refers to the same Builder
+	let art_owned = get_owned_leaf(&mut cache, leaf.clone());
+	assert!(rc::BinType::ptr_eq(&art, &art_owned));
+}
+
+#[test]
+fn test_new_lazy() {
+	let constructed = std::rc::Rc::new(std::cell::Cell::new(0));
+
+	let mut cache = rc::Cache::new();
+
+	let counter = constructed.clone();
+	let lazy: rc::Blueprint<_> = Blueprint::new_lazy(move || {
+		counter.set(counter.get() + 1);
+		BuilderLeaf::new()
+	});
+
+	assert_eq!(constructed.get(), 0);
+
+	cache.get(&lazy).unpack();
+	assert_eq!(constructed.get(), 1);
+
+	cache.invalidate(&lazy);
+	cache.get(&lazy).unpack();
+	assert_eq!(constructed.get(), 1);
+}
+
+#[test]
+fn test_on_evict() {
+	use std::cell::RefCell;
+
+	let evicted = std::rc::Rc::new(RefCell::new(Vec::new()));
+	let count = std::rc::Rc::new(std::cell::Cell::new(0));
+
+	let mut cache = rc::Cache::new();
+
+	let evicted_cb = evicted.clone();
+	cache.on_evict(Box::new(move |bid| evicted_cb.borrow_mut().push(bid)));
+	let count_cb = count.clone();
+	cache.on_evict(Box::new(move |_| count_cb.set(count_cb.get() + 1)));
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	cache.get(&node).unpack();
+	assert!(evicted.borrow().is_empty());
+
+	cache.invalidate(&leaf);
+	assert_eq!(evicted.borrow().len(), 2);
+	assert!(evicted.borrow().contains(&leaf.id()));
+	assert!(evicted.borrow().contains(&node.id()));
+
+	// Nothing to evict anymore
+	cache.purge(&leaf);
+	assert_eq!(evicted.borrow().len(), 2);
+
+	cache.get(&leaf).unpack();
+	cache.clear_artifacts();
+	assert_eq!(evicted.borrow().len(), 3);
+	assert_eq!(evicted.borrow()[2], leaf.id());
+	assert_eq!(count.get(), 3);
+}
+
+#[test]
+fn test_blueprint_get() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node1 = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	let node2 = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	// No need to name the Builder type
+	assert_eq!(node2.get(&mut cache).unpack().leaf, node1.get(&mut cache).unpack().leaf);
+	assert_eq!(leaf.get_cloned(&mut cache).unpack(), cache.get_cloned(&leaf).unpack());
+	assert!(rc::BinType::ptr_eq(&node1.get(&mut cache).unpack(), &cache.get(&node1).unpack()));
+}
+
+#[test]
+fn test_batch() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node1 = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	let node2 = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	let fallible = Blueprint::new(BuilderLeafFallible::new());
+
+	cache.get(&node1).unpack();
+	cache.get(&node2).unpack();
+	cache.get(&fallible).unwrap();
+
+	cache.batch(|ctx| {
+		ctx.invalidate(&leaf);
+		ctx.invalidate(&node1);
+		ctx.invalidate(&leaf);
+		ctx.set_dyn_state(&fallible, false);
+	});
+
+	// Invalidations are deduplicated
+	assert_eq!(cache.stats().invalidations, 3);
+
+	assert!(!cache.contains_artifact_id(leaf.id()));
+	assert!(!cache.contains_artifact_id(node1.id()));
+	assert!(!cache.contains_artifact_id(node2.id()));
+	assert!(!cache.contains_artifact_id(fallible.id()));
+
+	assert!(cache.get(&fallible).is_err());
+	cache.get(&node2).unpack();
+
+	cache.assert_consistent();
+}
+
+#[derive(Debug)]
+struct BuilderRcNode {
+	leaf: rc::Blueprint<BuilderLeaf>,
+}
+
+impl rc::SimpleBuilder for BuilderRcNode {
+	type Artifact = rc::BinType<Leaf>;
+
+	fn build(&self, resolver: &mut rc::Resolver) -> Self::Artifact {
+		resolver.resolve_rc(&self.leaf).unpack()
+	}
+}
+
+#[test]
+fn test_get_rc() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderRcNode {
+		leaf: leaf.clone(),
+	});
+
+	let leaf_rc = cache.get_rc(&leaf).unpack();
+	assert!(rc::BinType::ptr_eq(&leaf_rc, &cache.get(&leaf).unpack()));
+
+	let node_rc = cache.get_rc(&node).unpack();
+	assert!(rc::BinType::ptr_eq(&*node_rc, &leaf_rc));
+}
+
+#[test]
+fn test_get_or_register() {
+	let mut cache = rc::Cache::new();
+
+	let (leaf, art) = cache.get_or_register(BuilderLeaf::new);
+	let art = art.unpack();
+
+	assert!(cache.contains_artifact_id(leaf.id()));
+	assert!(rc::BinType::ptr_eq(&art, &cache.get(&leaf).unpack()));
+
+	let (fallible, res) = cache.get_or_register(BuilderLeafFallible::new);
+	assert!(res.is_ok());
+	assert!(cache.contains_artifact_id(fallible.id()));
+}
+
+#[test]
+fn test_multi_error() {
+	use crate::cache::MultiError;
+
+	let leaf1: rc::Blueprint<_> = Blueprint::new(BuilderLeaf::new());
+	let leaf2: rc::Blueprint<_> = Blueprint::new(BuilderLeaf::new());
+
+	let errors: MultiError<&str> = MultiError::new();
+	assert_eq!(errors.into_result(), Ok(()));
+
+	let mut errors = MultiError::new();
+	errors.push(leaf2.id(), "second");
+	errors.push(leaf1.id(), "first");
+
+	assert_eq!(errors.len(), 2);
+	assert_eq!(errors.to_string(), "2 Builder(s) failed; second; first");
+	assert_eq!(
+		errors.into_result().unwrap_err().into_errors(),
+		vec![(leaf2.id(), "second"), (leaf1.id(), "first")]
+	);
+}
+
+#[test]
+fn test_clear_reporting() {
+	use crate::cache::ClearReport;
+
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	cache.get(&node).unpack();
+
+	assert_eq!(cache.clear_artifacts_reporting(), ClearReport {
+		artifacts: 2,
+		dyn_states: 0,
+		edges: 1,
+	});
+
+	cache.get(&leaf).unpack();
+
+	assert_eq!(cache.clear_all_reporting(), ClearReport {
+		artifacts: 1,
+		dyn_states: 2,
+		edges: 0,
+	});
+
+	assert_eq!(cache.clear_all_reporting(), ClearReport::default());
+}
+
+#[test]
+fn test_build_sampler() {
+	use std::cell::RefCell;
+
+	let samples = std::rc::Rc::new(RefCell::new(Vec::new()));
+
+	let mut cache = rc::Cache::new();
+
+	let samples_cb = samples.clone();
+	cache.set_build_sampler(2, move |bid, _duration| {
+		samples_cb.borrow_mut().push(bid);
+	});
+
+	let leafs: Vec<_> = (0..5).map(|_| Blueprint::new(BuilderLeaf::new())).collect();
+
+	for leaf in &leafs {
+		cache.get(leaf).unpack();
+	}
+
+	// Lookups are not sampled
+	for leaf in &leafs {
+		cache.get(leaf).unpack();
+	}
+
+	assert_eq!(*samples.borrow(), vec![leafs[0].id(), leafs[2].id(), leafs[4].id()]);
+
+	cache.clear_build_sampler();
+	cache.clear_artifacts();
+	cache.get(&leafs[0]).unpack();
+	assert_eq!(samples.borrow().len(), 3);
+}
+
+#[test]
+fn test_get_rc_detached() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+
+	let before = cache.get_rc(&leaf).unpack();
+	let before_id = before.id;
+
+	cache.invalidate(&leaf);
+
+	// The old Rc is still usable, but detached from the cache
+	assert_eq!(before.id, before_id);
+	assert_eq!(rc::BinType::strong_count(&before), 1);
+
+	let after = cache.get_rc(&leaf).unpack();
+	assert!(!rc::BinType::ptr_eq(&before, &after));
+	assert_ne!(before.id, after.id);
+	assert!(rc::BinType::ptr_eq(&after, &cache.get(&leaf).unpack()));
+}
+
+#[test]
+fn test_chain_builder() {
+	use crate::utils::ChainBuilder;
+
+	let mut cache = rc::Cache::new();
+
+	let value = rc::Blueprint::new(rc::ConfigurableBuilder::new(2_u32));
+	let chain = rc::Blueprint::new(
+		ChainBuilder::new(value.clone())
+			.then(|v| *v += 1)
+			.then(|v| *v *= 10)
+	);
+
+	assert_eq!(cache.get_cloned(&chain).unpack(), 30);
+
+	// Disable the first step
+	cache.dyn_state_mut(&chain)[0] = false;
+	assert_eq!(cache.get_cloned(&chain).unpack(), 20);
+
+	// Changes of the inner artifact are propagated
+	*cache.dyn_state_mut(&value) = 4;
+	assert_eq!(cache.get_cloned(&chain).unpack(), 40);
+}
+
+#[test]
+fn test_lazy_collect_builder() {
+	use crate::utils::LazyCollectBuilder;
+
+	let mut cache = rc::Cache::new();
+
+	let range = rc::Blueprint::new(rc::ConfigurableBuilder::new(0_u32..4));
+	let collect = rc::Blueprint::new(LazyCollectBuilder::new(range.clone()));
+
+	let items = cache.get(&collect).unpack();
+	assert_eq!(*items, vec![0, 1, 2, 3]);
+
+	// The materialized result is cached
+	assert!(rc::BinType::ptr_eq(&items, &cache.get(&collect).unpack()));
+
+	// Changes of the inner iterator are propagated
+	*cache.dyn_state_mut(&range) = 2..4;
+	assert_eq!(*cache.get(&collect).unpack(), vec![2, 3]);
+}
+
+#[test]
+#[cfg(feature = "diagnostics")]
+fn test_find_duplicate_builders() {
+	let mut cache = rc::Cache::new();
+
+	let one = rc::Blueprint::new(rc::ConfigurableBuilder::new(1_u32));
+	let other_one = rc::Blueprint::new(rc::ConfigurableBuilder::new(1_u32));
+	let two = rc::Blueprint::new(rc::ConfigurableBuilder::new(2_u32));
+
+	cache.get(&one).unwrap();
+	cache.get(&two).unwrap();
+	assert!(cache.find_duplicate_builders().is_empty());
+
+	cache.get(&other_one).unwrap();
+	let mut expected = vec![one.id(), other_one.id()];
+	expected.sort();
+	assert_eq!(cache.find_duplicate_builders(), vec![expected]);
+
+	cache.purge(&other_one);
+	assert!(cache.find_duplicate_builders().is_empty());
+	cache.assert_consistent();
+}
+
+#[derive(Debug)]
+struct BuilderInfallibleNode {
+	leaf: rc::Blueprint<BuilderLeaf>,
+}
+
+impl rc::SimpleBuilder for BuilderInfallibleNode {
+	type Artifact = rc::BinType<Leaf>;
+
+	fn build(&self, resolver: &mut rc::Resolver) -> Self::Artifact {
+		resolver.resolve_infallible(&self.leaf)
+	}
+}
+
+#[test]
+fn test_build_infallible() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderInfallibleNode {
+		leaf: leaf.clone(),
+	});
+
+	let leaf_bin = cache.build_infallible(&leaf);
+	assert!(rc::BinType::ptr_eq(&leaf_bin, &cache.get(&leaf).unpack()));
+
+	let node_bin = cache.build_infallible(&node);
+	assert!(rc::BinType::ptr_eq(&*node_bin, &leaf_bin));
+	assert!(cache.contains_artifact_id(node.id()));
+}
+
+#[test]
+fn test_make_mut() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	cache.get(&node).unpack();
+	assert!(cache.contains_artifact_id(node.id()));
+
+	// Shared with the node artifact, thus cloned
+	let shared = cache.get(&leaf).unpack();
+	let ptr = cache.make_mut(&leaf).unpack() as *const Leaf;
+	assert_ne!(ptr, &*shared as *const Leaf);
+	assert!(!cache.contains_artifact_id(node.id()));
+
+	// Only held by the cache, thus mutated in place
+	drop(shared);
+	cache.make_mut(&leaf).unpack().id = 42;
+	assert_eq!(cache.make_mut(&leaf).unpack() as *const Leaf, ptr);
+	assert_eq!(cache.get(&leaf).unpack().id, 42);
+
+	cache.assert_consistent();
+}
+
+#[derive(Debug)]
+struct BuilderOrderNode {
+	first: rc::Blueprint<BuilderLeaf>,
+	second: rc::Blueprint<BuilderLeaf>,
+}
+
+impl rc::SimpleBuilder for BuilderOrderNode {
+	type Artifact = Vec<BuilderId>;
+
+	fn build(&self, resolver: &mut rc::Resolver) -> Self::Artifact {
+		assert!(resolver.resolved_so_far().is_empty());
+
+		resolver.resolve(&self.second).unpack();
+		resolver.resolve(&self.first).unpack();
+		resolver.resolve(&self.second).unpack();
+
+		resolver.resolved_so_far().to_vec()
+	}
+}
+
+#[test]
+fn test_resolved_so_far() {
+	let mut cache = rc::Cache::new();
+
+	let first = Blueprint::new(BuilderLeaf::new());
+	let second = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderOrderNode {
+		first: first.clone(),
+		second: second.clone(),
+	});
+
+	assert_eq!(*cache.get(&node).unpack(), vec![second.id(), first.id()]);
+}
+
+#[derive(Debug)]
+struct BuilderCtxLeaf;
+
+impl rc::SimpleBuilder for BuilderCtxLeaf {
+	type Artifact = Option<u32>;
+
+	fn build(&self, resolver: &mut rc::Resolver) -> Self::Artifact {
+		resolver.ctx_mut::<Vec<u32>>().and_then(|ctx| ctx.pop())
+	}
+}
+
+#[test]
+fn test_get_with_ctx() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderCtxLeaf);
+	let other = Blueprint::new(BuilderCtxLeaf);
+
+	let (art, ctx) = cache.get_with_ctx(&leaf, vec![1_u32, 2]);
+	assert_eq!(*art.unpack(), Some(2));
+	assert_eq!(ctx, vec![1]);
+
+	// Cached artifacts don't touch the context
+	let (art, ctx) = cache.get_with_ctx(&leaf, ctx);
+	assert_eq!(*art.unpack(), Some(2));
+	assert_eq!(ctx, vec![1]);
+
+	// Without or with a different context there is none
+	assert_eq!(*cache.get_with_ctx(&other, 42_u32).0.unpack(), None);
+	cache.invalidate(&other);
+	assert_eq!(*cache.get(&other).unpack(), None);
+
+	// A panicking builder drops the context
+	let panicking = Blueprint::new(BuilderPanicking);
+	let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+		cache.get_with_ctx(&panicking, vec![3_u32])
+	}));
+	assert!(res.is_err());
+	cache.invalidate(&other);
+	assert_eq!(*cache.get(&other).unpack(), None);
+}
+
+#[test]
+fn test_new_with_doctor() {
+	// Deliberately not `Default`
+	#[derive(Debug)]
+	#[cfg_attr(not(feature = "diagnostics"), allow(dead_code))]
+	struct CountingDoctor {
+		count: u32,
+	}
+
+	#[cfg(feature = "diagnostics")]
+	impl<ArtCan, BCan> crate::diagnostics::Doctor<ArtCan, BCan> for CountingDoctor {
+		fn clear(&mut self) {
+			self.count += 1;
+		}
+	}
+
+	// The same construction compiles with and without diagnostics
+	let mut cache = rc::Cache::new_with_doctor(CountingDoctor { count: 0 });
+	assert_eq!(cache.number_of_known_builders(), 0);
+
+	cache.clear_all();
+	#[cfg(feature = "diagnostics")]
+	assert_eq!(cache.into_doctor().count, 1);
+}
+
+#[test]
+#[cfg(feature = "diagnostics")]
+fn test_doctor_dyn_state_changed() {
+	use crate::diagnostics::Doctor;
+	use crate::diagnostics::BuilderHandle;
+
+	#[derive(Debug, Default)]
+	struct ReconfigDoctor {
+		changed: Vec<BuilderId>,
+	}
+
+	impl<ArtCan, BCan> Doctor<ArtCan, BCan> for ReconfigDoctor {
+		fn dyn_state_changed(&mut self, builder: &BuilderHandle<BCan>) {
+			self.changed.push(builder.id());
+		}
+	}
+
+	let mut cache = rc::Cache::new_with_doctor(ReconfigDoctor::default());
+
+	let leaf = Blueprint::new(BuilderLeafFallible::new());
+	let other = Blueprint::new(BuilderLeafFallible::new());
+
+	// Neither building nor reading the dyn state counts
+	let _ = cache.get(&leaf);
+	cache.dyn_state(&leaf);
+	assert!(cache.doctor().changed.is_empty());
+
+	*cache.dyn_state_mut(&leaf) = true;
+	cache.batch(|ctx| {
+		ctx.set_dyn_state(&other, true);
+	});
+
+	assert_eq!(cache.doctor().changed, vec![leaf.id(), other.id()]);
+}
+
+#[test]
+fn test_builder_id_usize() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	cache.get(&leaf).unpack();
+
+	let raw = leaf.id().as_usize();
+	let id = BuilderId::from_usize(raw);
+
+	assert_eq!(id, leaf.id());
+	assert!(cache.is_builder_known_id(id));
+}
+
+#[test]
+fn test_rebuild_dirty() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	let fallible = Blueprint::new(BuilderLeafFallible::new());
+
+	cache.watch(&node);
+	cache.watch(&fallible);
+	cache.get(&node).unpack();
+	cache.get(&fallible).unwrap();
+	assert!(cache.rebuild_dirty().is_empty());
+
+	// The leaf isn't watched, but rebuilt as dependency of the node
+	cache.invalidate(&leaf);
+	*cache.dyn_state_mut(&fallible) = false;
+
+	// Only failed builds carry an error
+	let rebuilt: Vec<_> = cache.rebuild_dirty().into_iter()
+		.map(|(bid, res)| (bid, res.map_err(|err| err.is_some())))
+		.collect();
+	let mut expected = vec![
+		(leaf.id(), Err(false)),
+		(node.id(), Ok(())),
+		(fallible.id(), Err(true)),
+	];
+	expected.sort();
+	assert_eq!(rebuilt, expected);
+
+	assert!(cache.contains_artifact_id(node.id()));
+	assert!(cache.contains_artifact_id(leaf.id()));
+	assert!(!cache.contains_artifact_id(fallible.id()));
+	assert!(cache.rebuild_dirty().is_empty());
+
+	// Builds in between clear the mark
+	cache.invalidate(&leaf);
+	cache.get(&node).unpack();
+	assert!(cache.rebuild_dirty().is_empty());
+
+	// The error of a failed build is kept
+	*cache.dyn_state_mut(&fallible) = true;
+	cache.get(&fallible).unwrap();
+	*cache.dyn_state_mut(&fallible) = false;
+	let rebuilt = cache.rebuild_dirty();
+	assert_eq!(rebuilt.len(), 1);
+	let err = rebuilt[0].1.as_ref().unwrap_err().as_ref().unwrap();
+	assert!(err.downcast_ref::<()>().is_some());
+
+	// Cleared artifacts are dirty too
+	cache.clear_artifacts();
+	let rebuilt = cache.rebuild_dirty();
+	assert!(rebuilt.iter().any(|(bid, res)| *bid == node.id() && res.is_ok()));
+	assert!(cache.contains_artifact_id(node.id()));
+
+	// Purged builders are no longer dirty
+	cache.invalidate(&leaf);
+	cache.purge(&node);
+	let rebuilt = cache.rebuild_dirty();
+	assert_eq!(rebuilt.len(), 1);
+	assert_eq!(rebuilt[0].0, leaf.id());
+	assert!(rebuilt[0].1.as_ref().unwrap_err().is_none());
+
+	cache.assert_consistent();
+}
+
+#[derive(Debug)]
+struct BuilderStaleNode {
+	leaf: rc::Blueprint<BuilderLeafFallible>,
+}
+
+impl rc::Builder for BuilderStaleNode {
+	type Artifact = rc::BinType<Leaf>;
+	type DynState = ();
+	type Err = ();
+
+	fn build(&self, resolver: &mut rc::Resolver) -> Result<rc::BinType<Self::Artifact>, ()> {
+		resolver.resolve_or_stale(&self.leaf).map(rc::BinType::new)
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+#[test]
+fn test_resolve_or_stale() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeafFallible::new());
+	let node = Blueprint::new(BuilderStaleNode {
+		leaf: leaf.clone(),
+	});
+
+	// Without a previous artifact the error is passed through
+	*cache.dyn_state_mut(&leaf) = false;
+	assert!(cache.get(&node).is_err());
+
+	*cache.dyn_state_mut(&leaf) = true;
+	let first = cache.get(&node).unwrap();
+
+	// The leaf fails now, thus its stale artifact is used
+	*cache.dyn_state_mut(&leaf) = false;
+	assert!(!cache.contains_artifact_id(node.id()));
+	let second = cache.get(&node).unwrap();
+	assert!(rc::BinType::ptr_eq(&*first, &*second));
+	assert!(cache.get(&leaf).is_err());
+
+	// A new artifact replaces the stale one
+	*cache.dyn_state_mut(&leaf) = true;
+	let third = cache.get(&node).unwrap();
+	assert!(!rc::BinType::ptr_eq(&*first, &*third));
+
+	cache.assert_consistent();
+}
+
+#[cfg(feature = "lru")]
+#[derive(Debug)]
+pub(crate) struct BuilderSized {
+	pub(crate) size: usize,
+}
+
+#[cfg(feature = "lru")]
+impl rc::Builder for BuilderSized {
+	type Artifact = Leaf;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, _resolver: &mut rc::Resolver) -> Result<rc::BinType<Self::Artifact>, Never> {
+		Ok(rc::BinType::new(Leaf {
+			id: COUNTER.fetch_add(1, Ordering::SeqCst),
+		}))
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+
+	fn artifact_size_hint(&self, _artifact: &Self::Artifact) -> usize {
+		self.size
+	}
+}
+
+#[derive(Debug)]
+pub(crate) struct BuilderPanicking;
+
+impl rc::Builder for BuilderPanicking {
+	type Artifact = Leaf;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, _resolver: &mut rc::Resolver) -> Result<rc::BinType<Self::Artifact>, Never> {
+		panic!("builder panicked")
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+#[cfg(feature = "lru")]
+#[test]
+fn test_memory_budget() {
+	let mut cache = rc::Cache::new();
+
+	let a = Blueprint::new(BuilderSized { size: 10 });
+	let b = Blueprint::new(BuilderSized { size: 20 });
+	let c = Blueprint::new(BuilderSized { size: 30 });
+	let free = Blueprint::new(BuilderLeaf::new());
+
+	cache.get(&a).unpack();
+	cache.get(&b).unpack();
+	cache.get(&free).unpack();
+	assert_eq!(cache.approx_memory(), 30);
+
+	// Access `a`, thus `b` is the least recently used
+	cache.get(&a).unpack();
+	cache.set_memory_budget(45);
+	cache.get(&c).unpack();
+
+	assert!(cache.contains_artifact_id(a.id()));
+	assert!(!cache.contains_artifact_id(b.id()));
+	assert!(cache.contains_artifact_id(c.id()));
+	assert!(cache.contains_artifact_id(free.id()));
+	assert_eq!(cache.approx_memory(), 40);
+
+	// Shrinking the budget evicts right away
+	cache.set_memory_budget(35);
+	assert!(!cache.contains_artifact_id(a.id()));
+	assert_eq!(cache.approx_memory(), 30);
+
+	// Just built artifacts are kept
+	cache.set_memory_budget(0);
+	assert_eq!(cache.approx_memory(), 0);
+	cache.get(&b).unpack();
+	assert_eq!(cache.approx_memory(), 20);
+
+	cache.clear_memory_budget();
+	cache.get(&a).unpack();
+	assert_eq!(cache.approx_memory(), 30);
+
+	cache.assert_consistent();
+}
+
+#[cfg(feature = "lru")]
+#[test]
+fn test_evict_lru() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	let other = Blueprint::new(BuilderLeaf::new());
+	let fallible = Blueprint::new(BuilderLeafFallible::new());
+
+	cache.get(&node).unpack();
+	cache.get(&other).unpack();
+	cache.get(&fallible).unwrap();
+
+	// Accessing the node doesn't count for the leaf
+	cache.get(&node).unpack();
+	assert_eq!(cache.evict_lru(3), vec![leaf.id(), node.id()]);
+	assert!(cache.contains_artifact_id(other.id()));
+	assert!(cache.contains_artifact_id(fallible.id()));
+
+	cache.get(&other).unpack();
+	assert_eq!(cache.evict_lru(1), vec![fallible.id()]);
+	assert!(cache.evict_lru(1).is_empty());
+
+	assert_eq!(cache.evict_lru(0), vec![other.id()]);
+	assert!(!cache.contains_artifact_id(other.id()));
+
+	cache.assert_consistent();
+}
+
+#[derive(Debug)]
+struct BuilderChainNode {
+	prev: Option<Blueprint<BuilderChainNode, rc::BuilderCan>>,
+}
+
+impl rc::Builder for BuilderChainNode {
+	type Artifact = u32;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, resolver: &mut rc::Resolver) -> Result<rc::BinType<Self::Artifact>, Never> {
+		let len = match &self.prev {
+			Some(prev) => *resolver.resolve(prev).unpack() + 1,
+			None => 1,
+		};
+
+		Ok(rc::BinType::new(len))
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+
+	fn declare_dependencies(&self) -> Vec<Box<dyn cache::DeclaredDependency<rc::CanType, rc::CanType>>> {
+		self.prev.iter()
+			.map(|prev| Box::new(prev.clone()) as Box<dyn cache::DeclaredDependency<_, _>>)
+			.collect()
+	}
+}
+
+#[test]
+fn test_plan_declared_dependencies() {
+	let mut cache = rc::Cache::new();
+
+	let first = Blueprint::new(BuilderChainNode { prev: None });
+	let second = Blueprint::new(BuilderChainNode { prev: Some(first.clone()) });
+	let third = Blueprint::new(BuilderChainNode { prev: Some(second.clone()) });
+
+	// Declared dependencies are known before anything is built
+	assert_eq!(cache.plan(&third), vec![third.id(), second.id(), first.id()]);
+
+	cache.get(&second).unpack();
+	assert_eq!(cache.plan(&third), vec![third.id()]);
+}
+
+#[derive(Debug)]
+struct BuilderFailingChainUser {
+	dep: Blueprint<BuilderChainNode, rc::BuilderCan>,
+}
+
+impl rc::Builder for BuilderFailingChainUser {
+	type Artifact = u32;
+	type DynState = ();
+	type Err = ();
+
+	fn build(&self, resolver: &mut rc::Resolver) -> Result<rc::BinType<Self::Artifact>, ()> {
+		resolver.resolve(&self.dep).unpack();
+
+		Err(())
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+#[test]
+fn test_plan_declared_behind_recorded() {
+	let mut cache = rc::Cache::new();
+
+	let first = Blueprint::new(BuilderChainNode { prev: None });
+	let second = Blueprint::new(BuilderChainNode { prev: Some(first.clone()) });
+	let user = Blueprint::new(BuilderFailingChainUser { dep: second.clone() });
+
+	// The failed build records its dependency upon the second node, which
+	// keeps it when invalidated to depth 1
+	assert!(cache.get(&user).is_err());
+	cache.invalidate_to_depth(&first, 1);
+	assert!(!cache.contains_artifact_id(second.id()));
+
+	// The declaration of the recorded dependency is followed too
+	assert_eq!(cache.plan(&user), vec![user.id(), second.id(), first.id()]);
+	cache.assert_consistent();
+}
+
+#[test]
+fn test_get_iterative() {
+	let mut cache = rc::Cache::new();
+
+	let mut chain = Vec::new();
+	let mut prev = None;
+	for _ in 0..2000 {
+		let node = Blueprint::new(BuilderChainNode { prev: prev.take() });
+		prev = Some(node.clone());
+		chain.push(node);
+	}
+	let head = chain.last().unwrap();
+
+	assert_eq!(*cache.get_iterative(head).unpack(), 2000);
+
+	for node in &chain {
+		assert!(cache.contains_artifact_id(node.id()));
+	}
+	cache.assert_consistent();
+}
+
+#[test]
+fn test_cache_hasher() {
+	use std::collections::hash_map::DefaultHasher;
+	use std::hash::BuildHasherDefault;
+
+	let mut cache: rc::CacheOwned =
+		CacheOwned::with_hasher(BuildHasherDefault::<DefaultHasher>::default());
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let nodes: Vec<_> = (0..100)
+		.map(|_| Blueprint::new(BuilderSimpleNode::new(leaf.clone())))
+		.collect();
+
+	let arts: Vec<_> = nodes.iter().map(|node| cache.get(node).unpack()).collect();
+	for (node, art) in nodes.iter().zip(&arts) {
+		assert!(std::rc::Rc::ptr_eq(art, &cache.get(node).unpack()));
+	}
+	cache.assert_consistent();
+
+	cache.invalidate(&leaf);
+	for node in &nodes {
+		assert!(!cache.contains_artifact_id(node.id()));
+	}
+	cache.assert_consistent();
+}
+
+#[test]
+fn test_select_builder() {
+	use crate::utils::SelectBuilder;
+
+	let mut cache = rc::Cache::new();
+
+	let first = Blueprint::new(BuilderLeaf::new());
+	let second = Blueprint::new(BuilderLeaf::new());
+	let select = Blueprint::new(SelectBuilder::new::<rc::CanType, _, _>(vec![first.clone(), second.clone()]));
+
+	let art = cache.get(&select).unpack();
+	assert!(std::rc::Rc::ptr_eq(&art, &cache.get(&first).unpack()));
+	assert!(!cache.contains_artifact_id(second.id()));
+
+	// Switching invalidates the selection
+	*cache.dyn_state_mut(&select) = 1;
+	assert!(!cache.contains_artifact_id(select.id()));
+
+	let art = cache.get(&select).unpack();
+	assert!(std::rc::Rc::ptr_eq(&art, &cache.get(&second).unpack()));
+
+	// Only the selected candidate is a dependency
+	cache.invalidate(&first);
+	assert!(cache.contains_artifact_id(select.id()));
+	cache.invalidate(&second);
+	assert!(!cache.contains_artifact_id(select.id()));
+
+	// Out of bounds indices select the last candidate
+	*cache.dyn_state_mut(&select) = 5;
+	let art = cache.get(&select).unpack();
+	assert!(std::rc::Rc::ptr_eq(&art, &cache.get(&second).unpack()));
+
+	cache.assert_consistent();
+}
+
+#[test]
+#[should_panic(expected = "SelectBuilder without candidates")]
+fn test_select_builder_empty() {
+	use crate::utils::SelectBuilder;
+
+	let _select = SelectBuilder::new::<rc::CanType, _, _>(Vec::<rc::Blueprint<BuilderLeaf>>::new());
+}
+
+#[test]
+fn test_get_cow() {
+	use std::borrow::Cow;
+
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let id = cache.get_cloned(&leaf).unpack().id;
+
+	match cache.get_cow(&leaf).unpack() {
+		Cow::Borrowed(art) => assert_eq!(art.id, id),
+		Cow::Owned(_) => panic!("Artifact should be borrowed"),
+	}
+
+	let owned: Leaf = cache.get_cow(&leaf).unpack().into_owned();
+	assert_eq!(owned.id, id);
+}
+
+#[derive(Debug)]
+struct BuilderSeeded;
+
+impl rc::Builder for BuilderSeeded {
+	type Artifact = u64;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, resolver: &mut rc::Resolver) -> Result<rc::BinType<Self::Artifact>, Never> {
+		Ok(rc::BinType::new(resolver.seed()))
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+#[test]
+fn test_seed_provider() {
+	let bp = Blueprint::new(BuilderSeeded);
+	let key = bp.id();
+
+	let seeds = |base: u64| {
+		let mut cache = rc::Cache::new();
+		cache.set_seed_provider(move |id| if id == key { base } else { 0 });
+
+		let first = *cache.get(&bp).unpack();
+		// Cached artifacts keep their seed
+		assert_eq!(first, *cache.get(&bp).unpack());
+
+		cache.invalidate(&bp);
+		let second = *cache.get(&bp).unpack();
+
+		cache.assert_consistent();
+		(first, second)
+	};
+
+	let (first, second) = seeds(42);
+	assert_ne!(first, second);
+
+	// Reproducible with the same provider
+	assert_eq!((first, second), seeds(42));
+	assert_ne!((first, second), seeds(7));
+
+	// Without provider, only the generation is used
+	let mut cache = rc::Cache::new();
+	let unseeded = *cache.get(&bp).unpack();
+	cache.set_seed_provider(|_| 0);
+	cache.clear_seed_provider();
+	cache.invalidate(&bp);
+	assert_ne!(unseeded, *cache.get(&bp).unpack());
+	assert_eq!(unseeded, seeds(0).0);
+}
+
+#[test]
+fn test_merge_from() {
+	let leaf1 = Blueprint::new(BuilderLeaf::new());
+	let leaf2 = Blueprint::new(BuilderLeaf::new());
+	let node1 = Blueprint::new(BuilderSimpleNode::new(leaf1.clone()));
+	let node2 = Blueprint::new(BuilderSimpleNode::new(leaf1.clone()));
+	let node3 = Blueprint::new(BuilderSimpleNode::new(leaf2.clone()));
+
+	let mut cache = rc::Cache::new();
+	let leaf1_id = cache.get(&node1).unpack().leaf.id;
+
+	let leaf3 = Blueprint::new(BuilderLeaf::new());
+	let node4 = Blueprint::new(BuilderSimpleNode::new(leaf3.clone()));
+
+	let mut other = rc::Cache::new();
+	other.get(&node2).unpack();
+	let node3_id = other.get(&node3).unpack().id;
+	assert_ne!(other.get(&leaf1).unpack().id, leaf1_id);
+	other.watch(&node4);
+	other.get(&node4).unpack();
+	other.invalidate(&leaf3);
+	other.freeze(&leaf2);
+
+	cache.merge_from(other);
+	cache.assert_consistent();
+
+	// This cache wins on conflict, thus the dependents are not imported
+	assert_eq!(cache.get(&leaf1).unpack().id, leaf1_id);
+	assert!(!cache.contains_artifact_id(node2.id()));
+	assert_eq!(cache.get(&node2).unpack().leaf.id, leaf1_id);
+
+	// Imported artifacts are served without rebuilding
+	assert_eq!(cache.get(&node3).unpack().id, node3_id);
+
+	// Watched, dirty, and frozen builders are carried over
+	assert!(cache.is_frozen(&leaf2));
+	cache.unfreeze(&leaf2);
+	let rebuilt = cache.rebuild_dirty();
+	assert_eq!(rebuilt.len(), 2);
+	assert!(rebuilt.iter().any(|(bid, res)| *bid == leaf3.id() && res.is_err()));
+	assert!(rebuilt.iter().any(|(bid, res)| *bid == node4.id() && res.is_ok()));
+	assert!(cache.contains_artifact_id(node4.id()));
+
+	// Dependency relations are unioned
+	cache.invalidate(&leaf1);
+	assert!(!cache.contains_artifact_id(node1.id()));
+	assert!(!cache.contains_artifact_id(node2.id()));
+	assert!(cache.contains_artifact_id(node3.id()));
+	cache.assert_consistent();
+
+	cache.invalidate(&leaf2);
+	assert!(!cache.contains_artifact_id(node3.id()));
+	cache.assert_consistent();
+}
+
+#[cfg(feature = "artifact_version")]
+#[test]
+fn test_artifact_version() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	assert_eq!(cache.artifact_version(&node), 0);
+
+	cache.get(&node).unpack();
+	assert_eq!(cache.artifact_version(&leaf), 1);
+	assert_eq!(cache.artifact_version(&node), 1);
+
+	// Cache hits keep the version
+	cache.get(&node).unpack();
+	assert_eq!(cache.artifact_version(&node), 1);
+
+	// Invalidation keeps the version until the rebuild
+	cache.invalidate(&leaf);
+	assert_eq!(cache.artifact_version(&node), 1);
+
+	cache.get(&node).unpack();
+	assert_eq!(cache.artifact_version(&leaf), 2);
+	assert_eq!(cache.artifact_version(&node), 2);
+
+	cache.purge(&node);
+	assert_eq!(cache.artifact_version(&node), 0);
+	assert_eq!(cache.artifact_version(&leaf), 2);
+	cache.assert_consistent();
+}
+
+#[test]
+fn test_result_builder() {
+	use crate::utils::ResultBuilder;
+	use std::cell::Cell;
+
+	let calls = std::rc::Rc::new(Cell::new(0_u32));
+	let counter = calls.clone();
+	let failing = Blueprint::new(ResultBuilder::new(move || {
+		counter.set(counter.get() + 1);
+		if counter.get() < 2 {
+			Err(counter.get())
+		} else {
+			Ok(counter.get())
+		}
+	}));
+
+	let mut cache = rc::Cache::new();
+
+	// The error arm is cached like any Artifact
+	assert_eq!(*cache.get(&failing).unpack(), Err(1));
+	assert_eq!(*cache.get(&failing).unpack(), Err(1));
+	assert_eq!(calls.get(), 1);
+
+	cache.invalidate(&failing);
+	assert_eq!(*cache.get(&failing).unpack(), Ok(2));
+	assert_eq!(calls.get(), 2);
+}
+
+#[derive(Debug)]
+struct BuilderFlaky {
+	fail: std::rc::Rc<std::cell::Cell<bool>>,
+}
+
+impl rc::Builder for BuilderFlaky {
+	type Artifact = Leaf;
+	type DynState = ();
+	type Err = ();
+
+	fn build(&self, _resolver: &mut rc::Resolver) -> Result<rc::BinType<Self::Artifact>, ()> {
+		if self.fail.get() {
+			Err(())
+		} else {
+			Ok(rc::BinType::new(Leaf {
+				id: COUNTER.fetch_add(1, Ordering::SeqCst),
+			}))
+		}
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+#[derive(Debug)]
+struct BuilderFlakyNode {
+	leaf: rc::Blueprint<BuilderFlaky>,
+}
+
+impl rc::Builder for BuilderFlakyNode {
+	type Artifact = rc::BinType<Leaf>;
+	type DynState = ();
+	type Err = ();
+
+	fn build(&self, resolver: &mut rc::Resolver) -> Result<rc::BinType<Self::Artifact>, ()> {
+		resolver.resolve(&self.leaf).map(rc::BinType::new)
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+#[test]
+fn test_rebuild_subtree() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let watched = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	let unwatched = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	cache.watch(&watched);
+	let old_id = cache.get(&watched).unpack().id;
+	cache.get(&unwatched).unpack();
+
+	let report = cache.rebuild_subtree(&leaf, true);
+	assert_eq!(report.rebuilt, vec![leaf.id(), watched.id()]);
+	assert!(report.failed.is_empty());
+	assert_eq!(report.skipped, vec![unwatched.id()]);
+	assert!(!report.is_complete());
+
+	assert_ne!(cache.get(&watched).unpack().id, old_id);
+	assert!(!cache.contains_artifact_id(unwatched.id()));
+	cache.assert_consistent();
+
+	// A failing root
+	let fail = std::rc::Rc::new(std::cell::Cell::new(false));
+	let flaky = Blueprint::new(BuilderFlaky {
+		fail: fail.clone(),
+	});
+	let node = Blueprint::new(BuilderFlakyNode {
+		leaf: flaky.clone(),
+	});
+
+	cache.watch(&node);
+	cache.get(&node).unwrap();
+	fail.set(true);
+
+	let report = cache.rebuild_subtree(&flaky, true);
+	assert!(report.rebuilt.is_empty());
+	assert_eq!(report.failed, vec![flaky.id()]);
+	assert_eq!(report.skipped, vec![node.id()]);
+
+	fail.set(false);
+	cache.get(&node).unwrap();
+	fail.set(true);
+
+	let report = cache.rebuild_subtree(&flaky, false);
+	assert!(report.rebuilt.is_empty());
+	assert_eq!(report.failed, vec![flaky.id(), node.id()]);
+	assert!(report.skipped.is_empty());
+
+	fail.set(false);
+	cache.get(&node).unwrap();
+
+	let report = cache.rebuild_subtree(&flaky, true);
+	assert_eq!(report.rebuilt, vec![flaky.id(), node.id()]);
+	assert!(report.is_complete());
+	cache.assert_consistent();
+}
+
+#[test]
+fn test_with_frozen_config() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeafFallible::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(Blueprint::new(BuilderLeaf::new())));
+
+	let id = cache.with_frozen_config(|cache| {
+		let id = cache.get(&leaf).unwrap().id;
+		cache.get(&node).unpack();
+
+		// Reading dyn states is fine
+		assert!(*cache.dyn_state(&leaf));
+
+		// Nested calls keep the states frozen
+		cache.with_frozen_config(|cache| cache.get(&leaf).unwrap());
+		id
+	});
+	assert_eq!(cache.get(&leaf).unwrap().id, id);
+
+	// Unfrozen again
+	*cache.dyn_state_mut(&leaf) = false;
+	assert!(cache.get(&leaf).is_err());
+
+	// A panic within the function unfreezes the states too
+	let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+		cache.with_frozen_config(|_| panic!("Panicking within frozen config"))
+	}));
+	assert!(res.is_err());
+	*cache.dyn_state_mut(&leaf) = true;
+	assert!(cache.get(&leaf).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "configuration is frozen")]
+fn test_with_frozen_config_panics() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeafFallible::new());
+
+	cache.with_frozen_config(|cache| {
+		cache.batch(|ctx| ctx.set_dyn_state(&leaf, false));
+	});
+}
+
+#[test]
+#[cfg(feature = "provenance")]
+fn test_artifact_provenance() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeafFallible::new());
+	let node = Blueprint::new(BuilderStaleNode {
+		leaf: leaf.clone(),
+	});
+
+	assert!(cache.artifact_provenance(&node).is_none());
+
+	cache.get(&node).unwrap();
+
+	let provenance = cache.artifact_provenance(&node).unwrap();
+	assert_eq!(provenance.artifacts, vec![leaf.id()]);
+	assert!(provenance.dyn_states.is_empty());
+
+	let provenance = cache.artifact_provenance(&leaf).unwrap();
+	assert!(provenance.artifacts.is_empty());
+	assert_eq!(provenance.dyn_states, vec![leaf.id()]);
+
+	// The provenance goes along with the artifact
+	cache.invalidate(&leaf);
+	assert!(cache.artifact_provenance(&leaf).is_none());
+	assert!(cache.artifact_provenance(&node).is_none());
+	cache.assert_consistent();
+}
+
+#[test]
+#[cfg(feature = "provenance")]
+fn test_precise_invalidation() {
+	let mut cache = rc::Cache::new();
+	cache.set_precise_invalidation(true);
+
+	// `BuilderLeaf` doesn't read its dyn state
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	let fallible = Blueprint::new(BuilderLeafFallible::new());
+
+	let node_id = cache.get(&node).unpack().id;
+	cache.get(&fallible).unwrap();
+
+	*cache.dyn_state_mut(&leaf) = ();
+	assert_eq!(cache.get(&node).unpack().id, node_id);
+
+	// `BuilderLeafFallible` does read its dyn state
+	*cache.dyn_state_mut(&fallible) = false;
+	assert!(cache.get(&fallible).is_err());
+
+	cache.batch(|ctx| ctx.set_dyn_state(&fallible, true));
+	assert!(cache.get(&fallible).is_ok());
+
+	// Back to the default
+	cache.set_precise_invalidation(false);
+	*cache.dyn_state_mut(&leaf) = ();
+	assert_ne!(cache.get(&node).unpack().id, node_id);
+	cache.assert_consistent();
+}
+
+#[cfg(feature = "provenance")]
+#[derive(Debug, Default)]
+struct BuilderSwitchedFailure {
+	builds: AtomicU32,
+}
+
+#[cfg(feature = "provenance")]
+impl rc::Builder for BuilderSwitchedFailure {
+	type Artifact = ();
+	type DynState = bool;
+	type Err = u32;
+
+	fn build(&self, resolver: &mut rc::Resolver<bool>) -> Result<std::rc::Rc<()>, u32> {
+		let builds = self.builds.fetch_add(1, Ordering::SeqCst);
+
+		if *resolver.my_state() {
+			Ok(std::rc::Rc::new(()))
+		} else {
+			Err(builds)
+		}
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		false
+	}
+
+	fn cache_error(&self, err: &u32) -> Option<u32> {
+		Some(*err)
+	}
+}
+
+#[test]
+#[cfg(feature = "provenance")]
+fn test_precise_invalidation_cached_error() {
+	let mut cache = rc::Cache::new();
+	cache.set_precise_invalidation(true);
+
+	let switched = rc::Blueprint::new(BuilderSwitchedFailure::default());
+
+	assert_eq!(Err(0), cache.get(&switched));
+	assert_eq!(Err(0), cache.get(&switched));
+
+	// The cached error must not outlive the configuration causing it
+	*cache.dyn_state_mut(&switched) = true;
+	assert!(!cache.has_cached_error(&switched));
+	assert!(cache.get(&switched).is_ok());
+
+	// Thus it has been built exactly once more
+	*cache.dyn_state_mut(&switched) = false;
+	assert_eq!(Err(2), cache.get(&switched));
+	cache.assert_consistent();
+}
+
+#[derive(Debug, Default)]
+struct CountingStore {
+	artifacts: std::collections::HashMap<BuilderId, rc::CanType>,
+	inserts: std::rc::Rc<std::cell::Cell<usize>>,
+}
+
+impl crate::cache::ArtifactStore<rc::CanType> for CountingStore {
+	fn insert(&mut self, id: BuilderId, can: rc::CanType) -> Option<rc::CanType> {
+		self.inserts.set(self.inserts.get() + 1);
+		self.artifacts.insert(id, can)
+	}
+
+	fn get(&self, id: BuilderId) -> Option<&rc::CanType> {
+		self.artifacts.get(&id)
+	}
+
+	fn get_mut(&mut self, id: BuilderId) -> Option<&mut rc::CanType> {
+		self.artifacts.get_mut(&id)
+	}
+
+	fn remove(&mut self, id: BuilderId) -> Option<rc::CanType> {
+		self.artifacts.remove(&id)
+	}
+
+	fn clear(&mut self) {
+		self.artifacts.clear()
+	}
+
+	fn len(&self) -> usize {
+		self.artifacts.len()
+	}
+
+	fn ids(&self) -> Box<dyn Iterator<Item = BuilderId> + '_> {
+		Box::new(self.artifacts.keys().copied())
+	}
+}
+
+#[test]
+fn test_artifact_store() {
+	let store = CountingStore::default();
+	let inserts = store.inserts.clone();
+
+	let mut cache = rc::Cache::with_store(store);
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	let id = cache.get(&node).unpack().id;
+	assert_eq!(inserts.get(), 2);
+	assert_eq!(cache.get(&node).unpack().id, id);
+	assert_eq!(inserts.get(), 2);
+	assert_eq!(cache.number_of_known_builders(), 2);
+
+	cache.invalidate(&leaf);
+	assert!(!cache.contains_artifact_id(node.id()));
+	cache.get(&node).unpack();
+	assert_eq!(inserts.get(), 4);
+	cache.assert_consistent();
+
+	cache.clear_artifacts();
+	assert!(!cache.contains_artifact_id(leaf.id()));
+	cache.assert_consistent();
+}
+
+#[test]
+#[should_panic(expected = "Replacing a non-empty artifact store")]
+fn test_artifact_store_non_empty() {
+	let mut store = CountingStore::default();
+	let leaf = rc::Blueprint::new(BuilderLeaf::new());
+	crate::cache::ArtifactStore::insert(&mut store, leaf.id(), std::rc::Rc::new(()));
+
+	rc::Cache::with_store(store);
+}
+
+#[derive(Debug)]
+struct BuilderPickingNode {
+	candidates: Vec<rc::Blueprint<BuilderLeaf>>,
+}
+
+impl rc::Builder for BuilderPickingNode {
+	type Artifact = (BuilderId, u32);
+	type DynState = usize;
+	type Err = Never;
+
+	fn build(&self, resolver: &mut rc::Resolver<Self::DynState>) -> Result<rc::BinType<Self::Artifact>, Never> {
+		let picked = &self.candidates[*resolver.my_state()];
+		let (id, leaf) = resolver.resolve_with_id(picked)?;
+
+		Ok(rc::BinType::new((id, leaf.id)))
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		0
+	}
+}
+
+#[test]
+fn test_resolve_with_id() {
+	let mut cache = rc::Cache::new();
+
+	let first = Blueprint::new(BuilderLeaf::new());
+	let second = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderPickingNode {
+		candidates: vec![first.clone(), second.clone()],
+	});
+
+	let (id, leaf_id) = *cache.get(&node).unpack();
+	assert_eq!(id, first.id());
+	assert_eq!(leaf_id, cache.get(&first).unpack().id);
+
+	*cache.dyn_state_mut(&node) = 1;
+	let (id, leaf_id) = *cache.get(&node).unpack();
+	assert_eq!(id, second.id());
+	assert_eq!(leaf_id, cache.get(&second).unpack().id);
+
+	// Tracked like a regular dependency
+	cache.invalidate(&second);
+	assert!(!cache.contains_artifact_id(node.id()));
+}
+
+#[test]
+fn test_get_once() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	let first = cache.get_once(&node).unpack();
+	assert!(!cache.contains_artifact_id(node.id()));
+	assert!(!cache.is_builder_known(&node));
+
+	// The dependency built for it is purged too
+	assert!(!cache.is_builder_known(&leaf));
+
+	// Known dependencies stay cached
+	let leaf_art = cache.get(&leaf).unpack();
+	let second = cache.get_once(&node).unpack();
+	assert_eq!(second.leaf.id, leaf_art.id);
+	assert!(cache.contains_artifact_id(leaf.id()));
+
+	// Built anew each time
+	assert_ne!(first.id, second.id);
+
+	// Cached artifacts are kept
+	let node_art = cache.get(&node).unpack();
+	assert_eq!(cache.get_once(&node).unpack().id, node_art.id);
+	assert!(cache.contains_artifact_id(node.id()));
+
+	// The dependencies built for a known builder don't linger either
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	cache.dyn_state_mut(&node);
+	cache.get_once(&node).unpack();
+	assert!(cache.is_builder_known(&node));
+	assert!(!cache.is_builder_known(&leaf));
+	assert!(!cache.contains_artifact_id(node.id()));
+	cache.assert_consistent();
+
+	cache.get(&node).unpack();
+	cache.invalidate(&leaf);
+	cache.get_once(&node).unpack();
+	assert!(cache.is_builder_known(&leaf));
+	assert!(!cache.contains_artifact_id(leaf.id()));
+	assert!(!cache.contains_artifact_id(node.id()));
+	cache.assert_consistent();
+
+	// Nothing is left behind of introduced builders
+	let fallible = Blueprint::new(BuilderLeafFallible::new());
+	assert!(cache.get_once(&fallible).is_ok());
+	assert!(!cache.is_builder_known(&fallible));
+
+	// The dyn state of known builders is kept
+	*cache.dyn_state_mut(&fallible) = false;
+	assert!(cache.get_once(&fallible).is_err());
+	assert!(!*cache.dyn_state(&fallible));
+	*cache.dyn_state_mut(&fallible) = true;
+	assert!(cache.get_once(&fallible).is_ok());
+	assert!(!cache.contains_artifact_id(fallible.id()));
+	assert!(cache.is_builder_known(&fallible));
+	cache.assert_consistent();
+}
+
+#[test]
+fn test_dyn_blueprint() {
+	let mut cache = rc::Cache::new();
+
+	let blueprints: Vec<rc::DynamicBlueprint<Leaf>> = vec![
+		rc::dyn_blueprint(BuilderLeaf::new()),
+		rc::dyn_blueprint(rc::ConstBuilder::new(rc::BinType::new(Leaf {
+			id: 42,
+		}))),
+	];
+
+	let leaf = cache.get(&blueprints[0]).unpack();
+	assert!(rc::BinType::ptr_eq(&leaf, &cache.get(&blueprints[0]).unpack()));
+	assert_eq!(cache.get(&blueprints[1]).unpack().id, 42);
+}
+
+#[test]
+#[cfg(feature = "rebuild_rate")]
+fn test_rebuild_rate() {
+	use std::time::Duration;
+
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	assert_eq!(cache.rebuild_rate(&node), 0.0);
+
+	cache.get(&node).unpack();
+	std::thread::sleep(Duration::from_millis(20));
+	let rate = cache.rebuild_rate(&node);
+	assert!(rate > 0.0 && rate <= 50.0, "{}", rate);
+
+	// Cache hits are no builds
+	cache.get(&node).unpack();
+	assert!(cache.rebuild_rate(&node) <= rate);
+
+	// Thrashing
+	for _ in 0..10 {
+		cache.invalidate(&leaf);
+		cache.get(&node).unpack();
+	}
+	assert!(cache.rebuild_rate(&node) > rate);
+
+	cache.purge(&node);
+	assert_eq!(cache.rebuild_rate(&node), 0.0);
+	cache.assert_consistent();
+}
+
+#[test]
+fn test_freeze() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	let root = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	let node_id = cache.get(&node).unpack().id;
+	cache.get(&root).unpack();
+	cache.freeze(&node);
+	assert!(cache.is_frozen(&node));
+
+	// Neither direct nor propagated invalidations remove it
+	cache.invalidate(&node);
+	cache.invalidate(&leaf);
+	assert!(cache.contains_artifact_id(node.id()));
+	assert!(!cache.contains_artifact_id(root.id()));
+	cache.assert_consistent();
+
+	cache.clear_artifacts();
+	assert!(cache.contains_artifact_id(node.id()));
+	assert!(!cache.contains_artifact_id(leaf.id()));
+	cache.assert_consistent();
+
+	#[cfg(feature = "lru")]
+	cache.evict_lru(0);
+	assert_eq!(cache.get(&node).unpack().id, node_id);
+
+	// Unfreezing doesn't invalidate, but the relations are kept
+	cache.unfreeze(&node);
+	assert!(!cache.is_frozen(&node));
+	assert_eq!(cache.get(&node).unpack().id, node_id);
+
+	cache.get(&leaf).unpack();
+	cache.invalidate(&leaf);
+	assert!(!cache.contains_artifact_id(node.id()));
+	cache.assert_consistent();
+
+	// Purging unfreezes
+	cache.freeze(&node);
+	cache.get(&node).unpack();
+	cache.purge(&node);
+	assert!(!cache.is_frozen(&node));
+	cache.assert_consistent();
+
+	// Invalidating a frozen builder along with its dependent
+	let first = Blueprint::new(BuilderChainNode { prev: None });
+	let second = Blueprint::new(BuilderChainNode { prev: Some(first.clone()) });
+	let third = Blueprint::new(BuilderChainNode { prev: Some(second.clone()) });
+
+	cache.get(&third).unpack();
+	cache.freeze(&second);
+	cache.batch(|ctx| {
+		ctx.invalidate(&third);
+		ctx.invalidate(&second);
+	});
+	assert!(cache.contains_artifact_id(second.id()));
+	assert!(!cache.contains_artifact_id(third.id()));
+	cache.assert_consistent();
+}
+
+#[test]
+#[cfg(feature = "dag_lint")]
+fn test_dag_lint() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	let lone = Blueprint::new(BuilderLeaf::new());
+
+	cache.get(&node).unpack();
+	cache.get(&lone).unpack();
+
+	let mut leaf_only = cache.leaf_only_builders();
+	leaf_only.sort();
+	let mut expected = vec![node.id(), lone.id()];
+	expected.sort();
+	assert_eq!(leaf_only, expected);
+	assert_eq!(cache.root_only_builders(), vec![leaf.id()]);
+
+	// Fetching a dependency directly makes it neither
+	cache.get(&leaf).unpack();
+	assert!(!cache.leaf_only_builders().contains(&leaf.id()));
+	assert!(cache.root_only_builders().is_empty());
+
+	cache.purge(&lone);
+	assert!(!cache.leaf_only_builders().contains(&lone.id()));
+	cache.assert_consistent();
+}
+
+#[test]
+#[cfg(feature = "deterministic_ids")]
+fn test_deterministic_ids() {
+	BuilderId::reset_deterministic_ids();
+
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	let other = rc::Blueprint::new(BuilderLeaf::new());
+
+	// Numbered in the order of the first query
+	cache.get(&node).unpack();
+	assert_eq!(node.id(), BuilderId::from_usize(0));
+	assert_eq!(leaf.id(), BuilderId::from_usize(1));
+	assert_eq!(other.id(), BuilderId::from_usize(2));
+
+	// Stable on further queries
+	assert_eq!(node.id(), BuilderId::from_usize(0));
+	assert!(cache.contains_artifact_id(BuilderId::from_usize(1)));
+	cache.assert_consistent();
+}
+
+#[test]
+#[cfg(feature = "deterministic_ids")]
+fn test_deterministic_ids_dropped() {
+	BuilderId::reset_deterministic_ids();
+
+	let first = rc::Blueprint::new(BuilderLeaf::new());
+	assert_eq!(first.id(), BuilderId::from_usize(0));
+	drop(first);
+
+	// Dropped Builders never lend their id to a later one
+	for i in 1..1000 {
+		let later = rc::Blueprint::new(BuilderLeaf::new());
+		assert_eq!(later.id(), BuilderId::from_usize(i));
+	}
+
+	// The entries of dropped Builders are purged
+	let len = crate::DETERMINISTIC_IDS.with(|ids| ids.borrow().ids.len());
+	assert!(len <= 64, "{} entries", len);
+}
+
+#[test]
+fn test_build_hooks() {
+	use std::cell::RefCell;
+
+	let mut cache = rc::Cache::new();
+
+	let events = std::rc::Rc::new(RefCell::new(Vec::new()));
+
+	let before = events.clone();
+	let after = events.clone();
+	cache.set_build_hooks(
+		move |bid| before.borrow_mut().push((bid, None)),
+		move |bid, ok| after.borrow_mut().push((bid, Some(ok))),
+	);
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	cache.get(&node).unpack();
+
+	// The build of the dependency is nested
+	assert_eq!(*events.borrow(), vec![
+		(node.id(), None),
+		(leaf.id(), None),
+		(leaf.id(), Some(true)),
+		(node.id(), Some(true)),
+	]);
+
+	// Cache hits call no hooks
+	events.borrow_mut().clear();
+	cache.get(&node).unpack();
+	assert!(events.borrow().is_empty());
+
+	// Failures are reported
+	let fallible = Blueprint::new(BuilderLeafFallible::new());
+	*cache.dyn_state_mut(&fallible) = false;
+	assert!(cache.get(&fallible).is_err());
+	assert_eq!(*events.borrow(), vec![
+		(fallible.id(), None),
+		(fallible.id(), Some(false)),
+	]);
+
+	cache.clear_build_hooks();
+	events.borrow_mut().clear();
+	cache.invalidate(&leaf);
+	cache.get(&node).unpack();
+	assert!(events.borrow().is_empty());
+}
+
+#[test]
+#[cfg(feature = "log")]
+fn test_log() {
+	use std::cell::RefCell;
+
+	thread_local! {
+		static RECORDS: RefCell<Vec<String>> = RefCell::new(Vec::new());
+	}
+
+	struct TestLogger;
+
+	impl log::Log for TestLogger {
+		fn enabled(&self, metadata: &log::Metadata) -> bool {
+			metadata.target() == "daab"
+		}
+		fn log(&self, record: &log::Record) {
+			if self.enabled(record.metadata()) {
+				RECORDS.with(|r| r.borrow_mut().push(format!("{}", record.args())));
+			}
+		}
+		fn flush(&self) {}
+	}
+
+	static LOGGER: TestLogger = TestLogger;
+	let _ = log::set_logger(&LOGGER);
+	log::set_max_level(log::LevelFilter::Trace);
+
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	cache.get(&node).unpack();
+	cache.invalidate(&leaf);
+	drop(node);
+	cache.garbage_collection();
+
+	let records = RECORDS.with(|r| r.borrow().clone());
+
+	assert!(records.iter().any(|r| r.starts_with("built") && r.ends_with("BuilderLeaf")));
+	assert!(records.iter().any(|r| r == &format!("invalidated {:?}", leaf.id())));
+	assert!(records.iter().any(|r| r.starts_with("collected")));
+}
+
+#[test]
+#[cfg(feature = "tracing")]
+fn test_tracing() {
+	use std::sync::Arc;
+	use std::sync::Mutex;
+	use tracing::span;
+
+	/// Records the `builder` field of each span along with the one of its
+	/// parent span.
+	#[derive(Default)]
+	struct TestSubscriber {
+		spans: Mutex<Vec<String>>,
+		stack: Mutex<Vec<span::Id>>,
+		built: Arc<Mutex<Vec<(String, Option<String>)>>>,
+	}
+
+	struct BuilderField(String);
+
+	impl tracing::field::Visit for BuilderField {
+		fn record_debug(&mut self, _field: &tracing::field::Field, _value: &dyn Debug) {}
+		fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+			if field.name() == "builder" {
+				self.0 = value.to_string();
+			}
+		}
+	}
+
+	impl tracing::Subscriber for TestSubscriber {
+		fn enabled(&self, _metadata: &tracing::Metadata) -> bool {
+			true
+		}
+		fn new_span(&self, attrs: &span::Attributes) -> span::Id {
+			assert_eq!(attrs.metadata().name(), "build");
+
+			let mut field = BuilderField(String::new());
+			attrs.record(&mut field);
+
+			let mut spans = self.spans.lock().unwrap();
+			let parent = self.stack.lock().unwrap().last()
+				.map(|id| spans[id.into_u64() as usize - 1].clone());
+			self.built.lock().unwrap().push((field.0.clone(), parent));
+			spans.push(field.0);
+
+			span::Id::from_u64(spans.len() as u64)
+		}
+		fn record(&self, _span: &span::Id, _values: &span::Record) {}
+		fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+		fn event(&self, _event: &tracing::Event) {}
+		fn enter(&self, span: &span::Id) {
+			self.stack.lock().unwrap().push(span.clone());
+		}
+		fn exit(&self, _span: &span::Id) {
+			self.stack.lock().unwrap().pop();
+		}
+	}
+
+	let subscriber = TestSubscriber::default();
+	let built = subscriber.built.clone();
+
+	tracing::subscriber::with_default(subscriber, || {
+		let mut cache = rc::Cache::new();
+
+		let leaf = Blueprint::new(BuilderLeaf::new());
+		let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+		cache.get(&node).unpack();
+		cache.get(&node).unpack();
+	});
+
+	let built = built.lock().unwrap();
+
+	// The leaf is built within the build of the node
+	assert_eq!(built.len(), 2);
+	assert!(built[0].0.contains("BuilderSimpleNode"));
+	assert_eq!(built[0].1, None);
+	assert!(built[1].0.ends_with("BuilderLeaf"));
+	assert_eq!(built[1].1.as_ref(), Some(&built[0].0));
+}
+
+#[test]
+fn test_get_or() {
+	let mut cache = rc::Cache::new();
+
+	let primary = Blueprint::new(BuilderLeafFallible::new());
+	let secondary = Blueprint::new(BuilderLeaf::new());
+
+	let art = cache.get_or(&primary, &secondary).unpack();
+	assert_eq!(art, cache.get(&primary).unwrap());
+	assert!(!cache.contains_artifact_id(secondary.id()));
+
+	// Let the primary fail
+	*cache.dyn_state_mut(&primary) = false;
+
+	let art = cache.get_or(&primary, &secondary).unpack();
+	assert_eq!(art, cache.get(&secondary).unpack());
+	assert!(!cache.contains_artifact_id(primary.id()));
+}
+
+#[test]
+fn test_assert_consistent() {
+	let mut cache = rc::Cache::new();
+	cache.assert_consistent();
+
+	let leaf1 = Blueprint::new(BuilderLeaf::new());
+	let leaf2 = Blueprint::new(BuilderLeaf::new());
+	let node1 = Blueprint::new(BuilderSimpleNode::new(leaf1.clone()));
+	let node2 = Blueprint::new(BuilderSimpleNode::new(leaf2.clone()));
+	let node3 = Blueprint::new(BuilderSimpleNode::new(leaf2.clone()));
+
+	cache.get(&node1).unpack();
+	cache.get(&node2).unpack();
+	cache.get(&node3).unpack();
+	cache.assert_consistent();
+
+	cache.invalidate(&leaf2);
+	cache.assert_consistent();
+
+	cache.get(&node2).unpack();
+	cache.invalidate_to_depth(&leaf1, 0);
+	cache.assert_consistent();
+
+	cache.purge(&node2);
+	cache.assert_consistent();
+
+	drop(node3);
+	cache.garbage_collection();
+	cache.assert_consistent();
+
+	cache.clear_all();
+	cache.assert_consistent();
+}
+
+#[test]
+fn test_artifact_ptr() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	assert_eq!(cache.artifact_ptr(&leaf), None);
+
+	let node_art = cache.get(&node).unpack();
+
+	assert_eq!(cache.artifact_ptr(&leaf), Some(&*node_art.leaf as *const Leaf as *const ()));
+	assert_eq!(cache.artifact_ptr(&node), Some(&*node_art as *const SimpleNode<rc::BinType<Leaf>> as *const ()));
+}
+
+// Resolves generically via a Cache or a Resolver
+fn resolve_leaf_id<R: crate::cache::ResolverLike<rc::CanType, rc::BuilderCan>>(
+		resolver: &mut R,
+		leaf: &rc::Blueprint<BuilderLeaf>,
+	) -> u32 {
+
+	resolver.resolve_ref(leaf).unpack().id
+}
+
+fn resolve_leaf_cloned<R: crate::cache::ResolverLike<rc::CanType, rc::BuilderCan>>(
+		resolver: &mut R,
+		leaf: &rc::Blueprint<BuilderLeaf>,
+	) -> Leaf {
+
+	resolver.resolve_cloned(leaf).unpack()
+}
+
+#[test]
+fn test_resolver_like() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderLeaf::new());
+
+	let id = resolve_leaf_id(&mut cache, &leaf);
+
+	cache.get_overriding(&node, |resolver| {
+		assert_eq!(resolve_leaf_id(resolver, &leaf), id);
+
+		Ok(resolve_leaf_cloned(resolver, &leaf))
+	}).unpack();
+
+	// The dependency is tracked by the Resolver
+	cache.invalidate(&leaf);
+	assert!(!cache.contains_artifact_id(node.id()));
+}
+
+#[test]
+fn test_builder_type_name() {
+	let leaf: rc::Blueprint<_> = Blueprint::new(BuilderLeaf::new());
+
+	assert_eq!(leaf.builder_type_name(), std::any::type_name::<BuilderLeaf>());
+
+	let dyn_leaf: rc::DynamicBlueprint<Leaf> = leaf.into();
+	assert!(dyn_leaf.builder_type_name().starts_with("dyn "));
+}
+
+#[test]
+fn test_get_erased() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let fallible = Blueprint::new(BuilderLeafFallible::new());
+	let config = rc::Blueprint::new(rc::ConfigurableBuilder::new(42_u32));
+
+	*cache.dyn_state_mut(&fallible) = false;
+
+	let promises: Vec<Box<dyn crate::cache::ErasedPromise<_, _>>> = vec![
+		Box::new(leaf.clone()),
+		Box::new(fallible),
+		Box::new(config),
+	];
+
+	let results: Vec<_> = promises.iter()
+		.map(|ap| cache.get_erased(ap.as_ref()))
+		.collect();
+
+	assert_eq!(results[0].as_ref().unwrap().downcast_ref::<Leaf>(), Some(&cache.get_cloned(&leaf).unpack()));
+	assert_eq!(results[1].as_ref().unwrap_err().downcast_ref::<()>(), Some(&()));
+	assert_eq!(results[2].as_ref().unwrap().downcast_ref::<u32>(), Some(&42));
+}
+
+#[test]
+fn test_warm() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+	let fallible = Blueprint::new(BuilderLeafFallible::new());
+
+	*cache.dyn_state_mut(&fallible) = false;
+
+	let roots: Vec<Box<dyn crate::cache::ErasedPromise<_, _>>> = vec![
+		Box::new(node.clone()),
+		Box::new(fallible.clone()),
+	];
+
+	assert_eq!(cache.warm(&roots), vec![Ok(()), Err(())]);
+
+	assert!(cache.contains_artifact_id(node.id()));
+	assert!(cache.contains_artifact_id(leaf.id()));
+	assert!(!cache.contains_artifact_id(fallible.id()));
+}
+
+#[test]
+fn test_bounded_redeeming() {
+	use crate::utils::RedeemingBuilder;
+
+	let mut cache = rc::Cache::new();
+
+	let fallible = Blueprint::new(BuilderLeafFallible::new());
+	let bounded: rc::Blueprint<_> = Blueprint::new(
+		RedeemingBuilder::with_max_stale::<rc::CanType, _, _, _>(fallible.clone(), None, 1)
+	);
+
+	let art = cache.get(&bounded).unwrap();
+
+	// Serve the stale value once
+	*cache.dyn_state_mut(&fallible) = false;
+	assert_eq!(cache.get(&bounded), Ok(art.clone()));
+
+	// Then fail
+	cache.invalidate(&bounded);
+	assert_eq!(cache.get(&bounded), Err(()));
+
+	// Until the inner builder recovers
+	*cache.dyn_state_mut(&fallible) = true;
+	let fresh = cache.get(&bounded).unwrap();
+	assert_ne!(fresh, art);
+	assert_eq!(cache.dyn_state(&bounded).1, 0);
+}
+
+#[derive(Debug, Hash, PartialEq, Eq)]
+struct BuilderValue {
+	value: u32,
+}
+
+impl rc::Builder for BuilderValue {
+	type Artifact = u32;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, _resolver: &mut rc::Resolver) -> Result<std::rc::Rc<u32>, Never> {
+		Ok(std::rc::Rc::new(self.value))
+	}
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+#[test]
+fn test_intern() {
+	let mut cache = rc::Cache::new();
+
+	let bp1 = cache.intern(BuilderValue { value: 1 });
+	let bp2 = cache.intern(BuilderValue { value: 1 });
+	let bp3 = cache.intern(BuilderValue { value: 2 });
+
+	assert_eq!(bp1, bp2);
+	assert_ne!(bp1, bp3);
+	assert!(std::rc::Rc::ptr_eq(&cache.get(&bp1).unpack(), &cache.get(&bp2).unpack()));
+
+	cache.clear_interned();
+	assert_ne!(bp1, cache.intern(BuilderValue { value: 1 }));
+}
+
+#[test]
+fn test_resolver_for() {
+	let mut cache = rc::Cache::new();
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderLeaf::new());
+
+	let mut resolver = cache.resolver_for(&node);
+	let art = resolver.resolve(&leaf).unpack();
+	resolver.finish(&node, art.clone());
+
+	assert_eq!(cache.lookup(&node), Some(art));
+	assert_eq!(cache.stats().builds, 2);
+
+	// The dependency is tracked
+	cache.invalidate(&leaf);
+	assert!(!cache.contains_artifact_id(node.id()));
+
+	// The manual build is accounted like a regular one
+	cache.watch(&node);
+	let mut resolver = cache.resolver_for(&node);
+	let art = resolver.resolve(&leaf).unpack();
+	resolver.finish(&node, art);
+
+	assert_eq!(cache.stats().builds, 4);
+	assert!(cache.rebuild_dirty().iter().all(|(bid, _)| *bid != node.id()));
+	#[cfg(feature = "artifact_version")]
+	assert_eq!(cache.artifact_version(&node), 2);
+	cache.assert_consistent();
+}
+
+#[test]
+#[cfg(feature = "diagnostics")]
+fn test_can_type_name() {
+	use crate::diagnostics::ArtifactHandle;
+	use crate::diagnostics::BuilderHandle;
+
+	let leaf: rc::Blueprint<_> = Blueprint::new(BuilderLeaf::new());
+
+	let builder = BuilderHandle::new(&leaf);
+	assert_eq!(builder.can_type_name, std::any::type_name::<rc::BuilderCan>());
+
+	let artifact = ArtifactHandle::<arc::CanType>::new(std::sync::Arc::new(42_u32));
+	assert_eq!(artifact.can_type_name, std::any::type_name::<arc::CanType>());
+}
+
+#[test]
+#[cfg(feature = "diagnostics")]
+fn test_artifact_builder_id() {
+	use crate::diagnostics::ArtifactHandle;
+	use crate::diagnostics::BuilderHandle;
+	use crate::diagnostics::Doctor;
+
+	#[derive(Debug, Default)]
+	struct IdDoctor {
+		built: Vec<(BuilderId, Option<BuilderId>)>,
+	}
+
+	impl<ArtCan, BCan> Doctor<ArtCan, BCan> for IdDoctor {
+		fn build(&mut self, builder: &BuilderHandle<BCan>, artifact: &ArtifactHandle<ArtCan>) {
+			self.built.push((builder.id(), artifact.builder_id()));
+		}
+	}
+
+	let mut cache = rc::Cache::new_with_doctor(IdDoctor::default());
+
+	let leaf = Blueprint::new(BuilderLeaf::new());
+	let node = Blueprint::new(BuilderSimpleNode::new(leaf.clone()));
+
+	cache.get(&node).unpack();
+
+	assert_eq!(cache.doctor().built, vec![
+		(leaf.id(), Some(leaf.id())),
+		(node.id(), Some(node.id())),
+	]);
+}
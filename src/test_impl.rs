@@ -292,6 +292,7 @@ fn visgraph_doc(buf: Vec<u8>) -> diagnostics::VisgraphDoc<std::io::Cursor<Vec<u8
 		diagnostics::VisgraphDocOptions {
 			show_builder_values: false,
 			show_artifact_values: true,
+			root_filter: None,
 		},
 		std::io::Cursor::new(buf),
 	)
@@ -373,6 +374,41 @@ fn test_vis_doc() {
 	assert!(regex.is_match(&string));
 }
 
+#[test]
+#[cfg(feature = "diagnostics")]
+fn test_vis_doc_root_filter() {
+	// Test data
+	let leaf1 = Blueprint::new(BuilderLeaf::new());
+	let leaf2 = Blueprint::new(BuilderLeaf::new());
+
+	let node1 = Blueprint::new(BuilderSimpleNode::new(leaf1.clone()));
+	let node2 = Blueprint::new(BuilderSimpleNode::new(leaf2.clone()));
+
+	let mut cache = Cache::new_with_doctor(
+		diagnostics::VisgraphDoc::new(
+			diagnostics::VisgraphDocOptions {
+				show_builder_values: false,
+				show_artifact_values: true,
+				root_filter: Some(node1.id()),
+			},
+			std::io::Cursor::new(Vec::new()),
+		)
+	);
+
+	cache.get(&node1).unpack();
+	cache.get(&node2).unpack();
+
+	let data = cache.into_doctor().into_inner().into_inner();
+	let string = String::from_utf8(data).unwrap();
+	println!("{}", string);
+
+	assert!(string.contains(&format!("{:p}", node1.id())));
+	assert!(string.contains(&format!("{:p}", leaf1.id())));
+	assert!(!string.contains(&format!("{:p}", node2.id())));
+	assert!(!string.contains(&format!("{:p}", leaf2.id())));
+	assert!(string.ends_with("}\n"));
+}
+
 #[allow(dead_code)]
 const TEXT_DOC_PATTERN_STD: &str = r"resolves daab::.+::BuilderSimpleNode -> daab::.+::BuilderLeaf
 built #0.0  daab::.+::BuilderLeaf => daab::.+::Leaf
@@ -410,6 +446,7 @@ fn test_text_doc() {
 				show_artifact_values: false,
 				show_addresses: false,
 				tynm_m_n: Some((0,0)),
+				filter: None,
 			},
 			data
 		)
@@ -460,6 +497,7 @@ fn test_text_doc_long() {
 				// TODO use when newer version in avaiable
 				//tynm_m_n: Some((std::usize::MAX,std::usize::MAX)),
 				tynm_m_n: Some((100,100)),
+				filter: None,
 			},
 			data
 		)
@@ -491,6 +529,93 @@ fn test_text_doc_long() {
 	assert!(regex.is_match(&string));
 }
 
+#[allow(dead_code)]
+const TEXT_DOC_PATTERN_FILTER: &str = r"^built #0.0  daab::.+::BuilderLeaf => daab::.+::Leaf
+$";
+
+#[test]
+#[cfg(feature = "diagnostics")]
+fn test_text_doc_filter() {
+
+	// Expected value as Regular Expression due to variable addresses and counters
+	let regex = regex::Regex::new(TEXT_DOC_PATTERN_FILTER).unwrap();
+
+	// Textual output storage
+	let mut data = Vec::new();
+
+	let mut cache = Cache::new_with_doctor(
+		diagnostics::TextualDoc::new(
+			diagnostics::TextualDocOptions {
+				show_builder_values: false,
+				show_artifact_values: false,
+				show_addresses: false,
+				tynm_m_n: None,
+				filter: Some(|ty| ty.ends_with("::BuilderLeaf")),
+			},
+			data
+		)
+	);
+
+
+	// Test data
+	let leaf1 = Blueprint::new(BuilderLeaf::new());
+
+	let node1 = Blueprint::new(BuilderSimpleNode::new(leaf1.clone()));
+	let node2 = Blueprint::new(BuilderSimpleNode::new(leaf1.clone()));
+
+	cache.get(&node1).unpack();
+	cache.get(&node2).unpack();
+
+	// Get the vector back, dissolves cache & doctor
+	data = cache.into_doctor().into_inner();
+
+	let string = String::from_utf8(data).unwrap();
+	// Print the resulting string, very usable in case it does not match
+	println!("{}", string);
+
+	assert!(regex.is_match(&string));
+}
+
+#[test]
+#[cfg(feature = "diagnostics")]
+fn test_text_doc_shared() {
+
+	// Expected value as Regular Expression due to variable addresses and counters
+	let regex = regex::Regex::new(TEXT_DOC_PATTERN_FILTER).unwrap();
+
+	// Shared textual output storage
+	let data = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+
+	let mut cache = Cache::new_with_doctor(
+		diagnostics::TextualDoc::new_shared(
+			diagnostics::TextualDocOptions {
+				show_builder_values: false,
+				show_artifact_values: false,
+				show_addresses: false,
+				tynm_m_n: None,
+				filter: Some(|ty| ty.ends_with("::BuilderLeaf")),
+			},
+			data.clone()
+		)
+	);
+
+	let leaf1 = Blueprint::new(BuilderLeaf::new());
+	let node1 = Blueprint::new(BuilderSimpleNode::new(leaf1.clone()));
+
+	cache.get(&node1).unpack();
+
+	// Inspect the output while the cache is still alive
+	let string = String::from_utf8(data.borrow().clone()).unwrap();
+	println!("{}", string);
+
+	assert!(regex.is_match(&string));
+
+	// The output continues afterwards
+	cache.invalidate(&leaf1);
+	cache.get(&node1).unpack();
+	assert!(data.borrow().len() > string.len());
+}
+
 #[test]
 fn test_complex_clear() {
 	let mut cache = Cache::new();
@@ -292,6 +292,7 @@ fn visgraph_doc(buf: Vec<u8>) -> diagnostics::VisgraphDoc<std::io::Cursor<Vec<u8
 		diagnostics::VisgraphDocOptions {
 			show_builder_values: false,
 			show_artifact_values: true,
+			root_filter: None,
 		},
 		std::io::Cursor::new(buf),
 	)
@@ -407,6 +408,7 @@ fn test_text_doc() {
 				show_artifact_values: false,
 				show_addresses: false,
 				tynm_m_n: Some((0,0)),
+				filter: None,
 			},
 			data
 		)
@@ -457,6 +459,7 @@ fn test_text_doc_long() {
 				// TODO use when newer version in avaiable
 				//tynm_m_n: Some((std::usize::MAX,std::usize::MAX)),
 				tynm_m_n: Some((100,100)),
+				filter: None,
 			},
 			data
 		)
@@ -5,6 +5,7 @@
 //!
 
 use crate::Resolver;
+use crate::Cache;
 use crate::Promise;
 use crate::Blueprint;
 use crate::Builder;
@@ -12,11 +13,80 @@ use crate::CanRef;
 use crate::CanStrong;
 use crate::CanSized;
 use crate::Can;
+use crate::BuilderId;
+use crate::cache::DeclaredDependency;
 use crate::Never;
+use crate::OnEvict;
 
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::fmt;
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::time::Instant;
+
+
+
+/// Eviction hook calling `OnEvict::on_evict` of the Artifact.
+///
+/// This function is intended to be returned from `Builder::evict_hook`.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+/// use daab::*;
+///
+/// #[derive(Debug)]
+/// struct Buffer {
+///     flushed: Rc<Cell<bool>>,
+/// }
+/// impl OnEvict for Buffer {
+///     fn on_evict(&self) {
+///         self.flushed.set(true);
+///     }
+/// }
+///
+/// #[derive(Debug)]
+/// struct BufferBuilder {
+///     flushed: Rc<Cell<bool>>,
+/// }
+/// impl rc::Builder for BufferBuilder {
+///     type Artifact = Buffer;
+///     type DynState = ();
+///     type Err = Never;
+///
+///     fn build(&self, _resolver: &mut rc::Resolver) -> Result<Rc<Buffer>, Never> {
+///         Ok(Rc::new(Buffer { flushed: self.flushed.clone() }))
+///     }
+///     fn init_dyn_state(&self) -> Self::DynState {}
+///     fn evict_hook(&self) -> Option<fn(&rc::CanType)> {
+///         Some(utils::on_evict_hook::<_, Buffer>)
+///     }
+/// }
+///
+/// let flushed = Rc::new(Cell::new(false));
+/// let blueprint = rc::Blueprint::new(BufferBuilder { flushed: flushed.clone() });
+///
+/// let mut cache = rc::Cache::new();
+/// cache.get(&blueprint).unpack();
+/// assert!(!flushed.get());
+///
+/// cache.invalidate(&blueprint);
+/// assert!(flushed.get());
+/// ```
+///
+pub fn on_evict_hook<ArtCan, T>(can: &ArtCan)
+		where
+			ArtCan: CanRef<T>,
+			T: OnEvict {
+
+	if let Some(art) = can.downcast_can_ref() {
+		art.on_evict();
+	}
+}
 
 
 
@@ -78,6 +148,29 @@ impl<AP, ArtBin> RedeemingBuilder<AP, ArtBin> {
 			default_value,
 		}
 	}
+
+	/// Wrap given Builder and fill missing recreations with a previous value,
+	/// but at most `max_failures` times in a row.
+	///
+	/// See `BoundedRedeemingBuilder`.
+	///
+	pub fn with_max_stale<ArtCan, BCan, B: ?Sized, T>(
+		inner: AP,
+		default_value: Option<ArtBin>,
+		max_failures: usize,
+	) -> BoundedRedeemingBuilder<AP, ArtBin>
+		where
+			B: Builder<ArtCan, BCan, Artifact=T>,
+			BCan: Can<AP::Builder>,
+			AP: Promise<Builder = B, BCan = BCan>,
+			T: Debug + 'static,
+			ArtCan: Clone + CanSized<T,Bin=ArtBin>,
+			ArtBin: Clone + Debug + 'static,
+			BCan: Clone + CanStrong,
+	{
+
+		BoundedRedeemingBuilder::new(inner, default_value, max_failures)
+	}
 }
 
 impl<ArtCan, AP, B: ?Sized, BCan, ArtBin, T> Builder<ArtCan, BCan> for RedeemingBuilder<AP, ArtBin>
@@ -121,6 +214,111 @@ impl<ArtCan, AP, B: ?Sized, BCan, ArtBin, T> Builder<ArtCan, BCan> for Redeeming
 
 
 
+/// A intermediate cached Builder to circumvent failing builders for a
+/// limited time.
+///
+/// Like the `RedeemingBuilder`, this wrapper builder will cache the result
+/// of its inner builder and return the cached value instead, if the inner
+/// builder failed to produce a new artifact. However, this is done at most
+/// `max_failures` times in a row, thereafter the error of the inner builder
+/// is returned, until the inner builder succeeds again. This bounds how long
+/// an outdated artifact is served. Also if there is no cached value at all,
+/// the error is returned instead of panicking.
+///
+/// Every build of this builder with a failing inner builder counts as
+/// failure, including repeated builds of this builder after it returned an
+/// error.
+///
+/// **Notice: It is likely a logical error to keep an artifact despite
+/// been invalidate, which is what this wrapper dose!** See `RedeemingBuilder`
+/// for details.
+///
+#[derive(Debug, Clone)]
+pub struct BoundedRedeemingBuilder<AP, ArtBin> {
+	inner: AP,
+	default_value: Option<ArtBin>,
+	max_failures: usize,
+}
+
+impl<AP, ArtBin> BoundedRedeemingBuilder<AP, ArtBin> {
+
+	/// Wrap given Builder and fill missing recreations with a previous value,
+	/// but at most `max_failures` times in a row.
+	///
+	/// **Use with care**
+	///
+	pub fn new<ArtCan, BCan, B: ?Sized, T>(
+		inner: AP,
+		default_value: Option<ArtBin>,
+		max_failures: usize,
+	) -> Self
+		where
+			B: Builder<ArtCan, BCan, Artifact=T>,
+			BCan: Can<AP::Builder>,
+			AP: Promise<Builder = B, BCan = BCan>,
+			T: Debug + 'static,
+			ArtCan: Clone + CanSized<T,Bin=ArtBin>,
+			ArtBin: Clone + Debug + 'static,
+			BCan: Clone + CanStrong,
+	{
+
+		BoundedRedeemingBuilder {
+			inner,
+			default_value,
+			max_failures,
+		}
+	}
+}
+
+impl<ArtCan, AP, B: ?Sized, BCan, ArtBin, T> Builder<ArtCan, BCan> for BoundedRedeemingBuilder<AP, ArtBin>
+	where
+		B: Builder<ArtCan, BCan, Artifact=T>,
+		BCan: Can<B>,
+		AP: Promise<Builder = B, BCan = BCan>,
+		T: Debug + 'static,
+		ArtCan: Clone + CanSized<T,Bin=ArtBin>,
+		ArtBin: Clone + Debug + 'static,
+		BCan: Clone + CanStrong,
+	{
+
+	type Artifact = T;
+	/// The cached value and the number of consecutive failures.
+	type DynState = (Option<ArtCan::Bin>, usize);
+	type Err = B::Err;
+
+	fn build(&self, resolver: &mut Resolver<ArtCan, BCan, Self::DynState>)
+			-> Result<ArtCan::Bin, B::Err> {
+
+		let value = resolver.resolve(&self.inner);
+		let max_failures = self.max_failures;
+		let (cached, failures) = resolver.my_state();
+
+		match value {
+			Ok(v) => {
+				*cached = Some(v.clone());
+				*failures = 0;
+
+				Ok(v)
+			}
+			Err(e) => {
+				*failures += 1;
+
+				// Serve the cached value, unless it got too stale
+				match cached {
+					Some(v) if *failures <= max_failures => Ok(v.clone()),
+					_ => Err(e),
+				}
+			}
+		}
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		(self.default_value.clone(), 0)
+	}
+}
+
+
+
 
 /// Functional leaf builder wrapper.
 ///
@@ -311,6 +509,106 @@ impl<ArtCan, BCan, F, E, T, S> Builder<ArtCan, BCan> for FunctionalBuilder<ArtCa
 
 
 
+/// A Builder caching the `Result` of a fallible closure as Artifact.
+///
+/// As opposed to the [`FunctionalBuilder`], whose closure failing is a
+/// build failure, which is not cached and thus retried on the next access,
+/// this Builder is infallible and caches the entire `Result` as its
+/// Artifact. So even the error arm is kept until this Builder is
+/// invalidated. This is appropriate, if the error is a legitimate outcome
+/// rather than a failure to produce an Artifact, e.g. a failed validation.
+///
+/// [`FunctionalBuilder`]: struct.FunctionalBuilder.html
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+/// use daab::utils::ResultBuilder;
+/// use daab::rc::Cache;
+/// use daab::rc::Blueprint;
+/// use daab::prelude::*;
+///
+/// let calls = Rc::new(Cell::new(0));
+/// let counter = calls.clone();
+/// let builder = ResultBuilder::new(move || {
+///     counter.set(counter.get() + 1);
+///     "x".parse::<u32>()
+/// });
+/// let blueprint = Blueprint::new(builder);
+///
+/// let mut cache = Cache::new();
+///
+/// assert!(cache.get(&blueprint).unpack().is_err());
+/// assert!(cache.get(&blueprint).unpack().is_err());
+///
+/// // The error has been cached
+/// assert_eq!(calls.get(), 1);
+/// ```
+///
+pub struct ResultBuilder<ArtCan, BCan, F, T, E> {
+	inner: F,
+	_art_can: PhantomData<ArtCan>,
+	_b_can: PhantomData<BCan>,
+	_t: PhantomData<T>,
+	_e: PhantomData<E>,
+}
+
+impl<ArtCan, BCan, F, T, E> Debug for ResultBuilder<ArtCan, BCan, F, T, E> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "ResultBuilder{{...}}")
+	}
+}
+
+impl<ArtCan, BCan, F, T, E> ResultBuilder<ArtCan, BCan, F, T, E>
+	where
+		F: Fn() -> Result<T, E> + 'static,
+		T: Debug + 'static,
+		E: Debug + 'static,
+		BCan: CanStrong,
+		ArtCan: CanSized<Result<T, E>>,
+		ArtCan: Debug + 'static {
+
+	/// Wraps the given fallible closure as Builder.
+	///
+	pub fn new(f: F) -> Self {
+		ResultBuilder {
+			inner: f,
+			_art_can: PhantomData,
+			_b_can: PhantomData,
+			_t: PhantomData,
+			_e: PhantomData,
+		}
+	}
+}
+
+impl<ArtCan, BCan, F, T, E> Builder<ArtCan, BCan> for ResultBuilder<ArtCan, BCan, F, T, E>
+	where
+		F: Fn() -> Result<T, E> + 'static,
+		T: Debug + 'static,
+		E: Debug + 'static,
+		BCan: CanStrong,
+		ArtCan: CanSized<Result<T, E>>,
+		ArtCan: Debug + 'static {
+
+	type Artifact = Result<T, E>;
+	type DynState = ();
+	type Err = Never;
+
+	fn build(&self, _resolver: &mut Resolver<ArtCan, BCan, Self::DynState>)
+			 -> Result<ArtCan::Bin, Self::Err> {
+
+		Ok(ArtCan::into_bin((self.inner)()))
+	}
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+
+
+
 /// A static builder.
 ///
 /// A builder which always builds a predetermined value as artifact.
@@ -439,6 +737,8 @@ impl<ArtCan, BCan, ArtBin, T> Builder<ArtCan, BCan> for ConstBuilder<ArtCan, BCa
 /// assert_eq!(42_u32, cache.get_cloned(&blueprint).unpack());
 /// # cache.invalidate(&blueprint);
 /// # assert_eq!(42_u32, cache.get_cloned(&blueprint).unpack());
+/// ConfigurableBuilder::reset(&mut cache, &blueprint);
+/// assert_eq!(0_u32, cache.get_cloned(&blueprint).unpack());
 /// ```
 ///
 pub struct ConfigurableBuilder<ArtCan, BCan, T> {
@@ -468,6 +768,26 @@ impl<ArtCan, BCan, T> ConfigurableBuilder<ArtCan, BCan, T>
 			_b_can: PhantomData,
 		}
 	}
+
+	/// Returns the initial value, i.e. the dyn state before any change.
+	///
+	pub fn initial(&self) -> &T {
+		&self.initial
+	}
+
+	/// Resets the dyn state of the given promise back to its initial value.
+	///
+	/// Like `Cache::dyn_state_mut`, this invalidates the Artifact of the
+	/// given promise, including all depending Artifacts.
+	///
+	pub fn reset<AP>(cache: &mut Cache<ArtCan, BCan>, promise: &AP)
+			where
+				ArtCan: CanSized<T>,
+				BCan: Can<Self>,
+				AP: Promise<Builder = Self, BCan = BCan> {
+
+		*cache.dyn_state_mut(promise) = promise.builder().builder.initial.clone();
+	}
 }
 
 impl<ArtCan, BCan, T> From<T> for Blueprint<ConfigurableBuilder<ArtCan, BCan, T>, BCan>
@@ -646,66 +966,781 @@ impl<ArtCan, AP, B: ?Sized, BCan> Builder<ArtCan, BCan> for ForwardingBuilder<AP
 
 
 
-/// A intermediate Builder which wraps a builder with `Err=Never` with a arbitrary error type.
+/// A intermediate Builder which records the time of its recent builds.
+///
+/// This wrapper builder forwards the artifact of its inner builder, just like
+/// the `ForwardingBuilder`, but additionally keeps the points in time of its
+/// last `capacity` builds in its dyn state (the oldest first). Thus it
+/// allows to watch how often a single node of the graph is rebuilt, without
+/// enabling the full **`diagnostics`** feature.
+///
+/// The log can be inspected via the dyn state accessors of the `Cache`,
+/// e.g. `get_dyn_state`.
+///
+/// Notice, that each invocation of the `build` method is recorded, even if
+/// the inner builder fails.
+///
+/// # Examples
+///
+/// ```
+/// use daab::utils::ConstBuilder;
+/// use daab::utils::LoggingBuilder;
+/// use daab::rc::Cache;
+/// use daab::rc::Blueprint;
+/// use daab::prelude::*;
+///
+/// let inner = Blueprint::new(ConstBuilder::new(std::rc::Rc::new(42_u32)));
+/// let blueprint = Blueprint::new(LoggingBuilder::new(inner, 2));
+///
+/// let mut cache = Cache::new();
+///
+/// assert_eq!(42_u32, cache.get_cloned(&blueprint).unpack());
+/// cache.invalidate(&blueprint);
+/// assert_eq!(42_u32, cache.get_cloned(&blueprint).unpack());
+/// cache.invalidate(&blueprint);
+/// assert_eq!(42_u32, cache.get_cloned(&blueprint).unpack());
+///
+/// // Only the last two builds are kept
+/// assert_eq!(2, cache.get_dyn_state(&blueprint).unwrap().len());
+/// ```
 ///
 #[derive(Debug, Clone)]
-pub struct FeigningBuilder<AP, Err> {
+pub struct LoggingBuilder<AP> {
 	inner: AP,
-	_err: PhantomData<Err>,
+	capacity: usize,
 }
 
-impl<AP, Err> FeigningBuilder<AP, Err> {
+impl<AP> LoggingBuilder<AP> {
 
-	/// Wrap given Builder forwarding its artifact.
+	/// Wrap given Builder logging up to `capacity` build times.
 	///
-	pub fn new<ArtCan, BCan, B>(
+	pub fn new<ArtCan, BCan, B: ?Sized>(
 		inner: AP,
+		capacity: usize,
 	) -> Self
 		where
-			B: Builder<ArtCan, BCan, Err=Never>,
+			B: Builder<ArtCan, BCan>,
 			BCan: Can<AP::Builder>,
 			AP: Promise<Builder = B, BCan = BCan>,
-			Err: Debug + 'static,
 			ArtCan: CanSized<B::Artifact>,
 			ArtCan: Clone,
 			BCan: CanStrong,
 			BCan: CanSized<Self>,
 	{
 
-		FeigningBuilder {
+		LoggingBuilder {
 			inner,
-			_err: PhantomData,
+			capacity,
 		}
 	}
 }
 
-impl<ArtCan, AP, B: ?Sized, BCan, Err> Builder<ArtCan, BCan> for FeigningBuilder<AP, Err>
+impl<ArtCan, AP, B: ?Sized, BCan> Builder<ArtCan, BCan> for LoggingBuilder<AP>
 	where
-		B: Builder<ArtCan, BCan, Err=Never>,
+		B: Builder<ArtCan, BCan>,
 		BCan: Can<B>,
 		AP: Promise<Builder = B, BCan = BCan>,
-		Err: Debug + 'static,
 		ArtCan: CanSized<B::Artifact>,
 		ArtCan: Clone,
 		BCan: CanStrong,
 	{
 
 	type Artifact = B::Artifact;
-	type DynState = ();
-	type Err = Err;
+	type DynState = VecDeque<Instant>;
+	type Err = B::Err;
 
 	fn build(&self, resolver: &mut Resolver<ArtCan, BCan, Self::DynState>)
 			-> Result<ArtCan::Bin, Self::Err> {
 
-		resolver.resolve(&self.inner).map_err(|n| n.into_any())
+		let log = resolver.my_state();
+
+		// Drop the oldest entries to make room for the new one
+		while !log.is_empty() && log.len() >= self.capacity {
+			log.pop_front();
+		}
+		if self.capacity > 0 {
+			log.push_back(Instant::now());
+		}
+
+		resolver.resolve(&self.inner)
 	}
 
 	fn init_dyn_state(&self) -> Self::DynState {
-		// empty
+		VecDeque::with_capacity(self.capacity)
 	}
 }
 
 
 
+/// A intermediate Builder which selects one of several builders at runtime.
+///
+/// This wrapper builder holds a list of candidate builders, which all produce
+/// the same artifact type, and forwards the artifact of the one selected by
+/// the index in its dyn state, which initially is `0`. Changing the index via
+/// the `dyn_state_mut` method of the `Cache` invalidates this builder, thus
+/// the newly selected candidate is resolved on the next access. This allows
+/// to switch strategies at runtime (e.g. quality levels of a render) without
+/// rebuilding the blueprint graph.
+///
+/// An index beyond the last candidate selects the last candidate.
+///
+/// Notice, that all candidates have the same promise type `AP`, thus
+/// `SelectBuilder::new(vec![a, b])` requires `a` and `b` to wrap the same
+/// Builder type. To select among Builders of different types, which produce
+/// the same artifact type, the candidates have to be type erased, e.g. as
+/// `BlueprintDyn`s, see the second example below.
+///
+/// # Examples
+///
+/// ```
+/// use daab::utils::ConstBuilder;
+/// use daab::utils::SelectBuilder;
+/// use daab::rc::Cache;
+/// use daab::rc::Blueprint;
+/// use daab::prelude::*;
+///
+/// let low = Blueprint::new(ConstBuilder::new(std::rc::Rc::new(1_u32)));
+/// let high = Blueprint::new(ConstBuilder::new(std::rc::Rc::new(2_u32)));
+/// let blueprint = Blueprint::new(SelectBuilder::new(vec![low, high]));
+///
+/// let mut cache = Cache::new();
+///
+/// assert_eq!(1_u32, cache.get_cloned(&blueprint).unpack());
+///
+/// // Select the second candidate
+/// *cache.dyn_state_mut(&blueprint) = 1;
+/// assert_eq!(2_u32, cache.get_cloned(&blueprint).unpack());
+/// ```
+///
+/// Selecting among Builders of different types:
+///
+/// ```
+/// use daab::utils::ConstBuilder;
+/// use daab::utils::ForwardingBuilder;
+/// use daab::utils::SelectBuilder;
+/// use daab::rc::Cache;
+/// use daab::rc::Blueprint;
+/// use daab::rc::dyn_blueprint;
+/// use daab::prelude::*;
+///
+/// let low = Blueprint::new(ConstBuilder::new(std::rc::Rc::new(1_u32)));
+/// let blueprint = Blueprint::new(SelectBuilder::new(vec![
+///     dyn_blueprint(ConstBuilder::new(std::rc::Rc::new(2_u32))),
+///     dyn_blueprint(ForwardingBuilder::new(low)),
+/// ]));
+///
+/// let mut cache = Cache::new();
+///
+/// assert_eq!(2_u32, cache.get_cloned(&blueprint).unpack());
+///
+/// *cache.dyn_state_mut(&blueprint) = 1;
+/// assert_eq!(1_u32, cache.get_cloned(&blueprint).unpack());
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct SelectBuilder<AP> {
+	candidates: Vec<AP>,
+}
+
+impl<AP> SelectBuilder<AP> {
 
+	/// Wrap given Builders forwarding the artifact of the selected one.
+	///
+	/// # Panics
+	///
+	/// Panics if `candidates` is empty.
+	///
+	pub fn new<ArtCan, BCan, B: ?Sized>(
+		candidates: Vec<AP>,
+	) -> Self
+		where
+			B: Builder<ArtCan, BCan>,
+			BCan: Can<AP::Builder>,
+			AP: Promise<Builder = B, BCan = BCan>,
+			ArtCan: CanSized<B::Artifact>,
+			ArtCan: Clone,
+			BCan: CanStrong,
+			BCan: CanSized<Self>,
+	{
+
+		assert!(!candidates.is_empty(), "SelectBuilder without candidates");
+
+		SelectBuilder {
+			candidates,
+		}
+	}
+}
+
+impl<ArtCan, AP, B: ?Sized, BCan> Builder<ArtCan, BCan> for SelectBuilder<AP>
+	where
+		B: Builder<ArtCan, BCan>,
+		BCan: Can<B>,
+		AP: Promise<Builder = B, BCan = BCan>,
+		ArtCan: CanSized<B::Artifact>,
+		ArtCan: Clone,
+		BCan: CanStrong,
+	{
+
+	type Artifact = B::Artifact;
+	type DynState = usize;
+	type Err = B::Err;
+
+	fn build(&self, resolver: &mut Resolver<ArtCan, BCan, Self::DynState>)
+			-> Result<ArtCan::Bin, Self::Err> {
+
+		// Clamp to the last candidate, `new` ensures there is one
+		let index = (*resolver.my_state()).min(self.candidates.len() - 1);
+
+		resolver.resolve(&self.candidates[index])
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		0
+	}
+}
+
+
+
+/// A intermediate Builder which maps the artifact of its inner builder.
+///
+/// This wrapper builder applies the given function to a reference of the
+/// artifact of its inner builder and uses the result as its own artifact.
+/// Errors of the inner builder are passed through.
+///
+pub struct MappingBuilder<AP, F> {
+	inner: AP,
+	f: F,
+}
+
+impl<AP: Debug, F> Debug for MappingBuilder<AP, F> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "MappingBuilder{{inner: {:?}, ...}}", self.inner)
+	}
+}
+
+impl<AP, F> MappingBuilder<AP, F> {
+
+	/// Wrap given Builder mapping its artifact with `f`.
+	///
+	pub fn new<ArtCan, BCan, B: ?Sized, T>(
+		inner: AP,
+		f: F,
+	) -> Self
+		where
+			B: Builder<ArtCan, BCan>,
+			BCan: Can<AP::Builder>,
+			AP: Promise<Builder = B, BCan = BCan>,
+			F: Fn(&B::Artifact) -> T + 'static,
+			T: Debug + 'static,
+			ArtCan: CanRef<B::Artifact>,
+			ArtCan: CanSized<T>,
+			BCan: CanStrong,
+			BCan: CanSized<Self>,
+	{
+
+		MappingBuilder {
+			inner,
+			f,
+		}
+	}
+}
+
+impl<ArtCan, AP, B: ?Sized, BCan, F, T> Builder<ArtCan, BCan> for MappingBuilder<AP, F>
+	where
+		B: Builder<ArtCan, BCan>,
+		BCan: Can<B>,
+		AP: Promise<Builder = B, BCan = BCan>,
+		F: Fn(&B::Artifact) -> T + 'static,
+		T: Debug + 'static,
+		ArtCan: CanRef<B::Artifact>,
+		ArtCan: CanSized<T>,
+		BCan: CanStrong,
+	{
+
+	type Artifact = T;
+	type DynState = ();
+	type Err = B::Err;
+
+	fn build(&self, resolver: &mut Resolver<ArtCan, BCan, Self::DynState>)
+			-> Result<<ArtCan as Can<T>>::Bin, Self::Err> {
+
+		resolver.resolve_ref(&self.inner)
+			.map(|art| ArtCan::into_bin((self.f)(art)))
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+
+
+/// A intermediate Builder which applies a sequence of transformations.
+///
+/// This wrapper builder clones the artifact of its inner builder and applies
+/// the given in-place transformations to it, in the order they were added.
+/// It is a higher-level composition over the `MappingBuilder`, which allows
+/// to assemble a processing pipeline declaratively instead of nesting many
+/// mapping builders. Errors of the inner builder are passed through.
+///
+/// The dyn state holds a toggle for each step, which are all enabled
+/// initially. Disabling a step via `Cache::dyn_state_mut` skips it and
+/// reconfigures the artifact accordingly.
+///
+pub struct ChainBuilder<AP, T> {
+	inner: AP,
+	steps: Vec<ChainStep<T>>,
+}
+
+/// A single transformation of a `ChainBuilder`.
+///
+type ChainStep<T> = Box<dyn Fn(&mut T)>;
+
+impl<AP: Debug, T> Debug for ChainBuilder<AP, T> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "ChainBuilder{{inner: {:?}, steps: {}, ...}}",
+			self.inner, self.steps.len())
+	}
+}
+
+impl<AP, T> ChainBuilder<AP, T> {
+
+	/// Wrap given Builder, initially without any transformation.
+	///
+	pub fn new<ArtCan, BCan, B: ?Sized>(
+		inner: AP,
+	) -> Self
+		where
+			B: Builder<ArtCan, BCan, Artifact = T>,
+			BCan: Can<AP::Builder>,
+			AP: Promise<Builder = B, BCan = BCan>,
+			T: Clone + Debug + 'static,
+			ArtCan: CanRef<T>,
+			BCan: CanStrong,
+			BCan: CanSized<Self>,
+	{
+
+		ChainBuilder {
+			inner,
+			steps: Vec::new(),
+		}
+	}
+
+	/// Appends the given transformation as last step.
+	///
+	pub fn then<F>(mut self, f: F) -> Self
+			where
+				F: Fn(&mut T) + 'static {
+
+		self.steps.push(Box::new(f));
+		self
+	}
+
+	/// Returns the number of steps.
+	///
+	pub fn len(&self) -> usize {
+		self.steps.len()
+	}
+
+	/// Returns whether there is no step.
+	///
+	pub fn is_empty(&self) -> bool {
+		self.steps.is_empty()
+	}
+}
+
+impl<ArtCan, AP, B: ?Sized, BCan, T> Builder<ArtCan, BCan> for ChainBuilder<AP, T>
+	where
+		B: Builder<ArtCan, BCan, Artifact = T>,
+		BCan: Can<B>,
+		AP: Promise<Builder = B, BCan = BCan>,
+		T: Clone + Debug + 'static,
+		ArtCan: CanRef<T>,
+		ArtCan: CanSized<T>,
+		BCan: CanStrong,
+	{
+
+	type Artifact = T;
+	type DynState = Vec<bool>;
+	type Err = B::Err;
+
+	fn build(&self, resolver: &mut Resolver<ArtCan, BCan, Self::DynState>)
+			-> Result<<ArtCan as Can<T>>::Bin, Self::Err> {
+
+		let mut art = resolver.resolve_cloned(&self.inner)?;
+		let enabled = resolver.my_state();
+
+		for (step, on) in self.steps.iter().zip(enabled.iter()) {
+			if *on {
+				step(&mut art);
+			}
+		}
+
+		Ok(ArtCan::into_bin(art))
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		vec![true; self.steps.len()]
+	}
+}
+
+
+
+/// A intermediate Builder which materializes the iterator of its inner builder.
+///
+/// This wrapper builder expects the artifact of its inner builder to be an
+/// iterator, i.e. a lazy description of a potentially large collection. The
+/// iterator is cloned and fully consumed the first time this Builder is
+/// resolved, and the collected items are cached as `Vec`. Thus, the inner
+/// builder may produce its items lazily, while consumers access the cached
+/// materialized result. Errors of the inner builder are passed through.
+///
+pub struct LazyCollectBuilder<AP, I> {
+	inner: AP,
+	_i: PhantomData<fn() -> I>,
+}
+
+impl<AP: Debug, I> Debug for LazyCollectBuilder<AP, I> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "LazyCollectBuilder{{inner: {:?}}}", self.inner)
+	}
+}
+
+impl<AP, I> LazyCollectBuilder<AP, I> {
+
+	/// Wrap given Builder collecting the items of its iterator.
+	///
+	pub fn new<ArtCan, BCan, B: ?Sized>(
+		inner: AP,
+	) -> Self
+		where
+			B: Builder<ArtCan, BCan, Artifact = I>,
+			BCan: Can<AP::Builder>,
+			AP: Promise<Builder = B, BCan = BCan>,
+			I: Iterator + Clone + Debug + 'static,
+			ArtCan: CanRef<I>,
+			ArtCan: CanSized<Vec<I::Item>>,
+			BCan: CanStrong,
+			BCan: CanSized<Self>,
+	{
+
+		LazyCollectBuilder {
+			inner,
+			_i: PhantomData,
+		}
+	}
+}
+
+impl<ArtCan, AP, B: ?Sized, BCan, I> Builder<ArtCan, BCan> for LazyCollectBuilder<AP, I>
+	where
+		B: Builder<ArtCan, BCan, Artifact = I>,
+		BCan: Can<B>,
+		AP: Promise<Builder = B, BCan = BCan>,
+		I: Iterator + Clone + Debug + 'static,
+		I::Item: Debug + 'static,
+		ArtCan: CanRef<I>,
+		ArtCan: CanSized<Vec<I::Item>>,
+		BCan: CanStrong,
+	{
+
+	type Artifact = Vec<I::Item>;
+	type DynState = ();
+	type Err = B::Err;
+
+	fn build(&self, resolver: &mut Resolver<ArtCan, BCan, Self::DynState>)
+			-> Result<<ArtCan as Can<Vec<I::Item>>>::Bin, Self::Err> {
+
+		resolver.resolve_ref(&self.inner)
+			.map(|iter| ArtCan::into_bin(iter.clone().collect::<Vec<_>>()))
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+
+
+/// A Builder which constructs its inner Builder lazily.
+///
+/// This wrapper builder holds a function producing the actual Builder, which
+/// is called the first time the Builder is used, i.e. when the Artifact is
+/// first resolved at a `Cache`. Thereafter the inner Builder is kept and the
+/// function is dropped, thus the inner Builder is constructed exactly once.
+/// This allows to register many expensive Builders, e.g. in a large registry,
+/// while only paying for those that are actually resolved.
+///
+/// Notice that this builder uses interior mutability (a `RefCell`) to keep
+/// the inner Builder, thus it is not `Sync`.
+///
+/// Usually, this builder is created via `Blueprint::new_lazy`.
+///
+pub struct LazyBuilder<B, F> {
+	builder: RefCell<Option<B>>,
+	make: Cell<Option<F>>,
+}
+
+impl<B: Debug, F> Debug for LazyBuilder<B, F> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match &*self.builder.borrow() {
+			Some(builder) => write!(fmt, "LazyBuilder{{builder: {:?}}}", builder),
+			None => write!(fmt, "LazyBuilder{{...}}"),
+		}
+	}
+}
+
+impl<B, F: FnOnce() -> B> LazyBuilder<B, F> {
+
+	/// Wrap the given function constructing the actual Builder on first use.
+	///
+	pub fn new(make: F) -> Self {
+		LazyBuilder {
+			builder: RefCell::new(None),
+			make: Cell::new(Some(make)),
+		}
+	}
+
+	/// Returns whether the inner Builder has been constructed yet.
+	///
+	pub fn is_constructed(&self) -> bool {
+		self.builder.borrow().is_some()
+	}
+
+	/// Calls `f` with the inner Builder, constructing it if necessary.
+	///
+	fn with_builder<R>(&self, f: impl FnOnce(&B) -> R) -> R {
+		if !self.is_constructed() {
+			let make = self.make.take()
+				.expect("LazyBuilder constructor has been lost");
+
+			let builder = make();
+			*self.builder.borrow_mut() = Some(builder);
+		}
+
+		let builder = self.builder.borrow();
+		f(builder.as_ref().expect("LazyBuilder has just been constructed"))
+	}
+}
+
+impl<ArtCan, B, BCan, F> Builder<ArtCan, BCan> for LazyBuilder<B, F>
+	where
+		B: Builder<ArtCan, BCan>,
+		F: FnOnce() -> B + 'static,
+		BCan: CanStrong,
+	{
+
+	type Artifact = B::Artifact;
+	type DynState = B::DynState;
+	type Err = B::Err;
+
+	fn build(&self, resolver: &mut Resolver<ArtCan, BCan, Self::DynState>)
+			-> Result<<ArtCan as Can<Self::Artifact>>::Bin, Self::Err>
+			where
+				ArtCan: Can<Self::Artifact> {
+
+		self.with_builder(|builder| builder.build(resolver))
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		self.with_builder(|builder| builder.init_dyn_state())
+	}
+
+	fn cache_error(&self, err: &Self::Err) -> Option<Self::Err> {
+		self.with_builder(|builder| builder.cache_error(err))
+	}
+
+	fn evict_hook(&self) -> Option<fn(&ArtCan)> {
+		self.with_builder(|builder| builder.evict_hook())
+	}
+
+	fn weak_dependencies(&self, declare: &mut dyn FnMut(BuilderId)) {
+		self.with_builder(|builder| builder.weak_dependencies(declare))
+	}
+
+	#[cfg(feature = "lru")]
+	fn artifact_size_hint(&self, artifact: &Self::Artifact) -> usize {
+		self.with_builder(|builder| builder.artifact_size_hint(artifact))
+	}
+
+	fn declare_dependencies(&self) -> Vec<Box<dyn DeclaredDependency<ArtCan, BCan>>> {
+		self.with_builder(|builder| builder.declare_dependencies())
+	}
+}
+
+
+
+/// A intermediate Builder which wraps a builder with `Err=Never` with a arbitrary error type.
+///
+#[derive(Debug, Clone)]
+pub struct FeigningBuilder<AP, Err> {
+	inner: AP,
+	_err: PhantomData<Err>,
+}
+
+impl<AP, Err> FeigningBuilder<AP, Err> {
+
+	/// Wrap given Builder forwarding its artifact.
+	///
+	pub fn new<ArtCan, BCan, B>(
+		inner: AP,
+	) -> Self
+		where
+			B: Builder<ArtCan, BCan, Err=Never>,
+			BCan: Can<AP::Builder>,
+			AP: Promise<Builder = B, BCan = BCan>,
+			Err: Debug + 'static,
+			ArtCan: CanSized<B::Artifact>,
+			ArtCan: Clone,
+			BCan: CanStrong,
+			BCan: CanSized<Self>,
+	{
+
+		FeigningBuilder {
+			inner,
+			_err: PhantomData,
+		}
+	}
+}
+
+impl<ArtCan, AP, B: ?Sized, BCan, Err> Builder<ArtCan, BCan> for FeigningBuilder<AP, Err>
+	where
+		B: Builder<ArtCan, BCan, Err=Never>,
+		BCan: Can<B>,
+		AP: Promise<Builder = B, BCan = BCan>,
+		Err: Debug + 'static,
+		ArtCan: CanSized<B::Artifact>,
+		ArtCan: Clone,
+		BCan: CanStrong,
+	{
+
+	type Artifact = B::Artifact;
+	type DynState = ();
+	type Err = Err;
+
+	fn build(&self, resolver: &mut Resolver<ArtCan, BCan, Self::DynState>)
+			-> Result<ArtCan::Bin, Self::Err> {
+
+		resolver.resolve(&self.inner).map_err(|n| n.into_any())
+	}
+
+	fn init_dyn_state(&self) -> Self::DynState {
+		// empty
+	}
+}
+
+
+
+
+
+
+
+
+/// Combinator methods wrapping a Promise into the builders of this module.
+///
+/// This trait is implemented for all `Promise`s (i.e. `Blueprint`s and
+/// `BlueprintUnsized`s) and is part of the [`prelude`]. Each method wraps the
+/// Promise into the respective Builder of this module and returns a new
+/// `Blueprint` of it, analogous to iterator adapters. This allows to chain
+/// these wrappers fluently.
+///
+/// Notice, the wrapper Builders impose further trait bounds on their inner
+/// Builder, which are only checked when the returned `Blueprint` is used with
+/// a `Cache`. See the documentation of the respective Builder.
+///
+/// # Examples
+///
+/// ```
+/// use daab::utils::ConstBuilder;
+/// use daab::rc::Cache;
+/// use daab::rc::Blueprint;
+/// use daab::prelude::*;
+///
+/// let inner = Blueprint::new(ConstBuilder::new(std::rc::Rc::new(21_u32)));
+/// let doubled = inner.mapped(|n: &u32| n * 2).forwarded();
+///
+/// let mut cache = Cache::new();
+///
+/// assert_eq!(42_u32, cache.get_cloned(&doubled).unpack());
+/// ```
+///
+/// [`prelude`]: ../prelude/index.html
+///
+pub trait BuilderExt: Promise + Sized {
+	/// Wraps this Promise into a `MappingBuilder` applying `f` to the
+	/// artifact.
+	///
+	fn mapped<F>(self, f: F) -> Blueprint<MappingBuilder<Self, F>, Self::BCan>
+			where
+				Self::BCan: CanSized<MappingBuilder<Self, F>> {
+
+		Blueprint::new(MappingBuilder {
+			inner: self,
+			f,
+		})
+	}
+
+	/// Wraps this Promise into a `ClonedBuilder`.
+	///
+	fn cloned(self) -> Blueprint<ClonedBuilder<Self>, Self::BCan>
+			where
+				Self::BCan: CanSized<ClonedBuilder<Self>> {
+
+		Blueprint::new(ClonedBuilder {
+			inner: self,
+		})
+	}
+
+	/// Wraps this Promise into a `ForwardingBuilder`.
+	///
+	fn forwarded(self) -> Blueprint<ForwardingBuilder<Self>, Self::BCan>
+			where
+				Self::BCan: CanSized<ForwardingBuilder<Self>> {
+
+		Blueprint::new(ForwardingBuilder {
+			inner: self,
+		})
+	}
+
+	/// Wraps this Promise into a `RedeemingBuilder` with the given default
+	/// value.
+	///
+	/// **Use with care**, see `RedeemingBuilder`.
+	///
+	fn redeeming<ArtBin>(self, default_value: Option<ArtBin>)
+			-> Blueprint<RedeemingBuilder<Self, ArtBin>, Self::BCan>
+			where
+				Self::BCan: CanSized<RedeemingBuilder<Self, ArtBin>> {
+
+		Blueprint::new(RedeemingBuilder {
+			inner: self,
+			default_value,
+		})
+	}
+
+	/// Wraps this Promise into a `FeigningBuilder` with the error type `Err`.
+	///
+	fn feigning<Err>(self) -> Blueprint<FeigningBuilder<Self, Err>, Self::BCan>
+			where
+				Self::BCan: CanSized<FeigningBuilder<Self, Err>> {
+
+		Blueprint::new(FeigningBuilder {
+			inner: self,
+			_err: PhantomData,
+		})
+	}
+
+	/// Wraps this Promise into a `LoggingBuilder` logging up to `capacity`
+	/// build times.
+	///
+	fn logged(self, capacity: usize) -> Blueprint<LoggingBuilder<Self>, Self::BCan>
+			where
+				Self::BCan: CanSized<LoggingBuilder<Self>> {
+
+		Blueprint::new(LoggingBuilder {
+			inner: self,
+			capacity,
+		})
+	}
+}
 
+impl<AP: Promise> BuilderExt for AP {}